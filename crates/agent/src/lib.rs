@@ -41,4 +41,80 @@ impl Agent {
         let msg = self.llm.chat(sys, &prompt).await?;
         Ok(msg.lines().next().unwrap_or(&msg).trim().to_string())
     }
+
+    /// Generates a short commit body paragraph explaining *why* the change
+    /// was made, from the goal and a diff excerpt. Used to fill `[commit]
+    /// llm_body` when the heuristic/template body would otherwise be empty.
+    pub async fn commit_body(&self, goal: &str, summary: &str, diff_head: &str) -> Result<String> {
+        let sys = "You write the body of a Conventional Commit message.\n\
+                   Output 1-3 short sentences of plain prose explaining WHY the\n\
+                   change was made, no markdown, no bullet list, no subject line.";
+        let prompt = format!(
+            "Goal: {goal}\nSummary: {summary}\nDiff (first lines):\n{diff_head}\n\
+             Write the commit body."
+        );
+        let body = self.llm.chat(sys, &prompt).await?;
+        Ok(body.trim().to_string())
+    }
+
+    /// Turns a structured symbol diff (see `devit explain-patch`) into a
+    /// short prose narrative a reviewer can skim before approving the patch.
+    pub async fn explain_patch(&self, symbol_summary: &str, diff_head: &str) -> Result<String> {
+        let sys = "You explain code patches to a reviewer in plain prose.\n\
+                   Output 2-4 short sentences, no markdown, no code blocks.";
+        let prompt = format!(
+            "Symbol changes:\n{symbol_summary}\nDiff (first lines):\n{diff_head}\n\
+             Summarize what this patch does and why it might matter to a reviewer."
+        );
+        self.llm.chat(sys, &prompt).await
+    }
+
+    /// One-paragraph summary of a directory's role from its file list — the
+    /// building block behind `devit context summarize`'s cached repo map.
+    pub async fn summarize_directory(&self, dir: &str, file_list: &str) -> Result<String> {
+        let sys = "You summarize a source directory for another engineer in ONE short paragraph.\n\
+                   No markdown, no code blocks, no file-by-file breakdown.";
+        let prompt = format!(
+            "Directory: {dir}\nFiles:\n{file_list}\n\
+             Describe what this directory is responsible for."
+        );
+        self.llm.chat(sys, &prompt).await
+    }
+
+    /// Embedding vector for `text`, used to rank context-index candidates
+    /// by semantic similarity (see `devit context search`).
+    pub async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        self.llm.embed(text).await
+    }
+
+    /// Advisory-only resolution proposal for a single `devit merge explain`
+    /// conflict hunk. Output is a single-line JSON object the caller parses
+    /// into `merge_assist::LlmResolution`; never applied without going
+    /// through the same plan file a human-authored resolution would.
+    pub async fn propose_merge_resolution(&self, ours: &str, theirs: &str) -> Result<String> {
+        let sys = "You resolve git merge conflicts for a senior engineer.\n\
+                   Output ONLY a single-line JSON object, no markdown, no code fences:\n\
+                   {\"resolution\": \"ours\"|\"theirs\"|\"keep_both\"|\"<merged text>\", \
+                   \"confidence\": <0.0-1.0>, \"rationale\": \"<1 sentence>\"}";
+        let prompt = format!("Ours:\n{ours}\nTheirs:\n{theirs}\nPropose the best resolution.");
+        self.llm.chat(sys, &prompt).await
+    }
+
+    /// Advisory-only triage for `devit`'s impacted-test gate: given the
+    /// diff that was just applied and the failing test output, produce a
+    /// root-cause hypothesis plus a suggested fix patch. The caller never
+    /// applies the suggested patch automatically.
+    pub async fn triage_test_failure(&self, diff_head: &str, test_output: &str) -> Result<String> {
+        let sys = "You triage failing tests for a senior engineer.\n\
+                   Output exactly two sections:\n\
+                   Root cause: <1-3 sentences>\n\
+                   Suggested fix:\n<unified diff>\n\
+                   This is advisory only -- never claim the fix was applied.";
+        let prompt = format!(
+            "Diff that was just applied (first lines):\n{diff_head}\n\
+             Failing test output:\n{test_output}\n\
+             Give a root-cause hypothesis and a suggested fix patch."
+        );
+        self.llm.chat(sys, &prompt).await
+    }
 }
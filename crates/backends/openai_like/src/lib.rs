@@ -10,6 +10,7 @@ use serde::{Deserialize, Serialize};
 #[async_trait]
 pub trait LlmBackend: Send + Sync {
     async fn chat(&self, sys: &str, user: &str) -> Result<String>;
+    async fn embed(&self, text: &str) -> Result<Vec<f32>>;
 }
 
 pub struct OpenAiLike {
@@ -54,6 +55,22 @@ struct ChoiceMsg {
     content: String,
 }
 
+#[derive(Serialize)]
+struct EmbedReq<'a> {
+    model: &'a str,
+    input: &'a str,
+}
+
+#[derive(Deserialize)]
+struct EmbedResp {
+    data: Vec<EmbedDatum>,
+}
+
+#[derive(Deserialize)]
+struct EmbedDatum {
+    embedding: Vec<f32>,
+}
+
 #[async_trait]
 impl LlmBackend for OpenAiLike {
     async fn chat(&self, sys: &str, user: &str) -> Result<String> {
@@ -85,4 +102,25 @@ impl LlmBackend for OpenAiLike {
             .map(|c| c.message.content.clone())
             .unwrap_or_default())
     }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let url = format!("{}/embeddings", self.cfg.backend.base_url);
+        let req = EmbedReq {
+            model: &self.cfg.backend.model,
+            input: text,
+        };
+
+        let mut rb = self.http.post(&url).json(&req);
+        if !self.cfg.backend.api_key.is_empty() {
+            rb = rb.bearer_auth(&self.cfg.backend.api_key);
+        }
+
+        let resp: EmbedResp = rb.send().await?.error_for_status()?.json().await?;
+        Ok(resp
+            .data
+            .into_iter()
+            .next()
+            .map(|d| d.embedding)
+            .unwrap_or_default())
+    }
 }
@@ -0,0 +1,40 @@
+// # -----------------------------
+// # crates/cli/src/autostash.rs
+// # -----------------------------
+// `devit apply --autostash`: stash a dirty worktree instead of requiring
+// `--force`, run the patch on a clean tree, then restore the stash.
+
+use devit_tools::git;
+
+/// Stashes the current worktree for the guard's lifetime (if it was dirty)
+/// and restores it on drop — covering every return path of
+/// `run_apply_pipeline`, success or error, the same way [`crate::progress::Progress`]
+/// clears its spinner line on drop.
+pub struct AutoStash {
+    stashed: bool,
+}
+
+impl AutoStash {
+    /// Stash uncommitted changes if any; a clean worktree is a no-op and
+    /// nothing is restored later.
+    pub fn engage() -> anyhow::Result<Self> {
+        let stashed = git::stash_push("devit-autostash")?;
+        Ok(Self { stashed })
+    }
+}
+
+impl Drop for AutoStash {
+    fn drop(&mut self) {
+        if !self.stashed {
+            return;
+        }
+        match git::stash_pop() {
+            Ok(true) => {}
+            Ok(false) => eprintln!(
+                "⚠️  autostash: conflit lors de la restauration, worktree remis à HEAD ; \
+                 tes changements précédents restent dans `git stash list` (résous avec `git stash pop`)."
+            ),
+            Err(e) => eprintln!("⚠️  autostash: échec de la restauration du stash: {e}"),
+        }
+    }
+}
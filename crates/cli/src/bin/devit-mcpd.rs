@@ -14,7 +14,7 @@ use rand::{rngs::OsRng, RngCore};
 use serde_json::{de::Deserializer, json, Value};
 use sha2::Sha256;
 use std::collections::{HashSet, VecDeque};
-use std::io::{self, BufRead, BufReader, Read, Write};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
 #[cfg(unix)]
 use std::os::unix::process::CommandExt;
 use std::path::Path;
@@ -74,7 +74,7 @@ struct Cli {
     #[arg(long = "cooldown-ms", default_value_t = 250)]
     cooldown_ms: u64,
 
-    /// Sandbox kind: bwrap|none (default: none)
+    /// Sandbox kind: bwrap|firejail|none (default: none)
     #[arg(long = "sandbox", default_value = "none")]
     sandbox: String,
     /// Network policy: off|full (default: off)
@@ -125,7 +125,8 @@ fn real_main() -> Result<()> {
         auto_yes: cli.yes,
     };
     let mut state = ServerState::new();
-    if cli.sandbox.to_ascii_lowercase() == "bwrap" && which("bwrap").is_none() {
+    let sandbox_kind = cli.sandbox.to_ascii_lowercase();
+    if (sandbox_kind == "bwrap" || sandbox_kind == "firejail") && which(&sandbox_kind).is_none() {
         // Do not exit; mark unavailable (will return structured error later)
         state.sandbox_unavailable = true;
     }
@@ -192,6 +193,8 @@ fn real_main() -> Result<()> {
                         "plugin.invoke",
                         "server.approve",
                         "server.context_head",
+                        "server.resources_list",
+                        "server.resources_read",
                         "server.health",
                         "server.stats",
                         "server.stats.reset",
@@ -215,6 +218,8 @@ fn real_main() -> Result<()> {
                     || name == "server.health"
                     || name == "server.stats"
                     || name == "server.context_head"
+                    || name == "server.resources_list"
+                    || name == "server.resources_read"
                     || name == "server.stats.reset"
                     || name == "server.approve";
                 if cli.dry_run && !is_server_tool {
@@ -488,6 +493,137 @@ fn real_main() -> Result<()> {
                             })
                         )?;
                     }
+                    "server.resources_list" => {
+                        let tool_key = "server.resources_list";
+                        state.bump_call(tool_key);
+                        let now = Instant::now();
+                        if let Err(e) = rl.allow(tool_key, now) {
+                            audit_pre(&audit, tool_key, "rate-limit");
+                            let v = match e {
+                                RateLimitErr::TooManyCalls { limit } => json!({
+                                    "type":"tool.error","payload":{
+                                        "name": tool_key,
+                                        "rate_limited": true,
+                                        "reason": "too_many_calls",
+                                        "limit_per_min": limit
+                                    }
+                                }),
+                                RateLimitErr::Cooldown { ms_left } => json!({
+                                    "type":"tool.error","payload":{
+                                        "name": tool_key,
+                                        "rate_limited": true,
+                                        "reason": "cooldown",
+                                        "cooldown_ms": ms_left
+                                    }
+                                }),
+                            };
+                            writeln!(stdout, "{}", v)?;
+                            continue;
+                        }
+                        let args_json = payload.get("args").cloned().unwrap_or(json!({}));
+                        let limit = args_json
+                            .get("limit")
+                            .and_then(|x| x.as_u64())
+                            .unwrap_or(50)
+                            .clamp(1, 1000) as usize;
+                        let ext_allow =
+                            args_json
+                                .get("ext_allow")
+                                .and_then(|x| x.as_array())
+                                .map(|arr| {
+                                    arr.iter()
+                                        .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                                        .collect::<Vec<String>>()
+                                });
+                        let index_path = args_json
+                            .get("index_path")
+                            .and_then(|x| x.as_str())
+                            .map(|s| std::path::Path::new(s).to_path_buf());
+                        let start = Instant::now();
+                        let v = resources_list_json(index_path.as_deref(), limit, ext_allow.as_deref());
+                        let dur = start.elapsed().as_millis();
+                        audit_done(&audit, tool_key, true, dur, None);
+                        state.bump_ok(tool_key);
+                        writeln!(
+                            stdout,
+                            "{}",
+                            json!({
+                                "type": "tool.result",
+                                "payload": {"ok": true, "name": tool_key, "resources": v}
+                            })
+                        )?;
+                    }
+                    "server.resources_read" => {
+                        let tool_key = "server.resources_read";
+                        state.bump_call(tool_key);
+                        let now = Instant::now();
+                        if let Err(e) = rl.allow(tool_key, now) {
+                            audit_pre(&audit, tool_key, "rate-limit");
+                            let v = match e {
+                                RateLimitErr::TooManyCalls { limit } => json!({
+                                    "type":"tool.error","payload":{
+                                        "name": tool_key,
+                                        "rate_limited": true,
+                                        "reason": "too_many_calls",
+                                        "limit_per_min": limit
+                                    }
+                                }),
+                                RateLimitErr::Cooldown { ms_left } => json!({
+                                    "type":"tool.error","payload":{
+                                        "name": tool_key,
+                                        "rate_limited": true,
+                                        "reason": "cooldown",
+                                        "cooldown_ms": ms_left
+                                    }
+                                }),
+                            };
+                            writeln!(stdout, "{}", v)?;
+                            continue;
+                        }
+                        let args_json = payload.get("args").cloned().unwrap_or(json!({}));
+                        let rel_path = match args_json.get("path").and_then(|x| x.as_str()) {
+                            Some(p) => p.to_string(),
+                            None => {
+                                state.bump_err(tool_key);
+                                writeln!(
+                                    stdout,
+                                    "{}",
+                                    json!({"type":"tool.error","payload":{
+                                        "name": tool_key,
+                                        "reason": "missing path"
+                                    }})
+                                )?;
+                                continue;
+                            }
+                        };
+                        let max_bytes = args_json
+                            .get("max_bytes")
+                            .and_then(|x| x.as_u64())
+                            .unwrap_or(65536)
+                            .clamp(1, 1_048_576) as usize;
+                        let index_path = args_json
+                            .get("index_path")
+                            .and_then(|x| x.as_str())
+                            .map(|s| std::path::Path::new(s).to_path_buf());
+                        let start = Instant::now();
+                        let v = resource_read_json(index_path.as_deref(), &rel_path, max_bytes);
+                        let dur = start.elapsed().as_millis();
+                        let ok = v["ok"].as_bool().unwrap_or(false);
+                        audit_done(&audit, tool_key, ok, dur, None);
+                        if ok {
+                            state.bump_ok(tool_key);
+                        } else {
+                            state.bump_err(tool_key);
+                        }
+                        writeln!(
+                            stdout,
+                            "{}",
+                            json!({
+                                "type": "tool.result",
+                                "payload": {"ok": ok, "name": tool_key, "resource": v}
+                            })
+                        )?;
+                    }
                     "plugin.invoke" => {
                         let tool_key = "plugin.invoke";
                         state.bump_call(tool_key);
@@ -853,12 +989,12 @@ fn real_main() -> Result<()> {
                         )?;
                     }
                     "devit.tool_list" => {
-                        if state.sandbox_unavailable && cli.sandbox.to_ascii_lowercase() == "bwrap"
-                        {
+                        if state.sandbox_unavailable {
+                            let kind = cli.sandbox.to_ascii_lowercase();
                             writeln!(
                                 stdout,
                                 "{}",
-                                json!({"type":"tool.error","payload":{"sandbox_unavailable": true, "reason":"bwrap_not_found"}})
+                                json!({"type":"tool.error","payload":{"sandbox_unavailable": true, "reason": format!("{kind}_not_found")}})
                             )?;
                             continue;
                         }
@@ -955,12 +1091,12 @@ fn real_main() -> Result<()> {
                         }
                     }
                     "devit.tool_call" => {
-                        if state.sandbox_unavailable && cli.sandbox.to_ascii_lowercase() == "bwrap"
-                        {
+                        if state.sandbox_unavailable {
+                            let kind = cli.sandbox.to_ascii_lowercase();
                             writeln!(
                                 stdout,
                                 "{}",
-                                json!({"type":"tool.error","payload":{"sandbox_unavailable": true, "reason":"bwrap_not_found"}})
+                                json!({"type":"tool.error","payload":{"sandbox_unavailable": true, "reason": format!("{kind}_not_found")}})
                             )?;
                             continue;
                         }
@@ -1297,6 +1433,8 @@ fn default_policies() -> Policies {
     m.insert("devit.tool_call".to_string(), "on_request".to_string());
     m.insert("server.policy".to_string(), "never".to_string());
     m.insert("server.context_head".to_string(), "never".to_string());
+    m.insert("server.resources_list".to_string(), "never".to_string());
+    m.insert("server.resources_read".to_string(), "never".to_string());
     m.insert("server.health".to_string(), "never".to_string());
     m.insert("server.stats".to_string(), "never".to_string());
     m.insert("server.stats.reset".to_string(), "on_request".to_string());
@@ -1597,6 +1735,8 @@ pub fn policy_dump_json(config_path: Option<&std::path::Path>) -> serde_json::Va
         "server.approve",
         "server.policy",
         "server.context_head",
+        "server.resources_list",
+        "server.resources_read",
         "server.health",
         "server.stats",
         "server.stats.reset",
@@ -2322,6 +2462,82 @@ fn stats_json(state: &ServerState) -> serde_json::Value {
     })
 }
 
+/// Byte offset of each record in a compact NDJSON index (see
+/// `devit context map --compact`).
+fn ndjson_offsets_path(index_path: &Path) -> PathBuf {
+    let mut s = index_path.as_os_str().to_os_string();
+    s.push(".offsets");
+    PathBuf::from(s)
+}
+
+/// Stream up to `limit` matching rows out of a compact NDJSON index by
+/// seeking straight to each record's offset instead of parsing the whole
+/// file — so a 100k+ file index answers "top 20" in a handful of reads.
+fn read_ndjson_head(
+    path: &Path,
+    limit: usize,
+    ext_allow: Option<&[String]>,
+) -> Result<(Vec<(i64, Value)>, usize), String> {
+    let offsets_raw = fs::read(ndjson_offsets_path(path)).map_err(|e| e.to_string())?;
+    let offsets: Vec<u64> = serde_json::from_slice(&offsets_raw).map_err(|e| e.to_string())?;
+    let total = offsets.len();
+    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut rows = Vec::new();
+    for &off in &offsets {
+        if rows.len() >= limit {
+            break;
+        }
+        file.seek(SeekFrom::Start(off)).map_err(|e| e.to_string())?;
+        let mut line = String::new();
+        BufReader::new(&file)
+            .read_line(&mut line)
+            .map_err(|e| e.to_string())?;
+        let v: Value = match serde_json::from_str(line.trim_end()) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        let p = v.get("path").and_then(|x| x.as_str()).unwrap_or("");
+        if let Some(exts) = ext_allow {
+            if !exts.iter().any(|e| p.ends_with(&format!(".{}", e))) {
+                continue;
+            }
+        }
+        let score = v.get("score").and_then(|x| x.as_i64()).unwrap_or(0);
+        rows.push((score, v));
+    }
+    Ok((rows, total))
+}
+
+/// Where `server.context_head`/`server.resources_list` read the index from
+/// when the caller doesn't pass an explicit `index_path`: the pretty JSON
+/// index if one was built, else the compact NDJSON one.
+fn default_index_path() -> PathBuf {
+    let json_path = PathBuf::from(".devit/index.json");
+    if json_path.exists() {
+        json_path
+    } else {
+        PathBuf::from(".devit/index.ndjson")
+    }
+}
+
+/// The `root` an index was generated from, read from its header (works for
+/// both the pretty JSON and compact NDJSON formats) — used by
+/// `server.resources_read` to confine reads to the indexed repository.
+fn index_root(index_path: &Path) -> Option<PathBuf> {
+    let root_str = if index_path.extension().and_then(|e| e.to_str()) == Some("ndjson") {
+        let mut file = fs::File::open(index_path).ok()?;
+        let mut header = String::new();
+        BufReader::new(&mut file).read_line(&mut header).ok()?;
+        let v: Value = serde_json::from_str(header.trim_end()).ok()?;
+        v.get("root")?.as_str()?.to_string()
+    } else {
+        let data = fs::read_to_string(index_path).ok()?;
+        let v: Value = serde_json::from_str(&data).ok()?;
+        v.get("root")?.as_str()?.to_string()
+    };
+    Some(PathBuf::from(root_str))
+}
+
 fn context_head_json(
     index_path_opt: Option<&std::path::Path>,
     limit: usize,
@@ -2330,7 +2546,43 @@ fn context_head_json(
     use serde_json::json;
     let path = index_path_opt
         .map(|p| p.to_path_buf())
-        .unwrap_or_else(|| PathBuf::from(".devit/index.json"));
+        .unwrap_or_else(default_index_path);
+
+    if path.extension().and_then(|e| e.to_str()) == Some("ndjson") {
+        return match read_ndjson_head(&path, limit, ext_allow) {
+            Ok((rows, total)) => {
+                let take = rows
+                    .into_iter()
+                    .map(|(_s, f)| {
+                        let mut m = serde_json::Map::new();
+                        m.insert("path".to_string(), f.get("path").cloned().unwrap_or(json!("")));
+                        m.insert("score".to_string(), f.get("score").cloned().unwrap_or(json!(0)));
+                        m.insert("lang".to_string(), f.get("lang").cloned().unwrap_or(json!(null)));
+                        m.insert("size".to_string(), f.get("size").cloned().unwrap_or(json!(null)));
+                        if let Some(sc) = f.get("symbols_count").cloned() {
+                            m.insert("symbols_count".to_string(), sc);
+                        }
+                        serde_json::Value::Object(m)
+                    })
+                    .collect::<Vec<_>>();
+                json!({
+                    "ok": true,
+                    "source": { "path": path.display().to_string(), "format": "ndjson" },
+                    "total": total,
+                    "limit": limit,
+                    "items": take
+                })
+            }
+            Err(e) => json!({
+                "ok": false,
+                "not_indexed": true,
+                "path": path.display().to_string(),
+                "read_error": e,
+                "hint": "run: devit context map . --compact",
+            }),
+        };
+    }
+
     let data = match fs::read_to_string(&path) {
         Ok(s) => s,
         Err(_) => {
@@ -2408,6 +2660,99 @@ fn context_head_json(
     })
 }
 
+/// List the context index as MCP resources: one entry per indexed file,
+/// with a `devit://file/<path>` URI clients pass to `server.resources_read`
+/// instead of learning the on-disk index format `server.context_head`
+/// exposes. Reuses [`context_head_json`] for the underlying index read.
+fn resources_list_json(
+    index_path_opt: Option<&Path>,
+    limit: usize,
+    ext_allow: Option<&[String]>,
+) -> Value {
+    let head = context_head_json(index_path_opt, limit, ext_allow);
+    if head["ok"].as_bool() != Some(true) {
+        return head;
+    }
+    let resources: Vec<Value> = head["items"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|f| {
+            let path = f.get("path").and_then(|x| x.as_str()).unwrap_or("");
+            json!({
+                "uri": format!("devit://file/{path}"),
+                "path": path,
+                "size": f.get("size").cloned().unwrap_or(json!(null)),
+                "lang": f.get("lang").cloned().unwrap_or(json!(null)),
+            })
+        })
+        .collect();
+    json!({
+        "ok": true,
+        "source": head["source"].clone(),
+        "total": head["total"].clone(),
+        "resources": resources
+    })
+}
+
+/// Resolve `rel_path` against `root` and confirm the result stays inside
+/// `root` (rejecting `..`/absolute escapes and symlinks pointing outside
+/// it) before it's ever opened — the path-confinement half of
+/// `server.resources_read`.
+fn confine_to_root(root: &Path, rel_path: &str) -> Result<PathBuf, String> {
+    if Path::new(rel_path).is_absolute() {
+        return Err("path must be relative".to_string());
+    }
+    let root_abs = fs::canonicalize(root).map_err(|e| e.to_string())?;
+    let candidate = root_abs.join(rel_path);
+    let resolved = fs::canonicalize(&candidate).map_err(|e| e.to_string())?;
+    if !resolved.starts_with(&root_abs) {
+        return Err("path escapes index root".to_string());
+    }
+    Ok(resolved)
+}
+
+/// `server.resources_read`: the content of one indexed file, capped to
+/// `max_bytes` and confined under the index's recorded `root` (see
+/// [`confine_to_root`]) so a malicious `path` can't read outside the repo.
+fn resource_read_json(index_path_opt: Option<&Path>, rel_path: &str, max_bytes: usize) -> Value {
+    let index_path = index_path_opt
+        .map(|p| p.to_path_buf())
+        .unwrap_or_else(default_index_path);
+    let root = match index_root(&index_path) {
+        Some(r) => r,
+        None => {
+            return json!({
+                "ok": false,
+                "not_indexed": true,
+                "path": index_path.display().to_string(),
+                "hint": "run: devit context map .",
+            })
+        }
+    };
+    let resolved = match confine_to_root(&root, rel_path) {
+        Ok(p) => p,
+        Err(e) => return json!({"ok": false, "denied": true, "reason": e, "path": rel_path}),
+    };
+    let bytes = match fs::read(&resolved) {
+        Ok(b) => b,
+        Err(e) => {
+            return json!({"ok": false, "read_error": e.to_string(), "path": rel_path})
+        }
+    };
+    let truncated = bytes.len() > max_bytes;
+    let capped = &bytes[..bytes.len().min(max_bytes)];
+    json!({
+        "ok": true,
+        "uri": format!("devit://file/{rel_path}"),
+        "path": rel_path,
+        "size": bytes.len(),
+        "truncated": truncated,
+        "content": String::from_utf8_lossy(capped),
+    })
+}
+
 #[cfg(test)]
 mod ctx_tests {
     use super::*;
@@ -2433,6 +2778,100 @@ mod ctx_tests {
         assert_eq!(v["items"].as_array().unwrap().len(), 1);
         assert_eq!(v["items"][0]["path"].as_str().unwrap(), "src/lib.rs");
     }
+
+    #[test]
+    fn context_head_reads_compact_ndjson_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let idx = dir.path().join("index.ndjson");
+        let records = [
+            r#"{"root": ".", "generated_at":"2025-09-14T00:00:00Z","skipped":{"too_large":0,"binary":0},"count":2}"#,
+            r#"{"path":"src/lib.rs","size":100,"lang":"rust","score":90,"symbols_count":5}"#,
+            r#"{"path":"README.md","size":200,"lang":"text","score":10}"#,
+        ];
+        let mut buf = Vec::new();
+        let mut offsets = Vec::new();
+        for (i, rec) in records.iter().enumerate() {
+            if i > 0 {
+                offsets.push(buf.len() as u64);
+            }
+            buf.extend_from_slice(rec.as_bytes());
+            buf.push(b'\n');
+        }
+        fs::write(&idx, &buf).unwrap();
+        fs::write(ndjson_offsets_path(&idx), serde_json::to_vec(&offsets).unwrap()).unwrap();
+
+        let v = context_head_json(Some(&idx), 1, None);
+        assert!(v["ok"].as_bool().unwrap_or(false));
+        assert_eq!(v["total"].as_u64().unwrap(), 2);
+        assert_eq!(v["items"].as_array().unwrap().len(), 1);
+        assert_eq!(v["items"][0]["path"].as_str().unwrap(), "src/lib.rs");
+    }
+
+    #[test]
+    fn resources_list_reshapes_index_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let devit_dir = dir.path().join(".devit");
+        fs::create_dir_all(&devit_dir).unwrap();
+        let idx = devit_dir.join("index.json");
+        let mut f = fs::File::create(&idx).unwrap();
+        write!(
+            f,
+            "{}",
+            r#"{"root": ".", "generated_at":"2025-09-14T00:00:00Z","files":[
+            {"path":"src/lib.rs","size":100,"lang":"rust","score":90,"symbols_count":5}
+        ]}"#
+        )
+        .unwrap();
+        let v = resources_list_json(Some(&idx), 10, None);
+        assert!(v["ok"].as_bool().unwrap_or(false));
+        let resources = v["resources"].as_array().unwrap();
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0]["uri"].as_str().unwrap(), "devit://file/src/lib.rs");
+        assert_eq!(resources[0]["path"].as_str().unwrap(), "src/lib.rs");
+        assert_eq!(resources[0]["lang"].as_str().unwrap(), "rust");
+    }
+
+    #[test]
+    fn resource_read_returns_file_content() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("hello.txt"), b"hi there").unwrap();
+        let devit_dir = dir.path().join(".devit");
+        fs::create_dir_all(&devit_dir).unwrap();
+        let idx = devit_dir.join("index.json");
+        let root = fs::canonicalize(dir.path()).unwrap();
+        fs::write(
+            &idx,
+            format!(
+                r#"{{"root": {:?}, "generated_at":"2025-09-14T00:00:00Z","files":[]}}"#,
+                root.display()
+            ),
+        )
+        .unwrap();
+        let v = resource_read_json(Some(&idx), "hello.txt", 65536);
+        assert!(v["ok"].as_bool().unwrap_or(false));
+        assert_eq!(v["content"].as_str().unwrap(), "hi there");
+        assert_eq!(v["truncated"].as_bool().unwrap(), false);
+    }
+
+    #[test]
+    fn resource_read_denies_path_traversal() {
+        let dir = tempfile::tempdir().unwrap();
+        let devit_dir = dir.path().join(".devit");
+        fs::create_dir_all(&devit_dir).unwrap();
+        let idx = devit_dir.join("index.json");
+        let root = fs::canonicalize(dir.path()).unwrap();
+        fs::write(
+            &idx,
+            format!(
+                r#"{{"root": {:?}, "generated_at":"2025-09-14T00:00:00Z","files":[]}}"#,
+                root.display()
+            ),
+        )
+        .unwrap();
+        let v = resource_read_json(Some(&idx), "../../etc/passwd", 65536);
+        assert!(!v["ok"].as_bool().unwrap_or(true));
+        assert!(v["denied"].as_bool().unwrap_or(false));
+    }
 }
 
 #[cfg(test)]
@@ -2463,8 +2902,30 @@ profile = "std"
         assert_eq!(v["tools"]["server.stats.reset"].as_str().unwrap(), "never");
     }
 }
+/// Build a `firejail` invocation of `bin` with a private, per-call profile:
+/// no network (when `net_off`), and the current workspace whitelisted so it
+/// stays writable inside firejail's otherwise-isolated filesystem.
+fn firejail_command(bin: &Path, net_off: bool, tool_args: &[&str]) -> Command {
+    let mut c = Command::new("firejail");
+    c.arg("--quiet").arg("--noprofile");
+    if net_off {
+        c.arg("--net=none");
+    }
+    if let Ok(cwd) = std::env::current_dir() {
+        c.arg(format!("--whitelist={}", cwd.display()));
+    }
+    c.arg("--").arg(bin.as_os_str()).args(tool_args);
+    c
+}
+
 fn run_devit_list_sandboxed(bin: &PathBuf, timeout: Duration, cli: &Cli) -> Result<Value> {
-    let mut cmd = if cli.sandbox.to_ascii_lowercase() == "bwrap" {
+    let mut cmd = if cli.sandbox.eq_ignore_ascii_case("firejail") {
+        firejail_command(
+            bin,
+            cli.net.to_ascii_lowercase() == "off",
+            &["tool", "list", "--json-only"],
+        )
+    } else if cli.sandbox.to_ascii_lowercase() == "bwrap" {
         let mut c = Command::new("bwrap");
         c.arg("--unshare-user");
         if cli.net.to_ascii_lowercase() == "off" {
@@ -2587,7 +3048,13 @@ fn run_devit_call_sandboxed(
     timeout: Duration,
     cli: &Cli,
 ) -> Result<Value> {
-    let mut cmd = if cli.sandbox.to_ascii_lowercase() == "bwrap" {
+    let mut cmd = if cli.sandbox.eq_ignore_ascii_case("firejail") {
+        firejail_command(
+            bin,
+            cli.net.to_ascii_lowercase() == "off",
+            &["tool", "call", "-", "--json-only"],
+        )
+    } else if cli.sandbox.to_ascii_lowercase() == "bwrap" {
         let mut c = Command::new("bwrap");
         c.arg("--unshare-user");
         if cli.net.to_ascii_lowercase() == "off" {
@@ -0,0 +1,118 @@
+// # -----------------------------
+// # crates/cli/src/blame.rs
+// # -----------------------------
+// `devit context blame <file>`: per-region authorship and recency, derived
+// from `git blame`, so a goal that mentions a file can be answered with
+// respect for whoever last touched the code (and why), instead of just the
+// file's current contents.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::process::Command;
+
+#[derive(Debug, Clone, Default)]
+struct CommitMeta {
+    author: String,
+    author_time: i64,
+    summary: String,
+}
+
+/// A contiguous run of lines last touched by the same commit.
+#[derive(Serialize, Debug, Clone)]
+pub struct BlameRegion {
+    pub start_line: usize,
+    pub end_line: usize,
+    pub sha: String,
+    pub author: String,
+    pub date: String,
+    pub summary: String,
+}
+
+/// Run `git blame` on `path` and collapse consecutive same-commit lines
+/// into regions, most-recently-changed first -- a prompt only needs the
+/// handful of regions that explain "who touched this and why", not every
+/// line.
+pub fn blame_regions(path: &str) -> Result<Vec<BlameRegion>, String> {
+    let out = Command::new("git")
+        .args(["blame", "--line-porcelain", "--", path])
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !out.status.success() {
+        return Err(String::from_utf8_lossy(&out.stderr).to_string());
+    }
+    let text = String::from_utf8_lossy(&out.stdout);
+
+    let mut commits: HashMap<String, CommitMeta> = HashMap::new();
+    let mut regions: Vec<(String, usize, usize)> = Vec::new(); // sha, start, end
+    let mut cur_sha = String::new();
+    let mut cur_line = 0usize;
+
+    for line in text.lines() {
+        if let Some(rest) = line.strip_prefix("author ") {
+            commits.entry(cur_sha.clone()).or_default().author = rest.to_string();
+        } else if let Some(rest) = line.strip_prefix("author-time ") {
+            commits.entry(cur_sha.clone()).or_default().author_time =
+                rest.trim().parse().unwrap_or(0);
+        } else if let Some(rest) = line.strip_prefix("summary ") {
+            commits.entry(cur_sha.clone()).or_default().summary = rest.to_string();
+        } else if let Some(rest) = line.strip_prefix('\t') {
+            let _ = rest; // line content itself, not needed here
+            match regions.last_mut() {
+                Some((sha, _start, end)) if *sha == cur_sha => *end = cur_line,
+                _ => regions.push((cur_sha.clone(), cur_line, cur_line)),
+            }
+        } else {
+            let mut parts = line.split_whitespace();
+            let sha = parts.next().unwrap_or("");
+            let looks_like_sha = sha.len() >= 4 && sha.chars().all(|c| c.is_ascii_hexdigit());
+            if looks_like_sha {
+                if let Some(final_line) = parts.nth(1) {
+                    if let Ok(n) = final_line.parse::<usize>() {
+                        cur_sha = sha.to_string();
+                        cur_line = n;
+                        commits.entry(cur_sha.clone()).or_default();
+                    }
+                }
+            }
+        }
+    }
+
+    let mut out: Vec<BlameRegion> = regions
+        .into_iter()
+        .map(|(sha, start_line, end_line)| {
+            let meta = commits.get(&sha).cloned().unwrap_or_default();
+            BlameRegion {
+                start_line,
+                end_line,
+                sha: sha.chars().take(12).collect(),
+                author: meta.author,
+                date: format_author_time(meta.author_time),
+                summary: meta.summary,
+            }
+        })
+        .collect();
+    out.sort_by_key(|r| std::cmp::Reverse(r.start_line));
+    Ok(out)
+}
+
+fn format_author_time(secs: i64) -> String {
+    chrono::DateTime::from_timestamp(secs, 0)
+        .map(|dt| dt.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+        .unwrap_or_default()
+}
+
+/// Render the most recently touched regions as a short block a prompt can
+/// paste alongside a file's contents, most recent commit first.
+pub fn recent_summary(path: &str, max_regions: usize) -> Result<String, String> {
+    let mut regions = blame_regions(path)?;
+    regions.sort_by(|a, b| b.date.cmp(&a.date).then(b.start_line.cmp(&a.start_line)));
+    regions.truncate(max_regions);
+    let mut out = String::new();
+    for r in &regions {
+        out.push_str(&format!(
+            "L{}-{} by {} ({}, {}): {}\n",
+            r.start_line, r.end_line, r.author, r.sha, r.date, r.summary
+        ));
+    }
+    Ok(out)
+}
@@ -0,0 +1,206 @@
+// # -----------------------------
+// # crates/cli/src/checkpoint.rs
+// # -----------------------------
+// Safety-net snapshots taken by `devit run` before it touches the worktree,
+// restored by `devit rollback`.
+
+use anyhow::{Context, Result};
+use devit_tools::git;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+const DIR: &str = ".devit/checkpoints";
+const LATEST: &str = ".devit/checkpoints/latest.json";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub id: String,
+    pub base_sha: String,
+    /// `git stash create` object covering any pre-existing dirty tracked
+    /// changes at checkpoint time (only possible when `run --force` starts
+    /// from a dirty worktree); `None` when the tree was clean.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dirty_stash: Option<String>,
+    /// Untracked files present at checkpoint time, so rollback can tell
+    /// them apart from ones the patch went on to create.
+    pub pre_untracked: Vec<String>,
+}
+
+fn untracked_files() -> Result<Vec<String>> {
+    let out = std::process::Command::new("git")
+        .args(["ls-files", "--others", "--exclude-standard"])
+        .output()?;
+    Ok(String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .map(str::to_string)
+        .collect())
+}
+
+/// Snapshot the worktree before `devit run` applies a patch.
+pub fn create() -> Result<Checkpoint> {
+    fs::create_dir_all(DIR)?;
+    let base_sha =
+        git::head_short().ok_or_else(|| anyhow::anyhow!("impossible de résoudre HEAD"))?;
+    let dirty_stash = if git::is_worktree_clean() {
+        None
+    } else {
+        stash_create_object()?
+    };
+    let pre_untracked = untracked_files()?;
+    let id = format!("{base_sha}-{}", pre_untracked.len());
+    let cp = Checkpoint {
+        id: id.clone(),
+        base_sha,
+        dirty_stash,
+        pre_untracked,
+    };
+    let json = serde_json::to_vec_pretty(&cp)?;
+    fs::write(format!("{DIR}/{id}.json"), &json)?;
+    fs::write(LATEST, &json)?;
+    Ok(cp)
+}
+
+/// The most recently recorded checkpoint, if any.
+pub fn latest() -> Result<Checkpoint> {
+    let s = fs::read_to_string(LATEST).context("aucun checkpoint enregistré")?;
+    serde_json::from_str(&s).context("checkpoint illisible")
+}
+
+/// Restore the worktree to `cp`, deleting any untracked files created since.
+pub fn restore(cp: &Checkpoint) -> Result<()> {
+    for f in untracked_files()? {
+        if !cp.pre_untracked.contains(&f) {
+            let p = Path::new(&f);
+            if p.exists() {
+                fs::remove_file(p).with_context(|| format!("suppression de {f}"))?;
+            }
+        }
+    }
+    let status = std::process::Command::new("git")
+        .args(["reset", "--hard", &cp.base_sha])
+        .status()?;
+    if !status.success() {
+        anyhow::bail!("git reset --hard {} a échoué", cp.base_sha);
+    }
+    if let Some(stash) = &cp.dirty_stash {
+        let status = std::process::Command::new("git")
+            .args(["stash", "apply", stash])
+            .status()?;
+        if !status.success() {
+            anyhow::bail!("git stash apply {} a échoué", stash);
+        }
+    }
+    Ok(())
+}
+
+fn stash_create_object() -> Result<Option<String>> {
+    let out = std::process::Command::new("git")
+        .args(["stash", "create", "devit-checkpoint"])
+        .output()?;
+    if !out.status.success() {
+        anyhow::bail!(
+            "git stash create a échoué:\n{}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+    }
+    let sha = String::from_utf8_lossy(&out.stdout).trim().to_string();
+    Ok(if sha.is_empty() { None } else { Some(sha) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CWD_LOCK;
+
+    /// Restores the previous working directory on drop, even on panic, so
+    /// one failing assertion can't leave later tests running from a deleted
+    /// tempdir.
+    struct CwdGuard(std::path::PathBuf);
+
+    impl CwdGuard {
+        fn enter(dir: &Path) -> Self {
+            let prev = std::env::current_dir().unwrap();
+            std::env::set_current_dir(dir).unwrap();
+            Self(prev)
+        }
+    }
+
+    impl Drop for CwdGuard {
+        fn drop(&mut self) {
+            let _ = std::env::set_current_dir(&self.0);
+        }
+    }
+
+    fn init_repo() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let run = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(args)
+                .current_dir(dir.path())
+                .status()
+                .unwrap();
+            assert!(status.success());
+        };
+        run(&["init", "-q"]);
+        run(&["config", "user.email", "a@a.com"]);
+        run(&["config", "user.name", "a"]);
+        fs::write(dir.path().join("f.txt"), "base\n").unwrap();
+        run(&["add", "-A"]);
+        run(&["commit", "-q", "-m", "base"]);
+        dir
+    }
+
+    #[test]
+    fn create_and_restore_round_trip() {
+        let _lock = CWD_LOCK.lock().unwrap();
+        let dir = init_repo();
+        let _cwd = CwdGuard::enter(dir.path());
+
+        let cp = create().unwrap();
+        fs::write(dir.path().join("f.txt"), "changed\n").unwrap();
+        fs::write(dir.path().join("new.txt"), "new\n").unwrap();
+
+        restore(&cp).unwrap();
+
+        assert_eq!(fs::read_to_string(dir.path().join("f.txt")).unwrap(), "base\n");
+        assert!(!dir.path().join("new.txt").exists());
+    }
+
+    #[test]
+    fn latest_reads_back_most_recent_checkpoint() {
+        let _lock = CWD_LOCK.lock().unwrap();
+        let dir = init_repo();
+        let _cwd = CwdGuard::enter(dir.path());
+
+        let cp = create().unwrap();
+        let got = latest().unwrap();
+        assert_eq!(got.id, cp.id);
+        assert_eq!(got.base_sha, cp.base_sha);
+    }
+
+    #[test]
+    fn restore_preserves_untracked_files_that_predate_checkpoint() {
+        let _lock = CWD_LOCK.lock().unwrap();
+        let dir = init_repo();
+        fs::write(dir.path().join("pre.txt"), "pre\n").unwrap();
+        let _cwd = CwdGuard::enter(dir.path());
+
+        let cp = create().unwrap();
+        fs::write(dir.path().join("post.txt"), "post\n").unwrap();
+
+        restore(&cp).unwrap();
+
+        assert!(dir.path().join("pre.txt").exists());
+        assert!(!dir.path().join("post.txt").exists());
+    }
+
+    #[test]
+    fn latest_errors_when_no_checkpoint_recorded() {
+        let _lock = CWD_LOCK.lock().unwrap();
+        let dir = init_repo();
+        let _cwd = CwdGuard::enter(dir.path());
+
+        assert!(latest().is_err());
+    }
+}
@@ -0,0 +1,117 @@
+// # -----------------------------
+// # crates/cli/src/clean.rs
+// # -----------------------------
+// Backing implementation for `devit clean`: prune stale `.devit` artifacts
+// (old reports and rotated journals, the context cache, checkpoint/merge
+// backup sessions) with a dry-run preview and per-category size reporting.
+
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Serialize, Default)]
+pub struct CleanCategory {
+    pub files: Vec<String>,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct CleanReport {
+    pub dry_run: bool,
+    pub reports: CleanCategory,
+    pub cache: CleanCategory,
+    pub sessions: CleanCategory,
+    pub bytes_total: u64,
+}
+
+/// Every file directly under `dir` (non-recursive, matching how `.devit`
+/// subdirectories are actually populated) plus its size.
+fn files_in(dir: &str) -> CleanCategory {
+    let mut cat = CleanCategory::default();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return cat;
+    };
+    for entry in entries.flatten() {
+        let Ok(md) = entry.metadata() else { continue };
+        if md.is_file() {
+            cat.bytes += md.len();
+            cat.files.push(entry.path().display().to_string());
+        }
+    }
+    cat.files.sort();
+    cat
+}
+
+fn reports_category() -> CleanCategory {
+    let mut cat = files_in(".devit/reports");
+    // Rotated journal backups (e.g. `journal.jsonl.1`); the live
+    // `.devit/journal.jsonl` itself is never touched.
+    if let Ok(entries) = fs::read_dir(".devit") {
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with("journal.jsonl.") {
+                if let Ok(md) = entry.metadata() {
+                    cat.bytes += md.len();
+                    cat.files.push(entry.path().display().to_string());
+                }
+            }
+        }
+    }
+    cat.files.sort();
+    cat
+}
+
+fn cache_category() -> CleanCategory {
+    let mut cat = CleanCategory::default();
+    for candidate in [".devit/index.json", ".devit/index.json.tmp"] {
+        if let Ok(md) = fs::metadata(candidate) {
+            if md.is_file() {
+                cat.bytes += md.len();
+                cat.files.push(candidate.to_string());
+            }
+        }
+    }
+    cat
+}
+
+fn sessions_category() -> CleanCategory {
+    let mut cat = files_in(".devit/checkpoints");
+    let backups = files_in(".devit/merge_backups");
+    cat.bytes += backups.bytes;
+    cat.files.extend(backups.files);
+    cat.files.sort();
+    cat
+}
+
+fn remove_all(cat: &CleanCategory) {
+    for f in &cat.files {
+        let _ = fs::remove_file(Path::new(f));
+    }
+}
+
+/// Build the report for the requested categories, deleting matched files
+/// unless `dry_run` is set.
+pub fn clean(reports: bool, cache: bool, sessions: bool, dry_run: bool) -> CleanReport {
+    let mut report = CleanReport {
+        dry_run,
+        ..Default::default()
+    };
+    if reports {
+        report.reports = reports_category();
+    }
+    if cache {
+        report.cache = cache_category();
+    }
+    if sessions {
+        report.sessions = sessions_category();
+    }
+    report.bytes_total = report.reports.bytes + report.cache.bytes + report.sessions.bytes;
+
+    if !dry_run {
+        remove_all(&report.reports);
+        remove_all(&report.cache);
+        remove_all(&report.sessions);
+    }
+    report
+}
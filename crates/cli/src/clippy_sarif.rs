@@ -0,0 +1,155 @@
+// # -----------------------------
+// # crates/cli/src/clippy_sarif.rs
+// # -----------------------------
+// Converts `cargo clippy --message-format=json` diagnostics into SARIF
+// 2.1.0, so the Rust path of `quality gate` doesn't depend on the external
+// `clippy-sarif`/`sarif-fmt` tools to populate `.devit/reports/clippy.sarif.json`.
+
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+struct ClippyResult {
+    rule_id: String,
+    level: String,
+    message: String,
+    file: String,
+    line: u32,
+    suggested_replacement: Option<String>,
+}
+
+/// Run `cargo clippy --workspace --all-targets --message-format=json` and
+/// write the diagnostics as SARIF to `out`. Returns the number of results
+/// written; clippy's own exit code (non-zero on any warning/error) is
+/// ignored since the quality gate reads the SARIF, not the process status.
+pub fn run(out: &Path) -> Result<u32> {
+    let output = Command::new("cargo")
+        .args([
+            "clippy",
+            "--workspace",
+            "--all-targets",
+            "--message-format=json",
+        ])
+        .output()
+        .context("run cargo clippy --message-format=json")?;
+    let results = parse(&String::from_utf8_lossy(&output.stdout));
+    let count = results.len() as u32;
+    let sarif = to_sarif(&results);
+    if let Some(dir) = out.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(out, serde_json::to_vec_pretty(&sarif)?)?;
+    Ok(count)
+}
+
+fn parse(stdout: &str) -> Vec<ClippyResult> {
+    let mut out = Vec::new();
+    for line in stdout.lines() {
+        let Ok(v) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        if v.get("reason").and_then(|r| r.as_str()) != Some("compiler-message") {
+            continue;
+        }
+        let Some(msg) = v.get("message") else {
+            continue;
+        };
+        let rule_id = msg
+            .get("code")
+            .and_then(|c| c.get("code"))
+            .and_then(|c| c.as_str())
+            .unwrap_or("")
+            .to_string();
+        if !rule_id.starts_with("clippy::") {
+            continue;
+        }
+        let level = match msg.get("level").and_then(|l| l.as_str()) {
+            Some("error") => "error",
+            _ => "warning",
+        }
+        .to_string();
+        let message = msg
+            .get("message")
+            .and_then(|m| m.as_str())
+            .unwrap_or("")
+            .to_string();
+        let spans = msg
+            .get("spans")
+            .and_then(|s| s.as_array())
+            .cloned()
+            .unwrap_or_default();
+        let Some(primary) = spans
+            .iter()
+            .find(|s| s.get("is_primary").and_then(|p| p.as_bool()) == Some(true))
+        else {
+            continue;
+        };
+        let file = primary
+            .get("file_name")
+            .and_then(|f| f.as_str())
+            .unwrap_or("unknown")
+            .to_string();
+        let line = primary
+            .get("line_start")
+            .and_then(|l| l.as_u64())
+            .unwrap_or(1) as u32;
+        let suggested_replacement = primary
+            .get("suggested_replacement")
+            .and_then(|s| s.as_str())
+            .map(|s| s.to_string());
+        out.push(ClippyResult {
+            rule_id,
+            level,
+            message,
+            file,
+            line,
+            suggested_replacement,
+        });
+    }
+    out
+}
+
+fn to_sarif(results: &[ClippyResult]) -> serde_json::Value {
+    let mut seen = std::collections::HashSet::new();
+    let rules: Vec<serde_json::Value> = results
+        .iter()
+        .filter(|r| seen.insert(r.rule_id.clone()))
+        .map(|r| serde_json::json!({ "id": r.rule_id }))
+        .collect();
+    let sarif_results: Vec<serde_json::Value> = results
+        .iter()
+        .map(|r| {
+            let mut result = serde_json::json!({
+                "ruleId": r.rule_id,
+                "level": r.level,
+                "message": { "text": r.message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": r.file },
+                        "region": { "startLine": r.line },
+                    }
+                }],
+            });
+            if let Some(fix) = &r.suggested_replacement {
+                result["fixes"] = serde_json::json!([{
+                    "description": { "text": "clippy suggested fix" },
+                    "artifactChanges": [{
+                        "artifactLocation": { "uri": r.file },
+                        "replacements": [{
+                            "deletedRegion": { "startLine": r.line },
+                            "insertedContent": { "text": fix },
+                        }]
+                    }]
+                }]);
+            }
+            result
+        })
+        .collect();
+    serde_json::json!({
+        "version": "2.1.0",
+        "runs": [{
+            "tool": { "driver": { "name": "clippy", "rules": rules } },
+            "results": sarif_results,
+        }]
+    })
+}
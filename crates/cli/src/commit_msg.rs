@@ -64,6 +64,126 @@ fn to_lines(out: std::io::Result<std::process::Output>) -> Vec<String> {
 }
 
 fn infer_scope(files: &[String]) -> String {
+    if let Some(s) = infer_scope_workspace(files) {
+        return s;
+    }
+    infer_scope_raw(files)
+}
+
+/// Public single-file entry point into [`infer_scope`], used by
+/// `patch_filter::split_by_scope` to group a patch's files by the same
+/// scope `devit commit-msg` would infer for them.
+pub(crate) fn scope_for_path(path: &str) -> String {
+    infer_scope(&[path.to_string()])
+}
+
+/// Cargo/npm package name owning every file in `files`, preferred over the
+/// raw path token `infer_scope_raw` would otherwise produce. `None` when
+/// there's no workspace manifest, or the files span more than one member.
+fn infer_scope_workspace(files: &[String]) -> Option<String> {
+    if files.is_empty() {
+        return None;
+    }
+    let members = workspace_members();
+    if members.is_empty() {
+        return None;
+    }
+    let mut name: Option<&str> = None;
+    for f in files {
+        let owner = members
+            .iter()
+            .filter(|(dir, _)| f.starts_with(&format!("{dir}/")))
+            .max_by_key(|(dir, _)| dir.len())?;
+        match name {
+            None => name = Some(&owner.1),
+            Some(n) if n == owner.1 => {}
+            Some(_) => return None,
+        }
+    }
+    name.map(str::to_string)
+}
+
+/// `(directory, package name)` for every Cargo and npm workspace member,
+/// read straight off disk (no caching -- this only runs once per commit).
+fn workspace_members() -> Vec<(String, String)> {
+    let mut out = cargo_workspace_members();
+    out.extend(npm_workspace_members());
+    out
+}
+
+fn cargo_workspace_members() -> Vec<(String, String)> {
+    let Ok(text) = std::fs::read_to_string("Cargo.toml") else {
+        return Vec::new();
+    };
+    let Ok(root) = toml::from_str::<toml::Value>(&text) else {
+        return Vec::new();
+    };
+    let patterns: Vec<String> = root
+        .get("workspace")
+        .and_then(|w| w.get("members"))
+        .and_then(|m| m.as_array())
+        .map(|a| a.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default();
+    expand_globs(&patterns)
+        .into_iter()
+        .filter_map(|dir| {
+            let text = std::fs::read_to_string(Path::new(&dir).join("Cargo.toml")).ok()?;
+            let manifest: toml::Value = toml::from_str(&text).ok()?;
+            let name = manifest
+                .get("package")
+                .and_then(|p| p.get("name"))
+                .and_then(|n| n.as_str())?;
+            Some((dir, name.to_string()))
+        })
+        .collect()
+}
+
+fn npm_workspace_members() -> Vec<(String, String)> {
+    let Ok(text) = std::fs::read_to_string("package.json") else {
+        return Vec::new();
+    };
+    let Ok(root) = serde_json::from_str::<serde_json::Value>(&text) else {
+        return Vec::new();
+    };
+    let patterns: Vec<String> = root
+        .get("workspaces")
+        .and_then(|w| w.as_array())
+        .map(|a| a.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default();
+    expand_globs(&patterns)
+        .into_iter()
+        .filter_map(|dir| {
+            let text = std::fs::read_to_string(Path::new(&dir).join("package.json")).ok()?;
+            let manifest: serde_json::Value = serde_json::from_str(&text).ok()?;
+            let name = manifest.get("name").and_then(|n| n.as_str())?;
+            Some((dir, name.to_string()))
+        })
+        .collect()
+}
+
+/// Expand the trailing-`/*` glob style used by Cargo/npm workspace member
+/// lists (e.g. `crates/*`) into real subdirectories; literal entries (no
+/// `*`) pass through unchanged.
+fn expand_globs(patterns: &[String]) -> Vec<String> {
+    let mut out = Vec::new();
+    for p in patterns {
+        match p.strip_suffix("/*") {
+            Some(prefix) => {
+                if let Ok(entries) = std::fs::read_dir(prefix) {
+                    for e in entries.flatten() {
+                        if e.path().is_dir() {
+                            out.push(format!("{prefix}/{}", e.file_name().to_string_lossy()));
+                        }
+                    }
+                }
+            }
+            None => out.push(p.clone()),
+        }
+    }
+    out
+}
+
+fn infer_scope_raw(files: &[String]) -> String {
     // deepest common directory name
     let parts: Vec<Vec<&str>> = files.iter().map(|f| f.split('/').collect()).collect();
     if parts.is_empty() {
@@ -152,6 +272,8 @@ pub struct MsgInput {
     pub max_subject: usize,
     pub template_body: Option<String>,
     pub scopes_alias: Option<HashMap<String, String>>, // optional alias mapping
+    /// `[commit] issue_prefixes` -- see [`extract_issue_ref`].
+    pub issue_prefixes: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -161,6 +283,108 @@ pub struct MsgOutput {
     pub subject: String,
     pub body: String,
     pub footers: Vec<String>,
+    /// Set when a staged Rust diff removes/renames a `pub` top-level item
+    /// ([`detect_breaking_change`]) -- callers render this as the `!`
+    /// marker on the subject line (see [`format_subject_line`]).
+    pub breaking: bool,
+}
+
+/// Public top-level Rust items removed by the staged diff of `staged_paths`
+/// (a rename shows up the same way as a removal: old name gone, new name
+/// added), via the tree-sitter symbol diff in [`crate::explain_patch`].
+fn detect_breaking_change(staged_paths: &[std::path::PathBuf]) -> Vec<String> {
+    let mut removed = Vec::new();
+    for p in staged_paths {
+        if p.extension().and_then(|e| e.to_str()) != Some("rs") {
+            continue;
+        }
+        let path_str = p.to_string_lossy().to_string();
+        let Ok(out) = Command::new("git")
+            .args(["diff", "--cached", "--", &path_str])
+            .output()
+        else {
+            continue;
+        };
+        if !out.status.success() {
+            continue;
+        }
+        let patch = String::from_utf8_lossy(&out.stdout).to_string();
+        if patch.trim().is_empty() {
+            continue;
+        }
+        let Ok(files) = crate::explain_patch::analyze(&patch) else {
+            continue;
+        };
+        for f in files {
+            for s in f.symbols.unwrap_or_default() {
+                if s.pub_api && matches!(s.change, crate::explain_patch::SymbolChange::Removed) {
+                    removed.push(s.name);
+                }
+            }
+        }
+    }
+    removed
+}
+
+/// Best-effort `<prefix>-<digits>` ticket ID out of `branch`, trying each
+/// of `prefixes` in order (e.g. `PROJ` matches `feature/PROJ-123-foo`).
+fn extract_issue_ref(branch: &str, prefixes: &[String]) -> Option<String> {
+    for prefix in prefixes {
+        let needle = format!("{prefix}-");
+        if let Some(i) = branch.find(&needle) {
+            let rest = &branch[i + needle.len()..];
+            let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+            if !digits.is_empty() {
+                return Some(format!("{prefix}-{digits}"));
+            }
+        }
+    }
+    None
+}
+
+/// Render `msg`'s subject line per `[commit] style`:
+/// - `"conventional"` (default, or unrecognized values): `type(scope)!: subject`,
+///   inserting the Conventional Commits `!` breaking-change marker right
+///   before the `:` when [`MsgOutput::breaking`] is set.
+/// - `"gitmoji"`: `<emoji> subject`, the emoji standing in for `ctype`.
+/// - `"custom"`: `subject_template` with `{type}`/`{scope}`/`{bang}`/
+///   `{subject}` placeholders substituted.
+pub fn format_subject_line(msg: &MsgOutput, style: &str, subject_template: Option<&str>) -> String {
+    let bang = if msg.breaking { "!" } else { "" };
+    match style {
+        "gitmoji" => format!("{} {}", gitmoji_for(&msg.ctype), msg.subject),
+        "custom" => {
+            let template = subject_template.unwrap_or("{type}({scope}){bang}: {subject}");
+            template
+                .replace("{type}", &msg.ctype)
+                .replace("{scope}", msg.scope.as_deref().unwrap_or(""))
+                .replace("{bang}", bang)
+                .replace("{subject}", &msg.subject)
+        }
+        _ => match &msg.scope {
+            Some(sc) => format!("{}({}){}: {}", msg.ctype, sc, bang, msg.subject),
+            None => format!("{}{}: {}", msg.ctype, bang, msg.subject),
+        },
+    }
+}
+
+/// Standard Gitmoji (https://gitmoji.dev) for each Conventional Commit
+/// type, falling back to the generic bookmark emoji for unknown types.
+fn gitmoji_for(ctype: &str) -> &'static str {
+    match ctype {
+        "feat" => "✨",
+        "fix" => "🐛",
+        "docs" => "📝",
+        "style" => "💄",
+        "refactor" => "♻️",
+        "perf" => "⚡️",
+        "test" => "✅",
+        "build" => "👷",
+        "ci" => "💚",
+        "chore" => "🔧",
+        "revert" => "⏪",
+        _ => "🔖",
+    }
 }
 
 pub fn generate_struct(input: &MsgInput) -> Result<MsgOutput> {
@@ -187,12 +411,29 @@ pub fn generate_struct(input: &MsgInput) -> Result<MsgOutput> {
     let subj_raw = infer_subject(&files, &ctype, scope.as_deref().unwrap_or("repo"));
     let subject = truncate_to(subj_raw.trim_end_matches('.'), input.max_subject);
     let body = input.template_body.clone().unwrap_or_default();
+    let removed_pub_items = detect_breaking_change(&input.staged_paths);
+    let breaking = !removed_pub_items.is_empty();
+    let mut footers = Vec::new();
+    if breaking {
+        footers.push(format!(
+            "BREAKING CHANGE: removed/renamed public item(s): {}",
+            removed_pub_items.join(", ")
+        ));
+    }
+    if !input.issue_prefixes.is_empty() {
+        if let Ok(branch) = devit_tools::git::current_branch() {
+            if let Some(issue) = extract_issue_ref(&branch, &input.issue_prefixes) {
+                footers.push(format!("Refs: {issue}"));
+            }
+        }
+    }
     Ok(MsgOutput {
         ctype,
         scope,
         subject,
         body,
-        footers: Vec::new(),
+        footers,
+        breaking,
     })
 }
 
@@ -216,3 +457,73 @@ fn truncate_to(s: &str, max: usize) -> String {
         s.chars().take(max).collect()
     }
 }
+
+// -------- Lint (commit-msg hook) --------
+
+struct ParsedHeader {
+    ctype: String,
+    scope: Option<String>,
+}
+
+/// Naive Conventional Commits header parse: `type(scope)!: subject` or
+/// `type!: subject`. `None` when the header doesn't contain the mandatory
+/// `: ` separator or has an empty type/subject.
+fn parse_header(header: &str) -> Option<ParsedHeader> {
+    let sep = header.find(": ")?;
+    let (head, subject) = (&header[..sep], header[sep + 2..].trim());
+    let head = head.strip_suffix('!').unwrap_or(head);
+    let (ctype, scope) = match head.find('(') {
+        Some(open) if head.ends_with(')') => (
+            head[..open].to_string(),
+            Some(head[open + 1..head.len() - 1].to_string()),
+        ),
+        Some(_) => return None,
+        None => (head.to_string(), None),
+    };
+    if ctype.is_empty() || subject.is_empty() {
+        return None;
+    }
+    Some(ParsedHeader { ctype, scope })
+}
+
+/// Validate `message` (a full commit message, as written to
+/// `.git/COMMIT_EDITMSG`) against `cfg`: Conventional Commits syntax,
+/// `max_subject`, and the allowed `types`/`allowed_scopes` lists. Returns
+/// one diagnostic string per violation; an empty result means the message
+/// passes.
+pub fn lint(message: &str, cfg: &devit_common::CommitCfg) -> Vec<String> {
+    let header = message.lines().next().unwrap_or("").trim_end();
+    if header.is_empty() {
+        return vec!["empty commit message".to_string()];
+    }
+    let Some(parsed) = parse_header(header) else {
+        return vec![format!(
+            "header does not match Conventional Commits syntax \"type(scope): subject\": \"{header}\""
+        )];
+    };
+    let mut violations = Vec::new();
+    if header.chars().count() > cfg.max_subject {
+        violations.push(format!(
+            "header is {} chars, max_subject is {}",
+            header.chars().count(),
+            cfg.max_subject
+        ));
+    }
+    if !cfg.types.is_empty() && !cfg.types.iter().any(|t| t == &parsed.ctype) {
+        violations.push(format!(
+            "type \"{}\" is not in the allowed types: {}",
+            parsed.ctype,
+            cfg.types.join(", ")
+        ));
+    }
+    if let Some(scope) = &parsed.scope {
+        if !cfg.allowed_scopes.is_empty() && !cfg.allowed_scopes.iter().any(|s| s == scope) {
+            violations.push(format!(
+                "scope \"{}\" is not in the allowed scopes: {}",
+                scope,
+                cfg.allowed_scopes.join(", ")
+            ));
+        }
+    }
+    violations
+}
@@ -0,0 +1,123 @@
+// # -----------------------------
+// # crates/cli/src/complexity.rs
+// # -----------------------------
+// `devit report complexity`: cyclomatic complexity / length per top-level
+// symbol, using the same tree-sitter [`devit_context::extract_symbols`]
+// scan as `devit context symbols` -- so an LLM patch that turns a small
+// function into a monster gets flagged by `quality gate`, not just by a
+// human skimming the diff.
+
+use anyhow::{Context, Result};
+use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionMetric {
+    pub file: String,
+    pub name: String,
+    pub kind: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub length: usize,
+    pub complexity: u32,
+}
+
+const FUNCTION_KINDS: &[&str] = &[
+    "function_item",
+    "function_declaration",
+    "function_definition",
+    "method_declaration",
+    "method",
+];
+
+/// Substrings counted as a branch point. Approximate on purpose (no full
+/// control-flow graph) -- same spirit as [`crate::report`]'s line-based
+/// JUnit/SARIF parsing: cheap and good enough to flag outliers.
+const DECISION_MARKERS: &[&str] = &[
+    "if ", "if(", "else if", "for ", "for(", "while ", "while(", "match ", "case ", "catch ",
+    "except ", "&&", "||", "?",
+];
+
+fn cyclomatic_complexity(text: &str) -> u32 {
+    let mut count = 1u32;
+    for marker in DECISION_MARKERS {
+        count += text.matches(marker).count() as u32;
+    }
+    count
+}
+
+fn analyze_file(path: &Path, root: &Path) -> Vec<FunctionMetric> {
+    let rel = pathdiff::diff_paths(path, root).unwrap_or_else(|| path.to_path_buf());
+    let rels = rel.to_string_lossy().to_string();
+    let lang = devit_context::detect_lang(&rels);
+    let Ok(source) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    devit_context::extract_symbols(&source, &lang)
+        .into_iter()
+        .filter(|s| FUNCTION_KINDS.contains(&s.kind))
+        .map(|s| FunctionMetric {
+            file: rels.clone(),
+            name: s.name,
+            kind: s.kind.to_string(),
+            start_line: s.start_line,
+            end_line: s.end_line,
+            length: s.end_line.saturating_sub(s.start_line) + 1,
+            complexity: cyclomatic_complexity(&s.text),
+        })
+        .collect()
+}
+
+/// Walk `root` (respecting `.gitignore`/`.devitignore`, skipping
+/// `.devit/`/`target/`) and compute a [`FunctionMetric`] per top-level
+/// function-like symbol in every recognized language file.
+pub fn collect(root: &Path) -> Vec<FunctionMetric> {
+    let mut out = Vec::new();
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .git_ignore(true)
+        .git_global(true)
+        .git_exclude(true)
+        .hidden(true)
+        .follow_links(false)
+        .add_custom_ignore_filename(".devitignore");
+    for ent in builder.build() {
+        let Ok(ent) = ent else { continue };
+        let path = ent.path();
+        let rel = path.to_string_lossy();
+        if rel.contains(".devit/") || rel.contains("target/") {
+            continue;
+        }
+        if !ent.file_type().map(|t| t.is_file()).unwrap_or(false) {
+            continue;
+        }
+        out.extend(analyze_file(path, root));
+    }
+    out
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComplexityReport {
+    pub functions: Vec<FunctionMetric>,
+    pub max_complexity: u32,
+    pub max_length: usize,
+}
+
+pub fn report(root: &Path, out: &Path) -> Result<ComplexityReport> {
+    let functions = collect(root);
+    let report = ComplexityReport {
+        max_complexity: functions.iter().map(|f| f.complexity).max().unwrap_or(0),
+        max_length: functions.iter().map(|f| f.length).max().unwrap_or(0),
+        functions,
+    };
+    if let Some(dir) = out.parent() {
+        fs::create_dir_all(dir).ok();
+    }
+    fs::write(
+        out,
+        serde_json::to_vec_pretty(&report).context("serialize complexity report")?,
+    )?;
+    Ok(report)
+}
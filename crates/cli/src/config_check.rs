@@ -0,0 +1,103 @@
+// # -----------------------------
+// # crates/cli/src/config_check.rs
+// # -----------------------------
+// Backing implementation for `devit config validate` / `devit config show`.
+
+use anyhow::{Context, Result};
+use devit_common::Config;
+use serde_json::json;
+use std::fs;
+
+/// Top-level sections `Config` knows how to deserialize.
+const KNOWN_SECTIONS: &[&str] = &[
+    "backend",
+    "policy",
+    "sandbox",
+    "git",
+    "provenance",
+    "precommit",
+    "commit",
+    "secrets",
+    "test",
+];
+
+/// Read the config at `path` (honoring the `DEVIT_CONFIG` override, same as
+/// `load_cfg`) and return its raw text.
+fn read_cfg_text(path: &str) -> Result<String> {
+    let cfg_path = std::env::var("DEVIT_CONFIG").unwrap_or_else(|_| path.to_string());
+    fs::read_to_string(&cfg_path).with_context(|| format!("unable to read config at {}", cfg_path))
+}
+
+/// Levenshtein distance, used to suggest a nearby known key for a typo'd one.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        let mut prev = row[0];
+        row[0] = i;
+        for j in 1..=b.len() {
+            let cur = row[j];
+            row[j] = if a[i - 1] == b[j - 1] {
+                prev
+            } else {
+                1 + prev.min(row[j]).min(row[j - 1])
+            };
+            prev = cur;
+        }
+    }
+    row[b.len()]
+}
+
+/// Nearest known section to `key`, if close enough to be worth suggesting.
+fn suggest_section(key: &str) -> Option<&'static str> {
+    KNOWN_SECTIONS
+        .iter()
+        .map(|&known| (known, edit_distance(key, known)))
+        .filter(|(_, dist)| *dist <= 2)
+        .min_by_key(|(_, dist)| *dist)
+        .map(|(known, _)| known)
+}
+
+/// Validate the config at `path`: unknown top-level keys become warnings
+/// (with a suggestion when a known key is close), and anything that fails to
+/// deserialize into `Config` becomes an error carrying toml's own
+/// line/column-aware message. Never fails itself — the report tells the
+/// caller whether the config is usable.
+pub fn validate(path: &str) -> Result<serde_json::Value> {
+    let text = read_cfg_text(path)?;
+
+    let mut warnings = Vec::new();
+    if let Ok(raw) = toml::from_str::<toml::Value>(&text) {
+        if let Some(table) = raw.as_table() {
+            for key in table.keys() {
+                if !KNOWN_SECTIONS.contains(&key.as_str()) {
+                    let mut msg = format!("unknown config section: [{}]", key);
+                    if let Some(suggestion) = suggest_section(key) {
+                        msg.push_str(&format!(" (did you mean [{}]?)", suggestion));
+                    }
+                    warnings.push(msg);
+                }
+            }
+        }
+    }
+
+    let mut errors = Vec::new();
+    if let Err(e) = toml::from_str::<Config>(&text) {
+        errors.push(e.to_string());
+    }
+
+    Ok(json!({
+        "ok": errors.is_empty(),
+        "errors": errors,
+        "warnings": warnings,
+    }))
+}
+
+/// Load and fully resolve the config at `path` (all `#[serde(default = "...")]`
+/// fields filled in), for `devit config show --effective`.
+pub fn effective(path: &str) -> Result<Config> {
+    let text = read_cfg_text(path)?;
+    let cfg: Config = toml::from_str(&text).context("config invalide")?;
+    Ok(cfg)
+}
@@ -0,0 +1,251 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Instant;
+
+use crate::test_runner::detect_framework;
+
+#[derive(Debug, Clone)]
+pub struct CoverageOpts {
+    pub framework: Option<String>,
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CoverageReport {
+    pub framework: String,
+    pub report_path: String,
+    pub lines_pct: Option<f64>,
+    pub duration_ms: u128,
+}
+
+fn ensure_coverage_dir() -> PathBuf {
+    let p = Path::new(".devit/reports/coverage");
+    let _ = fs::create_dir_all(p);
+    p.to_path_buf()
+}
+
+/// `devit test coverage`: run the detected stack's coverage tool
+/// (llvm-cov/coverage.py/nyc) and drop a normalized lcov/Cobertura report
+/// under `.devit/reports/coverage/` for [`crate::report::summary_markdown`]
+/// to surface totals from.
+pub fn run_coverage(opts: &CoverageOpts) -> anyhow::Result<CoverageReport> {
+    let framework = opts
+        .framework
+        .clone()
+        .filter(|s| s != "auto")
+        .unwrap_or_else(detect_framework);
+    let _to = crate::test_runner::timeout(opts.timeout_secs);
+    let dir = ensure_coverage_dir();
+    let t0 = Instant::now();
+
+    match framework.as_str() {
+        "cargo" => {
+            let out_path = dir.join("lcov.info");
+            let status = Command::new("bash")
+                .arg("-lc")
+                .arg(format!(
+                    "cargo llvm-cov --workspace --lcov --output-path {}",
+                    out_path.display()
+                ))
+                .status()?;
+            if !status.success() {
+                anyhow::bail!("cargo llvm-cov failed");
+            }
+            Ok(CoverageReport {
+                framework,
+                lines_pct: parse_lcov_totals(&out_path),
+                report_path: out_path.display().to_string(),
+                duration_ms: t0.elapsed().as_millis(),
+            })
+        }
+        "pytest" => {
+            let out_path = dir.join("cobertura.xml");
+            let status = Command::new("bash")
+                .arg("-lc")
+                .arg(format!(
+                    "coverage run -m pytest -q && coverage xml -o {}",
+                    out_path.display()
+                ))
+                .status()?;
+            if !status.success() {
+                anyhow::bail!("coverage.py run failed");
+            }
+            Ok(CoverageReport {
+                framework,
+                lines_pct: parse_cobertura_line_rate(&out_path),
+                report_path: out_path.display().to_string(),
+                duration_ms: t0.elapsed().as_millis(),
+            })
+        }
+        "npm" | "pnpm" => {
+            let out_path = dir.join("cobertura.xml");
+            let status = Command::new("bash")
+                .arg("-lc")
+                .arg("npx nyc --reporter=cobertura npm test --silent")
+                .status()?;
+            if !status.success() {
+                anyhow::bail!("nyc run failed");
+            }
+            let default_out = Path::new("coverage/cobertura-coverage.xml");
+            if default_out.is_file() {
+                let _ = fs::copy(default_out, &out_path);
+            }
+            Ok(CoverageReport {
+                framework,
+                lines_pct: parse_cobertura_line_rate(&out_path),
+                report_path: out_path.display().to_string(),
+                duration_ms: t0.elapsed().as_millis(),
+            })
+        }
+        other => {
+            anyhow::bail!(serde_json::json!({"unsupported_framework": other}).to_string());
+        }
+    }
+}
+
+/// Looks for a coverage report already produced by [`run_coverage`] under
+/// `.devit/reports/coverage/` and returns `(format, line coverage %)`,
+/// without re-running any tooling — used by
+/// [`crate::report::summary_markdown`].
+pub fn latest_totals() -> Option<(String, f64)> {
+    latest_coverage_totals().map(|t| (t.format, t.line_pct))
+}
+
+/// Line and (where the format reports it) branch coverage percentages from
+/// the latest `.devit/reports/coverage/` report — used by
+/// [`crate::report::summarize`]'s coverage-threshold/regression checks.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CoverageTotals {
+    pub format: String,
+    pub line_pct: f64,
+    pub branch_pct: Option<f64>,
+}
+
+pub fn latest_coverage_totals() -> Option<CoverageTotals> {
+    let lcov = Path::new(".devit/reports/coverage/lcov.info");
+    if lcov.is_file() {
+        if let Some(line_pct) = parse_lcov_totals(lcov) {
+            return Some(CoverageTotals {
+                format: "lcov".to_string(),
+                line_pct,
+                branch_pct: parse_lcov_branch_totals(lcov),
+            });
+        }
+    }
+    let cobertura = Path::new(".devit/reports/coverage/cobertura.xml");
+    if cobertura.is_file() {
+        if let Some(line_pct) = parse_cobertura_line_rate(cobertura) {
+            return Some(CoverageTotals {
+                format: "cobertura".to_string(),
+                line_pct,
+                branch_pct: parse_cobertura_branch_rate(cobertura),
+            });
+        }
+    }
+    None
+}
+
+fn baseline_path() -> PathBuf {
+    Path::new(".devit/coverage_baseline.json").to_path_buf()
+}
+
+/// Last coverage totals the quality gate passed with, recorded by
+/// [`save_baseline`] -- used to fail the gate on a coverage *regression*
+/// even when both percentages are still above `min_line_coverage`/
+/// `min_branch_coverage`.
+pub fn load_baseline() -> Option<CoverageTotals> {
+    let s = fs::read_to_string(baseline_path()).ok()?;
+    serde_json::from_str(&s).ok()
+}
+
+pub fn save_baseline(totals: &CoverageTotals) {
+    if let Some(dir) = baseline_path().parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    if let Ok(s) = serde_json::to_string(totals) {
+        let _ = fs::write(baseline_path(), s);
+    }
+}
+
+fn parse_lcov_totals(path: &Path) -> Option<f64> {
+    let s = fs::read_to_string(path).ok()?;
+    let mut found = 0u64;
+    let mut hit = 0u64;
+    for line in s.lines() {
+        let Some(rest) = line.strip_prefix("DA:") else {
+            continue;
+        };
+        let mut parts = rest.split(',');
+        let _line_no = parts.next();
+        if let Some(h) = parts.next().and_then(|x| x.parse::<u64>().ok()) {
+            found += 1;
+            if h > 0 {
+                hit += 1;
+            }
+        }
+    }
+    if found == 0 {
+        return None;
+    }
+    Some((hit as f64 / found as f64) * 100.0)
+}
+
+fn parse_cobertura_line_rate(path: &Path) -> Option<f64> {
+    let s = fs::read_to_string(path).ok()?;
+    for line in s.lines() {
+        if line.contains("<coverage") {
+            if let Some(v) = attr_f64(line, "line-rate") {
+                return Some(v * 100.0);
+            }
+        }
+    }
+    None
+}
+
+/// lcov's `BRDA:<line>,<block>,<branch>,<taken>` records one line per
+/// branch; `taken` is a hit count, or `-` when the branch was never
+/// reachable (excluded from the denominator, same as gcov/lcov itself do).
+fn parse_lcov_branch_totals(path: &Path) -> Option<f64> {
+    let s = fs::read_to_string(path).ok()?;
+    let mut found = 0u64;
+    let mut hit = 0u64;
+    for line in s.lines() {
+        let Some(rest) = line.strip_prefix("BRDA:") else {
+            continue;
+        };
+        let taken = rest.rsplit(',').next()?;
+        if taken == "-" {
+            continue;
+        }
+        found += 1;
+        if taken.parse::<u64>().unwrap_or(0) > 0 {
+            hit += 1;
+        }
+    }
+    if found == 0 {
+        return None;
+    }
+    Some((hit as f64 / found as f64) * 100.0)
+}
+
+fn parse_cobertura_branch_rate(path: &Path) -> Option<f64> {
+    let s = fs::read_to_string(path).ok()?;
+    for line in s.lines() {
+        if line.contains("<coverage") {
+            if let Some(v) = attr_f64(line, "branch-rate") {
+                return Some(v * 100.0);
+            }
+        }
+    }
+    None
+}
+
+fn attr_f64(line: &str, key: &str) -> Option<f64> {
+    let pat = format!("{key}=\"");
+    let i = line.find(&pat)?;
+    let rest = &line[i + pat.len()..];
+    let j = rest.find('"')?;
+    rest[..j].parse::<f64>().ok()
+}
@@ -0,0 +1,139 @@
+// # -----------------------------
+// # crates/cli/src/deadcode.rs
+// # -----------------------------
+// `devit report deadcode`: cross-reference the tree-sitter symbol scan
+// ([`devit_context::extract_symbols`]) with a whole-repo textual usage
+// count to flag public items that look unreferenced -- cleanup targets
+// for the agent's recipes, not a borrow-checker-grade dead-code analysis.
+
+use anyhow::{Context, Result};
+use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadSymbol {
+    pub file: String,
+    pub name: String,
+    pub kind: String,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadcodeReport {
+    pub candidates: Vec<DeadSymbol>,
+    pub scanned_files: usize,
+    pub scanned_symbols: usize,
+}
+
+/// Kinds worth flagging -- types and callables, not `impl`/`mod` blocks
+/// (whose "usage" is structural rather than name-reference based).
+const TRACKED_KINDS: &[&str] = &[
+    "function_item",
+    "struct_item",
+    "enum_item",
+    "trait_item",
+    "function_declaration",
+    "class_declaration",
+    "function_definition",
+    "class_definition",
+    "method_declaration",
+];
+
+fn is_public(lang: &str, text: &str) -> bool {
+    lang != "rust" || text.trim_start().starts_with("pub ")
+}
+
+/// Count whole-identifier occurrences of `name` in `source` (splitting on
+/// non `[A-Za-z0-9_]` boundaries, so `Foo` doesn't match inside `FooBar`).
+fn count_occurrences(source: &str, name: &str) -> usize {
+    source
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .filter(|w| *w == name)
+        .count()
+}
+
+/// Walk `root` (respecting `.gitignore`/`.devitignore`, skipping
+/// `.devit/`/`target/`), gathering `(relative path, source)` for every
+/// recognized language file.
+fn collect_sources(root: &Path) -> Vec<(String, String)> {
+    let mut out = Vec::new();
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .git_ignore(true)
+        .git_global(true)
+        .git_exclude(true)
+        .hidden(true)
+        .follow_links(false)
+        .add_custom_ignore_filename(".devitignore");
+    for ent in builder.build() {
+        let Ok(ent) = ent else { continue };
+        let path = ent.path();
+        let rel = path.to_string_lossy();
+        if rel.contains(".devit/") || rel.contains("target/") {
+            continue;
+        }
+        if !ent.file_type().map(|t| t.is_file()).unwrap_or(false) {
+            continue;
+        }
+        let lang = devit_context::detect_lang(&rel);
+        if !matches!(
+            lang.as_str(),
+            "rust" | "js" | "py" | "go" | "java" | "csharp" | "ruby" | "php"
+        ) {
+            continue;
+        }
+        if let Ok(source) = fs::read_to_string(path) {
+            let rel_path = pathdiff::diff_paths(path, root)
+                .unwrap_or_else(|| path.to_path_buf())
+                .to_string_lossy()
+                .to_string();
+            out.push((rel_path, source));
+        }
+    }
+    out
+}
+
+pub fn report(root: &Path, out: &Path) -> Result<DeadcodeReport> {
+    let sources = collect_sources(root);
+    let mut scanned_symbols = 0usize;
+    let mut candidates = Vec::new();
+    for (file, source) in &sources {
+        let lang = devit_context::detect_lang(file);
+        let symbols = devit_context::extract_symbols(source, &lang);
+        for sym in symbols {
+            if !TRACKED_KINDS.contains(&sym.kind) || !is_public(&lang, &sym.text) {
+                continue;
+            }
+            scanned_symbols += 1;
+            let total: usize = sources
+                .iter()
+                .map(|(_, s)| count_occurrences(s, &sym.name))
+                .sum();
+            // The definition itself contributes one occurrence -- anything
+            // beyond that means some other line referenced the name.
+            if total <= 1 {
+                candidates.push(DeadSymbol {
+                    file: file.clone(),
+                    name: sym.name,
+                    kind: sym.kind.to_string(),
+                    line: sym.start_line,
+                });
+            }
+        }
+    }
+    let report = DeadcodeReport {
+        candidates,
+        scanned_files: sources.len(),
+        scanned_symbols,
+    };
+    if let Some(dir) = out.parent() {
+        fs::create_dir_all(dir).ok();
+    }
+    fs::write(
+        out,
+        serde_json::to_vec_pretty(&report).context("serialize deadcode report")?,
+    )?;
+    Ok(report)
+}
@@ -0,0 +1,160 @@
+// # -----------------------------
+// # crates/cli/src/diff_preview.rs
+// # -----------------------------
+// Colorized terminal rendering for `devit apply --preview`: per-file
+// +/- stats and word-level intra-line highlighting, built on the same
+// unified-diff parser `--interactive` uses.
+
+use devit_tui::{parse_unified_diff, DiffFile};
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const HL_RED: &str = "\x1b[1;41m";
+const HL_GREEN: &str = "\x1b[1;42m";
+
+/// Render `patch` as a colored preview (per-file stat line, then each hunk
+/// with removed/added lines colored and, for 1:1 replace pairs, the
+/// changed words themselves highlighted). Falls back to the diff parser's
+/// error message on malformed input.
+pub fn render(patch: &str) -> Result<String, String> {
+    let files = parse_unified_diff(patch)?;
+    let mut out = String::new();
+    for file in &files {
+        render_file(file, &mut out);
+    }
+    Ok(out)
+}
+
+fn render_file(file: &DiffFile, out: &mut String) {
+    let (added, deleted) = file
+        .hunks
+        .iter()
+        .flat_map(|h| h.lines.iter())
+        .fold((0u32, 0u32), |(a, d), line| match line.chars().next() {
+            Some('+') => (a + 1, d),
+            Some('-') => (a, d + 1),
+            _ => (a, d),
+        });
+    out.push_str(&format!(
+        "{BOLD}{}{RESET} {GREEN}+{added}{RESET} {RED}-{deleted}{RESET}\n",
+        file.display_name
+    ));
+    for hunk in &file.hunks {
+        out.push_str(&format!("{DIM}{}{RESET}\n", hunk.header));
+        render_hunk_lines(&hunk.lines, out);
+    }
+}
+
+fn render_hunk_lines(lines: &[String], out: &mut String) {
+    let mut i = 0;
+    while i < lines.len() {
+        let line = &lines[i];
+        match line.chars().next() {
+            Some('-') => {
+                // A single removed line immediately followed by a single
+                // added line is treated as a "replace" and gets word-level
+                // highlighting; anything else (deletes, adds, multi-line
+                // blocks) is just colored whole-line, like `git diff`.
+                let is_replace_pair = i + 1 < lines.len()
+                    && lines[i + 1].starts_with('+')
+                    && (i + 2 >= lines.len() || !lines[i + 2].starts_with('-'));
+                if is_replace_pair {
+                    render_word_diff(&line[1..], &lines[i + 1][1..], out);
+                    i += 2;
+                } else {
+                    out.push_str(&format!("{RED}{line}{RESET}\n"));
+                    i += 1;
+                }
+            }
+            Some('+') => {
+                out.push_str(&format!("{GREEN}{line}{RESET}\n"));
+                i += 1;
+            }
+            _ => {
+                out.push_str(&format!("{line}\n"));
+                i += 1;
+            }
+        }
+    }
+}
+
+fn render_word_diff(old: &str, new: &str, out: &mut String) {
+    let old_words = tokenize(old);
+    let new_words = tokenize(new);
+    let (old_same, new_same) = common_tokens(&old_words, &new_words);
+
+    out.push_str(&format!("{RED}-{RESET}"));
+    for (idx, word) in old_words.iter().enumerate() {
+        if old_same[idx] {
+            out.push_str(&format!("{RED}{word}{RESET}"));
+        } else {
+            out.push_str(&format!("{HL_RED}{word}{RESET}"));
+        }
+    }
+    out.push('\n');
+
+    out.push_str(&format!("{GREEN}+{RESET}"));
+    for (idx, word) in new_words.iter().enumerate() {
+        if new_same[idx] {
+            out.push_str(&format!("{GREEN}{word}{RESET}"));
+        } else {
+            out.push_str(&format!("{HL_GREEN}{word}{RESET}"));
+        }
+    }
+    out.push('\n');
+}
+
+/// Split into alternating runs of "word" (alnum/underscore) and
+/// "non-word" characters, so e.g. `foo_bar(baz)` tokenizes as
+/// `["foo_bar", "(", "baz", ")"]` and whitespace stays significant.
+fn tokenize(line: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let bytes = line.as_bytes();
+    let is_word = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+    let mut start = 0;
+    while start < bytes.len() {
+        let mut end = start + 1;
+        while end < bytes.len() && is_word(bytes[end]) == is_word(bytes[start]) {
+            end += 1;
+        }
+        tokens.push(&line[start..end]);
+        start = end;
+    }
+    tokens
+}
+
+/// For each side, mark which tokens participate in the longest common
+/// subsequence between `old` and `new` (the unmarked ones are the changed
+/// words to highlight).
+fn common_tokens(old: &[&str], new: &[&str]) -> (Vec<bool>, Vec<bool>) {
+    let (n, m) = (old.len(), new.len());
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let mut old_same = vec![false; n];
+    let mut new_same = vec![false; m];
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            old_same[i] = true;
+            new_same[j] = true;
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    (old_same, new_same)
+}
@@ -0,0 +1,95 @@
+// # -----------------------------
+// # crates/cli/src/exit_code.rs
+// # -----------------------------
+// Stable process exit codes and the `code` string surfaced in `--json`
+// error payloads (`{"type":"tool.error","error":..,"code":..}`), so scripts
+// can branch on failure kind instead of matching human-readable text.
+
+use anyhow::Result;
+use serde_json::Value;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCode {
+    Ok,
+    GenericError,
+    ApprovalRequired,
+    PrecommitFailed,
+    TestsFailed,
+    Timeout,
+    SecretsFound,
+    CommitMsgInvalid,
+}
+
+impl ExitCode {
+    pub fn code(self) -> u8 {
+        match self {
+            ExitCode::Ok => 0,
+            ExitCode::GenericError => 1,
+            ExitCode::ApprovalRequired => 2,
+            ExitCode::PrecommitFailed => 3,
+            ExitCode::TestsFailed => 4,
+            ExitCode::SecretsFound => 5,
+            ExitCode::CommitMsgInvalid => 6,
+            ExitCode::Timeout => 124,
+        }
+    }
+}
+
+/// A subcommand failure carrying the process exit code, a machine-readable
+/// `code` string (finer-grained than [`ExitCode`] — several `code`s can
+/// share the same numeric exit), and, when available, the structured
+/// details previously stringified straight into the error message.
+#[derive(Debug)]
+pub struct CliError {
+    pub exit: ExitCode,
+    pub code: &'static str,
+    pub message: String,
+    pub details: Option<Value>,
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for CliError {}
+
+/// Fail with a stable `code` and no extra structured payload.
+pub fn fail<T>(exit: ExitCode, code: &'static str, message: impl Into<String>) -> Result<T> {
+    Err(CliError {
+        exit,
+        code,
+        message: message.into(),
+        details: None,
+    }
+    .into())
+}
+
+/// Fail with a stable `code` plus a structured `details` payload (e.g. the
+/// failing tool's stderr), instead of stringifying it into the message.
+pub fn fail_with<T>(
+    exit: ExitCode,
+    code: &'static str,
+    message: impl Into<String>,
+    details: Value,
+) -> Result<T> {
+    Err(CliError {
+        exit,
+        code,
+        message: message.into(),
+        details: Some(details),
+    }
+    .into())
+}
+
+/// Recover the `(exit_code, code, details)` triple from a bubbled-up error,
+/// falling back to the generic contract for errors not raised via [`fail`]/
+/// [`fail_with`] (plain `anyhow::bail!`/`.context(..)` call sites).
+pub fn describe(err: &anyhow::Error) -> (ExitCode, &'static str, Option<Value>) {
+    match err.downcast_ref::<CliError>() {
+        Some(e) => (e.exit, e.code, e.details.clone()),
+        None => (ExitCode::GenericError, "generic_error", None),
+    }
+}
@@ -0,0 +1,174 @@
+// # -----------------------------
+// # crates/cli/src/explain_patch.rs
+// # -----------------------------
+// `devit explain-patch`: combine diff parsing with the symbol index (see
+// `context::extract_symbols`) to report which functions/types a patch
+// adds, removes, or modifies -- a quick semantic summary before approving
+// a large diff.
+
+use crate::context::{detect_lang, extract_symbols, SymbolInfo};
+use devit_tui::{parse_unified_diff, DiffFile, DiffHunk};
+use serde::Serialize;
+use std::process::Command;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolChange {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SymbolDiff {
+    pub name: String,
+    pub kind: &'static str,
+    pub change: SymbolChange,
+    /// Whether the item is `pub` (rust only) -- lets callers like
+    /// `commit_msg::detect_breaking_change` flag public API removals.
+    pub pub_api: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct FileExplanation {
+    pub path: String,
+    pub lang: String,
+    /// `None` when `lang` has no symbol index (see `context::extract_symbols`);
+    /// the file is still listed so a large patch can't hide a touched file.
+    pub symbols: Option<Vec<SymbolDiff>>,
+}
+
+/// Parse `patch` and, for each touched rust/js/py file, diff its top-level
+/// symbols between the pre- and post-image to say what changed.
+pub fn analyze(patch: &str) -> Result<Vec<FileExplanation>, String> {
+    let files = parse_unified_diff(patch)?;
+    Ok(files.iter().map(explain_file).collect())
+}
+
+fn explain_file(file: &DiffFile) -> FileExplanation {
+    let lang = detect_lang(&file.display_name);
+    if !matches!(lang.as_str(), "rust" | "js" | "py") {
+        return FileExplanation {
+            path: file.display_name.clone(),
+            lang,
+            symbols: None,
+        };
+    }
+
+    let added_file = file.header.iter().any(|l| l.starts_with("--- /dev/null"));
+    let deleted_file = file.header.iter().any(|l| l.starts_with("+++ /dev/null"));
+
+    let old_content = if added_file {
+        String::new()
+    } else {
+        git_show_head(&file.display_name).unwrap_or_default()
+    };
+    let new_content = if deleted_file {
+        String::new()
+    } else {
+        apply_hunks(&old_content, &file.hunks)
+    };
+
+    let old_symbols = extract_symbols(&old_content, &lang);
+    let new_symbols = extract_symbols(&new_content, &lang);
+
+    FileExplanation {
+        path: file.display_name.clone(),
+        lang: lang.clone(),
+        symbols: Some(diff_symbols(&lang, &old_symbols, &new_symbols)),
+    }
+}
+
+/// Whether `text` declares a publicly visible item -- only meaningful for
+/// `rust`, where `pub` is an explicit marker.
+fn is_pub_item(lang: &str, text: &str) -> bool {
+    lang == "rust" && text.trim_start().starts_with("pub ")
+}
+
+/// Best-effort pre-image: `git show HEAD:<path>` for a file already tracked
+/// at HEAD; `None` for new files, untracked files, or when there's no repo
+/// yet -- the symbol diff then just reports everything in the patch as added.
+fn git_show_head(path: &str) -> Option<String> {
+    let out = Command::new("git")
+        .args(["show", &format!("HEAD:{path}")])
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    String::from_utf8(out.stdout).ok()
+}
+
+/// Reconstruct the post-image by replaying `hunks` over `old`. Hunks are
+/// non-overlapping and given in file order, so each hunk's
+/// `@@ -start,len +... @@` header says how many untouched old lines to
+/// copy before it starts.
+fn apply_hunks(old: &str, hunks: &[DiffHunk]) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let mut new_lines: Vec<&str> = Vec::new();
+    let mut old_cursor = 0usize;
+
+    for hunk in hunks {
+        let start_idx = parse_old_start(&hunk.header)
+            .map(|n| n.saturating_sub(1))
+            .unwrap_or(old_cursor);
+        while old_cursor < start_idx && old_cursor < old_lines.len() {
+            new_lines.push(old_lines[old_cursor]);
+            old_cursor += 1;
+        }
+        for line in &hunk.lines {
+            match line.as_bytes().first() {
+                Some(b' ') => {
+                    new_lines.push(&line[1..]);
+                    old_cursor += 1;
+                }
+                Some(b'-') => old_cursor += 1,
+                Some(b'+') => new_lines.push(&line[1..]),
+                _ => {} // e.g. "\ No newline at end of file"
+            }
+        }
+    }
+    while old_cursor < old_lines.len() {
+        new_lines.push(old_lines[old_cursor]);
+        old_cursor += 1;
+    }
+    new_lines.join("\n")
+}
+
+/// Old-file starting line number out of a `@@ -a,b +c,d @@` hunk header.
+fn parse_old_start(header: &str) -> Option<usize> {
+    let minus = header.split_whitespace().find(|s| s.starts_with('-'))?;
+    minus.trim_start_matches('-').split(',').next()?.parse().ok()
+}
+
+fn diff_symbols(lang: &str, old: &[SymbolInfo], new: &[SymbolInfo]) -> Vec<SymbolDiff> {
+    let mut out = Vec::new();
+    for n in new {
+        match old.iter().find(|o| o.kind == n.kind && o.name == n.name) {
+            None => out.push(SymbolDiff {
+                name: n.name.clone(),
+                kind: n.kind,
+                change: SymbolChange::Added,
+                pub_api: is_pub_item(lang, &n.text),
+            }),
+            Some(o) if o.text != n.text => out.push(SymbolDiff {
+                name: n.name.clone(),
+                kind: n.kind,
+                change: SymbolChange::Modified,
+                pub_api: is_pub_item(lang, &n.text),
+            }),
+            Some(_) => {} // unchanged, nothing to report
+        }
+    }
+    for o in old {
+        if !new.iter().any(|n| n.kind == o.kind && n.name == o.name) {
+            out.push(SymbolDiff {
+                name: o.name.clone(),
+                kind: o.kind,
+                change: SymbolChange::Removed,
+                pub_api: is_pub_item(lang, &o.text),
+            });
+        }
+    }
+    out
+}
@@ -0,0 +1,76 @@
+// `devit hooks install|uninstall`: write/remove the repo's `pre-commit`
+// and `commit-msg` git hooks so human commits run the same gates
+// `fs_patch_apply` already runs for the agent (not to be confused with
+// the `[hooks]` devit.toml lifecycle scripts in `hooks.rs`).
+
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+
+/// Marker line written into every hook `devit hooks install` creates, so
+/// `uninstall` only ever removes hooks it owns.
+const MARKER: &str = "# devit-managed-hook";
+
+const PRE_COMMIT_SCRIPT: &str = "#!/bin/sh\n# devit-managed-hook\nexec devit tool call fs_patch_apply --precommit-only < /dev/null\n";
+
+const COMMIT_MSG_SCRIPT: &str = "#!/bin/sh\n# devit-managed-hook\nexec devit commit-msg lint --file \"$1\"\n";
+
+fn hooks_dir() -> Result<PathBuf> {
+    let dir = devit_tools::git::git_dir().context("hooks: pas un dépôt git")?;
+    Ok(PathBuf::from(dir).join("hooks"))
+}
+
+/// Write `contents` to `path`, refusing to clobber a pre-existing hook that
+/// devit doesn't own unless `force` is set.
+fn write_hook(path: &PathBuf, contents: &str, force: bool) -> Result<bool> {
+    if path.exists() {
+        let existing = fs::read_to_string(path).unwrap_or_default();
+        if !existing.contains(MARKER) && !force {
+            return Ok(false);
+        }
+    }
+    fs::write(path, contents)?;
+    let mut perms = fs::metadata(path)?.permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(path, perms)?;
+    Ok(true)
+}
+
+pub fn install(force: bool) -> Result<Vec<String>> {
+    let dir = hooks_dir()?;
+    fs::create_dir_all(&dir)?;
+    let mut installed = Vec::new();
+    for (name, script) in [
+        ("pre-commit", PRE_COMMIT_SCRIPT),
+        ("commit-msg", COMMIT_MSG_SCRIPT),
+    ] {
+        let path = dir.join(name);
+        if write_hook(&path, script, force)? {
+            installed.push(name.to_string());
+        } else {
+            bail!(
+                "{} existe déjà et n'est pas géré par devit (utilisez --force pour remplacer)",
+                path.display()
+            );
+        }
+    }
+    Ok(installed)
+}
+
+pub fn uninstall() -> Result<Vec<String>> {
+    let dir = hooks_dir()?;
+    let mut removed = Vec::new();
+    for name in ["pre-commit", "commit-msg"] {
+        let path = dir.join(name);
+        if !path.exists() {
+            continue;
+        }
+        let existing = fs::read_to_string(&path).unwrap_or_default();
+        if existing.contains(MARKER) {
+            fs::remove_file(&path)?;
+            removed.push(name.to_string());
+        }
+    }
+    Ok(removed)
+}
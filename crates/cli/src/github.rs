@@ -0,0 +1,112 @@
+// # -----------------------------
+// # crates/cli/src/github.rs
+// # -----------------------------
+// Minimal GitHub REST client for `devit pr create`: parse the `origin`
+// remote, open a pull request, and attach config-defined labels.
+
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+/// Extract `(owner, repo)` from a GitHub remote URL, SSH or HTTPS.
+pub fn parse_owner_repo(remote_url: &str) -> Option<(String, String)> {
+    let trimmed = remote_url.trim().trim_end_matches(".git");
+    let path = trimmed
+        .strip_prefix("git@github.com:")
+        .or_else(|| trimmed.strip_prefix("https://github.com/"))
+        .or_else(|| trimmed.strip_prefix("http://github.com/"))
+        .or_else(|| trimmed.strip_prefix("ssh://git@github.com/"))?;
+    let (owner, repo) = path.split_once('/')?;
+    if owner.is_empty() || repo.is_empty() {
+        return None;
+    }
+    Some((owner.to_string(), repo.to_string()))
+}
+
+pub struct GitHubClient {
+    http: Client,
+    token: String,
+    api_base: String,
+}
+
+#[derive(Serialize)]
+struct CreatePrBody<'a> {
+    title: &'a str,
+    head: &'a str,
+    base: &'a str,
+    body: &'a str,
+    draft: bool,
+}
+
+#[derive(Deserialize)]
+pub struct PullRequest {
+    pub number: u64,
+    pub html_url: String,
+}
+
+#[derive(Serialize)]
+struct AddLabelsBody<'a> {
+    labels: &'a [String],
+}
+
+impl GitHubClient {
+    pub fn new(token: String, api_base: String) -> Self {
+        Self {
+            http: Client::new(),
+            token,
+            api_base,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_pull_request(
+        &self,
+        owner: &str,
+        repo: &str,
+        title: &str,
+        head: &str,
+        base: &str,
+        body: &str,
+        draft: bool,
+    ) -> Result<PullRequest> {
+        let url = format!("{}/repos/{owner}/{repo}/pulls", self.api_base);
+        let resp = self
+            .http
+            .post(&url)
+            .bearer_auth(&self.token)
+            .header("User-Agent", "devit-cli")
+            .header("Accept", "application/vnd.github+json")
+            .json(&CreatePrBody {
+                title,
+                head,
+                base,
+                body,
+                draft,
+            })
+            .send()
+            .await
+            .context("appel à l'API GitHub (création de PR)")?
+            .error_for_status()
+            .context("l'API GitHub a rejeté la création de la PR")?;
+        resp.json().await.context("réponse GitHub invalide")
+    }
+
+    pub async fn add_labels(&self, owner: &str, repo: &str, number: u64, labels: &[String]) -> Result<()> {
+        if labels.is_empty() {
+            return Ok(());
+        }
+        let url = format!("{}/repos/{owner}/{repo}/issues/{number}/labels", self.api_base);
+        self.http
+            .post(&url)
+            .bearer_auth(&self.token)
+            .header("User-Agent", "devit-cli")
+            .header("Accept", "application/vnd.github+json")
+            .json(&AddLabelsBody { labels })
+            .send()
+            .await
+            .context("appel à l'API GitHub (ajout de labels)")?
+            .error_for_status()
+            .context("l'API GitHub a rejeté l'ajout des labels")?;
+        Ok(())
+    }
+}
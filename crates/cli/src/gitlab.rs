@@ -0,0 +1,93 @@
+// # -----------------------------
+// # crates/cli/src/gitlab.rs
+// # -----------------------------
+// Minimal GitLab REST client for `devit mr create`: parse the `origin`
+// remote's project path and open a merge request with config-defined labels.
+
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+/// Extract the `group/subgroup/project` path from a GitLab remote URL, SSH or HTTPS.
+pub fn parse_project_path(remote_url: &str) -> Option<String> {
+    let trimmed = remote_url.trim().trim_end_matches(".git");
+    let path = trimmed
+        .strip_prefix("git@gitlab.com:")
+        .or_else(|| trimmed.strip_prefix("https://gitlab.com/"))
+        .or_else(|| trimmed.strip_prefix("http://gitlab.com/"))
+        .or_else(|| trimmed.strip_prefix("ssh://git@gitlab.com/"))?;
+    if path.is_empty() {
+        return None;
+    }
+    Some(path.to_string())
+}
+
+pub struct GitLabClient {
+    http: Client,
+    token: String,
+    api_base: String,
+}
+
+#[derive(Serialize)]
+struct CreateMrBody<'a> {
+    source_branch: &'a str,
+    target_branch: &'a str,
+    title: &'a str,
+    description: &'a str,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    labels: &'a str,
+}
+
+#[derive(Deserialize)]
+pub struct MergeRequest {
+    pub iid: u64,
+    pub web_url: String,
+}
+
+impl GitLabClient {
+    pub fn new(token: String, api_base: String) -> Self {
+        Self {
+            http: Client::new(),
+            token,
+            api_base,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_merge_request(
+        &self,
+        project_path: &str,
+        source_branch: &str,
+        target_branch: &str,
+        title: &str,
+        description: &str,
+        labels: &[String],
+    ) -> Result<MergeRequest> {
+        let encoded_project = urlencode_path(project_path);
+        let url = format!(
+            "{}/projects/{encoded_project}/merge_requests",
+            self.api_base
+        );
+        let resp = self
+            .http
+            .post(&url)
+            .header("PRIVATE-TOKEN", &self.token)
+            .json(&CreateMrBody {
+                source_branch,
+                target_branch,
+                title,
+                description,
+                labels: &labels.join(","),
+            })
+            .send()
+            .await
+            .context("appel à l'API GitLab (création de MR)")?
+            .error_for_status()
+            .context("l'API GitLab a rejeté la création de la MR")?;
+        resp.json().await.context("réponse GitLab invalide")
+    }
+}
+
+fn urlencode_path(path: &str) -> String {
+    path.replace('/', "%2F")
+}
@@ -0,0 +1,139 @@
+// # -----------------------------
+// # crates/cli/src/history.rs
+// # -----------------------------
+// Backing implementation for `devit history`: correlates DevIt-authored git
+// commits (via their `DevIt-Attest` footer) with tool-call activity recorded
+// in `.devit/journal.jsonl`.
+
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+const RECORD_SEP: char = '\u{1e}';
+const FIELD_SEP: char = '\u{1f}';
+
+/// One `git log` entry whose body carries a `DevIt-Attest` footer.
+fn devit_commits(since: Option<&str>) -> Result<Vec<Value>> {
+    let mut args = vec![
+        "log".to_string(),
+        "--grep=DevIt-Attest:".to_string(),
+        format!("--format=%H{FIELD_SEP}%ct{FIELD_SEP}%s{FIELD_SEP}%B{RECORD_SEP}"),
+    ];
+    if let Some(s) = since {
+        args.push(format!("--since={s}"));
+    }
+    let out = Command::new("git").args(&args).output()?;
+    if !out.status.success() {
+        return Ok(Vec::new());
+    }
+    let text = String::from_utf8_lossy(&out.stdout);
+    let mut entries = Vec::new();
+    for record in text.split(RECORD_SEP) {
+        let record = record.trim_matches('\n');
+        if record.is_empty() {
+            continue;
+        }
+        let mut parts = record.splitn(4, FIELD_SEP);
+        let sha = parts.next().unwrap_or_default();
+        let ts: u64 = parts.next().unwrap_or_default().parse().unwrap_or(0);
+        let subject = parts.next().unwrap_or_default();
+        let body = parts.next().unwrap_or_default();
+        let Some(hash) = body
+            .lines()
+            .find_map(|l| l.strip_prefix("DevIt-Attest: "))
+        else {
+            continue;
+        };
+        entries.push(json!({
+            "type": "commit",
+            "ts": ts,
+            "sha": sha,
+            "subject": subject,
+            "attest_hash": hash.trim(),
+        }));
+    }
+    Ok(entries)
+}
+
+/// `ToolCall`/`CommandOut` pairs from the journal, joined into one record per
+/// call. `tool_filter` restricts to a single tool name; `failed_only` keeps
+/// only calls whose `exit_code` was non-zero.
+fn journal_tool_calls(tool_filter: Option<&str>, failed_only: bool) -> Result<Vec<Value>> {
+    let path = Path::new(".devit/journal.jsonl");
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let text = fs::read_to_string(path)?;
+    let mut out = Vec::new();
+    let mut pending: Option<(u64, String, Value)> = None;
+    for line in text.lines() {
+        let Ok(rec) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        let ts = rec["ts"].as_u64().unwrap_or(0);
+        if let Some(name) = rec["event"]["ToolCall"]["name"].as_str() {
+            let args = rec["event"]["ToolCall"]["args"].clone();
+            pending = Some((ts, name.to_string(), args));
+            continue;
+        }
+        let Some(line_str) = rec["event"]["CommandOut"]["line"].as_str() else {
+            continue;
+        };
+        let payload: Value = serde_json::from_str(line_str).unwrap_or(Value::Null);
+        let (call_ts, tool, args) = match pending.take() {
+            Some(v) => v,
+            None => continue,
+        };
+        if let Some(f) = tool_filter {
+            if tool != f {
+                continue;
+            }
+        }
+        let exit_code = payload.get("exit_code").and_then(|v| v.as_i64());
+        if failed_only && exit_code.unwrap_or(0) == 0 {
+            continue;
+        }
+        out.push(json!({
+            "type": "tool_call",
+            "ts": call_ts,
+            "tool": tool,
+            "args": args,
+            "cmd": payload.get("cmd"),
+            "exit_code": exit_code,
+            "duration_ms": payload.get("duration_ms"),
+        }));
+    }
+    Ok(out)
+}
+
+/// Resolve `since` (any string `date(1)` understands, e.g. "2 days ago" or
+/// an ISO date) to a Unix timestamp, best-effort.
+fn since_to_unix(since: &str) -> Option<u64> {
+    let out = Command::new("date")
+        .args(["--date", since, "+%s"])
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&out.stdout).trim().parse().ok()
+}
+
+/// Combined, newest-first history of DevIt commits and tool calls.
+pub fn collect(since: Option<&str>, tool: Option<&str>, failed: bool) -> Result<Vec<Value>> {
+    let mut entries = journal_tool_calls(tool, failed)?;
+    if let Some(since) = since {
+        if let Some(threshold) = since_to_unix(since) {
+            entries.retain(|e| e["ts"].as_u64().unwrap_or(0) >= threshold);
+        }
+    }
+    // Commits aren't tool calls and don't carry an exit code, so a --tool or
+    // --failed filter naturally excludes them.
+    if tool.is_none() && !failed {
+        entries.extend(devit_commits(since)?);
+    }
+    entries.sort_by(|a, b| b["ts"].as_u64().cmp(&a["ts"].as_u64()));
+    Ok(entries)
+}
@@ -0,0 +1,83 @@
+// # -----------------------------
+// # crates/cli/src/hooks.rs
+// # -----------------------------
+// User scripts registered under `[hooks]` in devit.toml, run at fixed
+// lifecycle points around `devit apply`/`devit run` with the patch/report
+// path passed as an env var. A failing `pre_apply` hook vetoes the apply.
+
+use devit_common::Config;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Write `patch` to a scratch file so hook scripts get a `DEVIT_PATCH` path
+/// rather than the diff inlined into an env var (which risks the OS's
+/// argv/env size limit on a large patch). Caller removes it once done.
+pub fn write_patch_tempfile(patch: &str) -> std::io::Result<PathBuf> {
+    let path = std::env::temp_dir().join(format!("devit-hook-patch-{}.diff", std::process::id()));
+    std::fs::write(&path, patch)?;
+    Ok(path)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Point {
+    PreApply,
+    PostCommit,
+    PostTest,
+}
+
+impl Point {
+    fn key(self) -> &'static str {
+        match self {
+            Point::PreApply => "pre_apply",
+            Point::PostCommit => "post_commit",
+            Point::PostTest => "post_test",
+        }
+    }
+}
+
+/// A hook script exited non-zero (or couldn't be spawned at all); enough
+/// detail to surface as a structured `--json` error via `exit_code::fail_with`.
+#[derive(Debug)]
+pub struct HookFailure {
+    pub point: &'static str,
+    pub command: String,
+    pub exit_code: i32,
+    pub stderr: String,
+}
+
+fn commands_for(cfg: &Config, point: Point) -> &[String] {
+    match point {
+        Point::PreApply => &cfg.hooks.pre_apply,
+        Point::PostCommit => &cfg.hooks.post_commit,
+        Point::PostTest => &cfg.hooks.post_test,
+    }
+}
+
+/// Run every hook registered for `point`, in order, stopping at the first
+/// failure. `env` supplies the lifecycle-specific paths (e.g. `DEVIT_PATCH`,
+/// `DEVIT_SHA`, `DEVIT_REPORT`) as env vars for the script.
+pub fn run(cfg: &Config, point: Point, env: &HashMap<&str, String>) -> Result<(), HookFailure> {
+    for cmd in commands_for(cfg, point) {
+        let mut command = Command::new("bash");
+        command.arg("-lc").arg(cmd);
+        for (k, v) in env {
+            command.env(k, v);
+        }
+        let out = command.output().map_err(|e| HookFailure {
+            point: point.key(),
+            command: cmd.clone(),
+            exit_code: 127,
+            stderr: e.to_string(),
+        })?;
+        if !out.status.success() {
+            return Err(HookFailure {
+                point: point.key(),
+                command: cmd.clone(),
+                exit_code: out.status.code().unwrap_or(1),
+                stderr: String::from_utf8_lossy(&out.stderr).to_string(),
+            });
+        }
+    }
+    Ok(())
+}
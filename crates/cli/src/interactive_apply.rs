@@ -0,0 +1,107 @@
+// # -----------------------------
+// # crates/cli/src/interactive_apply.rs
+// # -----------------------------
+// Hunk-level review for `devit apply --interactive`, reusing the TUI's
+// unified-diff parser.
+
+use anyhow::{Context, Result};
+use devit_tui::{parse_unified_diff, DiffFile};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+
+/// Prompt the user hunk-by-hunk and rebuild `patch` from only the accepted
+/// ones. Each hunk applies against the original file independently, so
+/// dropping the rejected ones needs no offset bookkeeping.
+pub fn select_hunks(patch: &str) -> Result<String> {
+    let files = parse_unified_diff(patch).map_err(|e| anyhow::anyhow!("diff invalide: {e}"))?;
+    // The patch itself is often piped in on stdin (`devit apply -` is the
+    // default), so prompts can't also read stdin — fall back to the
+    // controlling terminal, like `git add -p` does.
+    let mut prompt_in = open_prompt_reader()?;
+    let mut accept_rest = false;
+    let mut quit = false;
+    let mut out = String::new();
+
+    for file in &files {
+        let mut kept_hunks = Vec::new();
+        for hunk in &file.hunks {
+            let keep = if quit {
+                false
+            } else if accept_rest {
+                true
+            } else {
+                match prompt_hunk(prompt_in.as_mut(), file, &hunk.header)? {
+                    HunkChoice::Yes => true,
+                    HunkChoice::No => false,
+                    HunkChoice::AllRemaining => {
+                        accept_rest = true;
+                        true
+                    }
+                    HunkChoice::Quit => {
+                        quit = true;
+                        false
+                    }
+                }
+            };
+            if keep {
+                kept_hunks.push(hunk);
+            }
+        }
+        if kept_hunks.is_empty() {
+            continue;
+        }
+        for line in &file.header {
+            out.push_str(line);
+            out.push('\n');
+        }
+        for hunk in kept_hunks {
+            out.push_str(&hunk.header);
+            out.push('\n');
+            for line in &hunk.lines {
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+    }
+    Ok(out)
+}
+
+enum HunkChoice {
+    Yes,
+    No,
+    AllRemaining,
+    Quit,
+}
+
+/// Open the controlling terminal for prompts, falling back to stdin when
+/// there isn't one (e.g. tests piping answers directly on stdin alongside a
+/// patch file rather than "-").
+fn open_prompt_reader() -> Result<Box<dyn BufRead>> {
+    let tty_path = if cfg!(windows) { "CONIN$" } else { "/dev/tty" };
+    match File::open(tty_path) {
+        Ok(f) => Ok(Box::new(BufReader::new(f))),
+        Err(_) => Ok(Box::new(BufReader::new(io::stdin()))),
+    }
+}
+
+fn prompt_hunk(input: &mut dyn BufRead, file: &DiffFile, hunk_header: &str) -> Result<HunkChoice> {
+    loop {
+        eprint!(
+            "{} {} — apply this hunk? [y,n,a,q] ",
+            file.display_name, hunk_header
+        );
+        io::stderr().flush().ok();
+        let mut buf = String::new();
+        let n = input.read_line(&mut buf).context("lecture de la réponse")?;
+        if n == 0 {
+            anyhow::bail!("entrée interactive fermée avant la fin de la sélection des hunks");
+        }
+        match buf.trim() {
+            "y" | "Y" => return Ok(HunkChoice::Yes),
+            "n" | "N" => return Ok(HunkChoice::No),
+            "a" | "A" => return Ok(HunkChoice::AllRemaining),
+            "q" | "Q" => return Ok(HunkChoice::Quit),
+            _ => eprintln!("Réponse invalide, utilise y/n/a/q."),
+        }
+    }
+}
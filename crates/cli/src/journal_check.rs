@@ -0,0 +1,180 @@
+// # -----------------------------
+// # crates/cli/src/journal_check.rs
+// # -----------------------------
+// Backing implementation for `devit journal verify`: recomputes the HMAC
+// `journal_event` (main.rs) stamps on every line and flags anything that
+// doesn't check out.
+
+use anyhow::{Context, Result};
+use devit_common::Event;
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use serde_json::json;
+use sha2::Sha256;
+use std::fs;
+use std::path::Path;
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Deserialize)]
+struct JournalRecord {
+    ts: u64,
+    event: Event,
+    sig: String,
+}
+
+/// Verify every line of the journal at `path`, recomputing its HMAC with
+/// `key`. Never fails on a bad journal — the returned report says whether it
+/// passed; the caller decides the process exit code.
+pub fn verify(path: &Path, key: Option<&[u8]>) -> Result<serde_json::Value> {
+    if !path.exists() {
+        return Ok(json!({"ok": true, "total": 0, "tampered": [], "unsigned": [], "gaps": []}));
+    }
+    let text = fs::read_to_string(path).with_context(|| format!("unable to read {}", path.display()))?;
+
+    let mut tampered = Vec::new();
+    let mut unsigned = Vec::new();
+    let mut gaps = Vec::new();
+    let mut verified = 0usize;
+    let mut total = 0usize;
+    let mut prev_ts: Option<u64> = None;
+
+    for (idx, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let lineno = idx + 1;
+        total += 1;
+
+        let raw: serde_json::Value = match serde_json::from_str(line) {
+            Ok(v) => v,
+            Err(e) => {
+                tampered.push(json!({"line": lineno, "reason": format!("invalid JSON: {e}")}));
+                continue;
+            }
+        };
+        if raw.get("sig").and_then(|v| v.as_str()).is_none() {
+            unsigned.push(json!({"line": lineno, "ts": raw.get("ts")}));
+            continue;
+        }
+
+        let rec: JournalRecord = match serde_json::from_value(raw) {
+            Ok(r) => r,
+            Err(e) => {
+                tampered.push(json!({"line": lineno, "reason": format!("unreadable event: {e}")}));
+                continue;
+            }
+        };
+
+        if let Some(prev) = prev_ts {
+            if rec.ts < prev {
+                gaps.push(json!({"line": lineno, "prev_ts": prev, "ts": rec.ts}));
+            }
+        }
+        prev_ts = Some(rec.ts);
+
+        let Some(key) = key else {
+            tampered.push(json!({"line": lineno, "ts": rec.ts, "reason": "no hmac key available to verify against"}));
+            continue;
+        };
+        let ev_json = serde_json::to_vec(&rec.event)?;
+        let mut mac = HmacSha256::new_from_slice(key).expect("HMAC key");
+        mac.update(&ev_json);
+        let expected = hex::encode(mac.finalize().into_bytes());
+        if expected == rec.sig {
+            verified += 1;
+        } else {
+            tampered.push(json!({"line": lineno, "ts": rec.ts, "reason": "signature mismatch"}));
+        }
+    }
+
+    let ok = tampered.is_empty() && unsigned.is_empty() && gaps.is_empty();
+    Ok(json!({
+        "ok": ok,
+        "total": total,
+        "verified": verified,
+        "tampered": tampered,
+        "unsigned": unsigned,
+        "gaps": gaps,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &[u8] = b"test-hmac-key";
+
+    fn signed_line(ts: u64, event: &Event) -> String {
+        let ev_json = serde_json::to_vec(event).unwrap();
+        let mut mac = HmacSha256::new_from_slice(KEY).unwrap();
+        mac.update(&ev_json);
+        let sig = hex::encode(mac.finalize().into_bytes());
+        serde_json::to_string(&json!({"ts": ts, "event": event, "sig": sig})).unwrap()
+    }
+
+    #[test]
+    fn missing_journal_is_ok() {
+        let report = verify(Path::new("/nonexistent/devit/journal.jsonl"), Some(KEY)).unwrap();
+        assert_eq!(report["ok"], json!(true));
+        assert_eq!(report["total"], json!(0));
+    }
+
+    #[test]
+    fn verifies_correctly_signed_lines() {
+        let ev = Event::Attest { hash: "deadbeef".into() };
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.jsonl");
+        fs::write(&path, signed_line(1, &ev) + "\n").unwrap();
+        let report = verify(&path, Some(KEY)).unwrap();
+        assert_eq!(report["ok"], json!(true));
+        assert_eq!(report["verified"], json!(1));
+        assert_eq!(report["tampered"], json!([]));
+    }
+
+    #[test]
+    fn flags_tampered_signature() {
+        let ev = Event::Attest { hash: "deadbeef".into() };
+        let mut line = signed_line(1, &ev);
+        line = line.replace("deadbeef", "c0ffee00");
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.jsonl");
+        fs::write(&path, line + "\n").unwrap();
+        let report = verify(&path, Some(KEY)).unwrap();
+        assert_eq!(report["ok"], json!(false));
+        assert_eq!(report["tampered"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn flags_unsigned_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.jsonl");
+        fs::write(&path, r#"{"ts": 1, "event": {"type":"Info","message":"hi"}}"#.to_string() + "\n").unwrap();
+        let report = verify(&path, Some(KEY)).unwrap();
+        assert_eq!(report["ok"], json!(false));
+        assert_eq!(report["unsigned"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn flags_timestamp_gap() {
+        let ev = Event::Attest { hash: "deadbeef".into() };
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.jsonl");
+        let text = format!("{}\n{}\n", signed_line(10, &ev), signed_line(5, &ev));
+        fs::write(&path, text).unwrap();
+        let report = verify(&path, Some(KEY)).unwrap();
+        assert_eq!(report["ok"], json!(false));
+        assert_eq!(report["gaps"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn no_key_marks_signed_lines_tampered() {
+        let ev = Event::Attest { hash: "deadbeef".into() };
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.jsonl");
+        fs::write(&path, signed_line(1, &ev) + "\n").unwrap();
+        let report = verify(&path, None).unwrap();
+        assert_eq!(report["ok"], json!(false));
+        assert_eq!(report["tampered"].as_array().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,143 @@
+use std::fs;
+use std::path::Path;
+
+/// One normalized test result, framework-agnostic.
+#[derive(Debug, Clone)]
+pub struct Case {
+    /// Stable identifier (`<suite>::<name>`), so the same test keeps the
+    /// same ID across reruns regardless of which framework produced it.
+    pub id: String,
+    pub name: String,
+    pub passed: bool,
+}
+
+impl Case {
+    pub fn new(suite: &str, name: impl Into<String>, passed: bool) -> Self {
+        let name = name.into();
+        Self {
+            id: format!("{suite}::{name}"),
+            name,
+            passed,
+        }
+    }
+}
+
+/// Write `cases` as a single-schema JUnit report: every framework's runner
+/// (cargo/go/dotnet/pytest/...) normalizes its own dialect into `Case`s and
+/// calls this instead of hand-rolling XML, so `.devit/reports/junit.xml`
+/// always has the same shape for [`crate::report::read_junit`] and `devit
+/// quality gate` to parse regardless of which framework ran.
+pub fn write(path: &Path, suite_name: &str, cases: &[Case], dur_ms: u128) {
+    let failures = cases.iter().filter(|c| !c.passed).count();
+    let mut body = String::new();
+    for c in cases {
+        if c.passed {
+            body.push_str(&format!(
+                "    <testcase id=\"{}\" classname=\"{}\" name=\"{}\"/>\n",
+                xml_escape(&c.id),
+                xml_escape(suite_name),
+                xml_escape(&c.name)
+            ));
+        } else {
+            body.push_str(&format!(
+                "    <testcase id=\"{}\" classname=\"{}\" name=\"{}\"><failure/></testcase>\n",
+                xml_escape(&c.id),
+                xml_escape(suite_name),
+                xml_escape(&c.name)
+            ));
+        }
+    }
+    let content = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites>
+  <testsuite name="{}" tests="{}" failures="{}" time="{}">
+{}  </testsuite>
+</testsuites>
+"#,
+        xml_escape(suite_name),
+        cases.len(),
+        failures,
+        (dur_ms as f64) / 1000.0,
+        body
+    );
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let _ = fs::write(path, content);
+}
+
+/// Write a report that only knows aggregate pass/fail counts, not
+/// individual case names (best-effort frameworks like npm/ctest) — still
+/// goes through the same schema, just with synthetic per-slot case names.
+pub fn write_counts(path: &Path, suite_name: &str, ran: u32, failed: u32, dur_ms: u128) {
+    let cases: Vec<Case> = (0..ran)
+        .map(|i| Case::new(suite_name, format!("case-{i}"), i >= failed))
+        .collect();
+    write(path, suite_name, &cases, dur_ms);
+}
+
+/// Best-effort extraction of `(name, passed)` pairs from an arbitrary
+/// existing JUnit XML file (e.g. one `pytest --junitxml` just wrote in its
+/// own dialect) — a `<testcase ... name="...">` line is a pass unless
+/// immediately followed by a `<failure`/`<error` line before the closing
+/// `</testcase>`.
+pub fn parse_foreign(path: &Path) -> Vec<(String, bool)> {
+    let content = fs::read_to_string(path).unwrap_or_default();
+    let mut out = Vec::new();
+    let mut pending: Option<String> = None;
+    for raw in content.lines() {
+        let line = raw.trim_start();
+        if line.starts_with("<testcase") {
+            if let Some(prev) = pending.take() {
+                out.push((prev, true));
+            }
+            pending = Some(
+                attr(line, "name")
+                    .or_else(|| attr(line, "classname"))
+                    .unwrap_or_else(|| "unknown".to_string()),
+            );
+            if line.contains("/>") {
+                out.push((pending.take().unwrap(), true));
+            }
+        } else if let Some(name) = &pending {
+            if line.starts_with("<failure") || line.starts_with("<error") {
+                out.push((name.clone(), false));
+                pending = None;
+            } else if line.starts_with("</testcase>") {
+                out.push((name.clone(), true));
+                pending = None;
+            }
+        }
+    }
+    if let Some(prev) = pending {
+        out.push((prev, true));
+    }
+    out
+}
+
+fn attr(line: &str, key: &str) -> Option<String> {
+    let pat = format!("{key}=\"");
+    let i = line.find(&pat)?;
+    let rest = &line[i + pat.len()..];
+    let j = rest.find('"')?;
+    Some(rest[..j].to_string())
+}
+
+/// Re-read a JUnit file a framework's own tooling just wrote (e.g. pytest's
+/// native dialect) and rewrite it in the normalized schema — folds a
+/// framework's dialect back into the same shape as every other one.
+pub fn normalize_in_place(path: &Path, suite_name: &str, dur_ms: u128) {
+    let results = parse_foreign(path);
+    let cases: Vec<Case> = results
+        .into_iter()
+        .map(|(name, passed)| Case::new(suite_name, name, passed))
+        .collect();
+    write(path, suite_name, &cases, dur_ms);
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,151 @@
+// # -----------------------------
+// # crates/cli/src/licenses.rs
+// # -----------------------------
+// `devit report licenses`: inventory dependency licenses (cargo metadata,
+// package-lock.json) and flag any that violate the `[licenses]` allow/deny
+// policy in config -- same minimal-inventory approach as [`crate::sbom`].
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LicenseEntry {
+    pub name: String,
+    pub version: String,
+    pub license: Option<String>,
+    pub group: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Violation {
+    pub name: String,
+    pub version: String,
+    pub license: Option<String>,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LicenseReport {
+    pub entries: Vec<LicenseEntry>,
+    pub violations: Vec<Violation>,
+}
+
+/// Rust deps via `cargo metadata` -- licenses are read straight from each
+/// crate's `Cargo.toml` `license` field (no SPDX-expression parsing).
+fn collect_cargo() -> Vec<LicenseEntry> {
+    let Ok(output) = Command::new("cargo")
+        .args(["metadata", "--format-version=1"])
+        .output()
+    else {
+        return Vec::new();
+    };
+    let Ok(v) = serde_json::from_slice::<serde_json::Value>(&output.stdout) else {
+        return Vec::new();
+    };
+    v.get("packages")
+        .and_then(|p| p.as_array())
+        .cloned()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|p| LicenseEntry {
+            name: p
+                .get("name")
+                .and_then(|x| x.as_str())
+                .unwrap_or("")
+                .to_string(),
+            version: p
+                .get("version")
+                .and_then(|x| x.as_str())
+                .unwrap_or("")
+                .to_string(),
+            license: p
+                .get("license")
+                .and_then(|x| x.as_str())
+                .map(|s| s.to_string()),
+            group: "rust".to_string(),
+        })
+        .collect()
+}
+
+/// JS deps via `package-lock.json` -- npm v7+ lockfiles record a `license`
+/// field per entry in `packages` when the installed package declares one.
+fn collect_npm() -> Vec<LicenseEntry> {
+    let Ok(s) = std::fs::read_to_string("package-lock.json") else {
+        return Vec::new();
+    };
+    let Ok(v) = serde_json::from_str::<serde_json::Value>(&s) else {
+        return Vec::new();
+    };
+    let Some(packages) = v.get("packages").and_then(|p| p.as_object()) else {
+        return Vec::new();
+    };
+    packages
+        .iter()
+        .filter(|(key, _)| !key.is_empty())
+        .map(|(key, info)| {
+            let name = key
+                .rsplit("node_modules/")
+                .next()
+                .unwrap_or(key)
+                .to_string();
+            LicenseEntry {
+                name,
+                version: info
+                    .get("version")
+                    .and_then(|x| x.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                license: info
+                    .get("license")
+                    .and_then(|x| x.as_str())
+                    .map(|s| s.to_string()),
+                group: "npm".to_string(),
+            }
+        })
+        .collect()
+}
+
+fn check_violations(entries: &[LicenseEntry], cfg: &devit_common::LicensesCfg) -> Vec<Violation> {
+    entries
+        .iter()
+        .filter_map(|e| {
+            let reason = match &e.license {
+                None => Some("license unknown".to_string()),
+                Some(lic) => {
+                    if cfg.deny.iter().any(|d| d.eq_ignore_ascii_case(lic)) {
+                        Some(format!("license denied: {lic}"))
+                    } else if !cfg.allow.is_empty() && !cfg.allow.iter().any(|a| a.eq_ignore_ascii_case(lic)) {
+                        Some(format!("license not in allow list: {lic}"))
+                    } else {
+                        None
+                    }
+                }
+            }?;
+            Some(Violation {
+                name: e.name.clone(),
+                version: e.version.clone(),
+                license: e.license.clone(),
+                reason,
+            })
+        })
+        .collect()
+}
+
+/// Inventory licenses, check them against `cfg`, and write the combined
+/// report to `out`.
+pub fn report(cfg: &devit_common::LicensesCfg, out: &Path) -> Result<LicenseReport> {
+    let mut entries = collect_cargo();
+    entries.extend(collect_npm());
+    let violations = check_violations(&entries, cfg);
+    let report = LicenseReport { entries, violations };
+    if let Some(dir) = out.parent() {
+        std::fs::create_dir_all(dir).ok();
+    }
+    std::fs::write(
+        out,
+        serde_json::to_vec_pretty(&report).context("serialize license report")?,
+    )?;
+    Ok(report)
+}
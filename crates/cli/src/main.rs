@@ -4,15 +4,36 @@
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
 use devit_agent::Agent;
+use devit_common::messages::{t, MsgKey};
 use devit_common::{Config, Event, PolicyCfg};
 use devit_sandbox as sandbox;
 use devit_tools::{codeexec, git};
+
+/// Guards tests (in this crate's own `#[cfg(test)]` modules) that call
+/// `std::env::set_current_dir` -- every such test runs in the same process,
+/// so without this they could race each other's working directory.
+#[cfg(test)]
+pub(crate) static CWD_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+mod clippy_sarif;
 mod commit_msg;
+mod complexity;
+mod coverage;
+mod deadcode;
+mod git_hooks;
+mod junit;
+mod licenses;
 mod merge_assist;
+mod plugins;
 mod precommit;
+mod quality_history;
 mod recipes;
 mod report;
+mod rerere;
+mod secrets_scan;
+mod test_history;
 mod test_runner;
+mod watch;
 use hmac::{Hmac, Mac};
 use rand::RngCore;
 use recipes::{list_recipes, run_recipe, RecipeRunError};
@@ -22,13 +43,33 @@ use std::io::{stdin, Read, Write};
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 mod context;
+mod autostash;
+mod blame;
+mod checkpoint;
+mod clean;
+mod config_check;
+mod diff_preview;
+mod exit_code;
+mod explain_patch;
+mod github;
+mod gitlab;
+mod history;
+mod hooks;
+mod interactive_apply;
+mod journal_check;
+mod patch_filter;
+mod progress;
 mod sbom;
+mod status;
 
 #[derive(Parser, Debug)]
 #[command(name = "devit", version, about = "DevIt CLI - patch-only agent", long_about = None)]
 struct Cli {
     #[arg(long = "json-only", alias = "quiet-json", global = true)]
     json_only: bool,
+    /// Emit one tool.result/tool.error JSON document on stdout; human text goes to stderr
+    #[arg(long = "json", global = true)]
+    json: bool,
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -42,6 +83,18 @@ enum Commands {
         /// Goal to achieve (e.g., "add websocket support")
         #[arg(short, long)]
         goal: String,
+        /// Write the generated diff to this file instead of stdout
+        #[arg(long)]
+        out: Option<String>,
+        /// Pipe the generated diff straight into the apply pipeline
+        #[arg(long)]
+        apply: bool,
+        /// Auto-approve the apply step (no prompt); ignored without --apply
+        #[arg(long)]
+        yes: bool,
+        /// Continue even if worktree/index is dirty; ignored without --apply
+        #[arg(long)]
+        force: bool,
     },
 
     /// Apply a unified diff to the workspace
@@ -55,6 +108,97 @@ enum Commands {
         /// Continue even if worktree/index is dirty (try 3-way)
         #[arg(long)]
         force: bool,
+        /// Review each hunk (y/n/a/q) and apply only the accepted subset
+        #[arg(long)]
+        interactive: bool,
+        /// Render the diff with colors, per-file stats and word-level
+        /// highlighting before the approval prompt, instead of a file list
+        #[arg(long)]
+        preview: bool,
+        /// Stash a dirty worktree instead of requiring --force, then
+        /// restore it once the patch is applied/committed
+        #[arg(long)]
+        autostash: bool,
+        /// Apply to the worktree only, skip staging/commit entirely
+        #[arg(long, conflicts_with = "no_commit")]
+        worktree: bool,
+        /// Stage the patch but stop before committing
+        #[arg(long)]
+        no_commit: bool,
+        /// Keep only files matching these glob patterns (CSV, e.g. "src/**,crates/*/src/**")
+        #[arg(long)]
+        only: Option<String>,
+        /// Drop files matching these glob patterns (CSV), applied after --only
+        #[arg(long)]
+        exclude: Option<String>,
+        /// Apply every patch file in this directory, in order, one commit each
+        #[arg(long, conflicts_with_all = ["worktree", "no_commit", "interactive", "only", "exclude"])]
+        batch: Option<String>,
+        /// Group hunks by scope (crate/directory) and create one
+        /// Conventional Commit per group, each with its own generated
+        /// message and attestation
+        #[arg(long = "split-commits", conflicts_with_all = ["worktree", "no_commit", "interactive", "batch"])]
+        split_commits: bool,
+    },
+
+    /// Summarize a patch's semantic impact (which functions/types are
+    /// added, removed or modified) before approving it
+    ExplainPatch {
+        /// Read diff from file, or '-' for stdin (default)
+        #[arg(default_value = "-")]
+        input: String,
+        /// Ask the configured LLM backend for a short prose narrative on
+        /// top of the structured symbol diff
+        #[arg(long)]
+        narrate: bool,
+    },
+
+    /// Revert a DevIt-authored commit (verified via its DevIt-Attest footer)
+    Revert {
+        /// Revert HEAD instead of a specific commit
+        #[arg(long, conflicts_with = "sha")]
+        last: bool,
+        /// Commit to revert (full or abbreviated SHA)
+        sha: Option<String>,
+    },
+
+    /// Bisect a regression: drives `git bisect` through the sandboxed
+    /// executor, then asks the agent to explain the culprit commit's diff
+    Bisect {
+        /// Commit known to be bad (default: HEAD)
+        #[arg(long, default_value = "HEAD")]
+        bad: String,
+        /// Commit known to be good
+        #[arg(long)]
+        good: String,
+        /// Shell command that exits 0 on a good commit, non-zero on a bad one
+        #[arg(long = "test")]
+        test_cmd: String,
+    },
+
+    /// Restore the latest `devit run` checkpoint (worktree + untracked files)
+    Rollback,
+
+    /// Dashboard: worktree, pending reports, journal, quality gate, recipes, backend
+    Status,
+
+    /// Prune stale `.devit` artifacts (reports, context cache, checkpoint sessions)
+    Clean {
+        /// Old reports and rotated journal backups
+        #[arg(long)]
+        reports: bool,
+        /// The context index cache (`.devit/index.json`)
+        #[arg(long)]
+        cache: bool,
+        /// Checkpoint snapshots and merge backups
+        #[arg(long)]
+        sessions: bool,
+        /// All of the above
+        #[arg(long)]
+        all: bool,
+        /// Preview what would be removed without deleting anything
+        #[arg(long = "dry-run", default_value_t = false)]
+        dry_run: bool,
     },
 
     /// Chain: suggest -> (approval) -> apply -> commit -> test
@@ -71,6 +215,9 @@ enum Commands {
         /// Continue even if worktree/index is dirty (try 3-way)
         #[arg(long)]
         force: bool,
+        /// Stage the patch but stop before committing/testing
+        #[arg(long)]
+        no_commit: bool,
     },
 
     /// Run tests according to detected stack (Cargo/npm/CMake)
@@ -103,26 +250,10 @@ enum Commands {
         action: CtxCmd,
     },
 
-    /// Generate Conventional Commit message from staged or diff
+    /// Generate a Conventional Commit message, or lint one (commit-msg hook)
     CommitMsg {
-        /// Use staged changes (git diff --cached)
-        #[arg(long = "from-staged", default_value_t = true)]
-        from_staged: bool,
-        /// Or compare from this ref to HEAD
-        #[arg(long = "from-ref")]
-        from_ref: Option<String>,
-        /// Force type (feat|fix|refactor|docs|test|chore|perf|ci)
-        #[arg(long = "type")]
-        typ: Option<String>,
-        /// Force scope (path or token)
-        #[arg(long)]
-        scope: Option<String>,
-        /// Write to .git/COMMIT_EDITMSG instead of stdout
-        #[arg(long)]
-        write: bool,
-        /// Include a small body template
-        #[arg(long = "with-template")]
-        with_template: bool,
+        #[command(subcommand)]
+        action: CommitMsgCmd,
     },
 
     /// Export reports (SARIF / JUnit)
@@ -143,12 +274,73 @@ enum Commands {
         action: MergeCmd,
     },
 
+    /// Rebase assistance
+    Rebase {
+        #[command(subcommand)]
+        action: RebaseCmd,
+    },
+
     /// Generate SBOM (CycloneDX JSON)
     Sbom {
         #[command(subcommand)]
         action: SbomCmd,
     },
 
+    /// Secrets scanning (built-in regex/entropy detectors)
+    Scan {
+        #[command(subcommand)]
+        action: ScanCmd,
+    },
+
+    /// Validate/inspect devit.toml
+    Config {
+        #[command(subcommand)]
+        action: ConfigCmd,
+    },
+
+    /// Install/remove git hooks that run the precommit/commit-msg gates
+    Hooks {
+        #[command(subcommand)]
+        action: HooksCmd,
+    },
+
+    /// GitHub pull request integration
+    Pr {
+        #[command(subcommand)]
+        action: PrCmd,
+    },
+
+    /// GitLab merge request integration
+    Mr {
+        #[command(subcommand)]
+        action: MrCmd,
+    },
+
+    /// Generate shell completion script
+    Completions {
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+
+    /// Journal integrity checks
+    Journal {
+        #[command(subcommand)]
+        action: JournalCmd,
+    },
+
+    /// List DevIt-authored commits and tool calls (JSON)
+    History {
+        /// Only entries at/after this date (anything `date(1)` understands)
+        #[arg(long = "since")]
+        since: Option<String>,
+        /// Only tool calls for this tool (e.g. shell_exec)
+        #[arg(long = "tool")]
+        tool: Option<String>,
+        /// Only tool calls that exited non-zero
+        #[arg(long = "failed", default_value_t = false)]
+        failed: bool,
+    },
+
     /// Apply a patch via JSON API (parity with tool call)
     FsPatchApply {
         /// Read JSON from file or '-' for stdin
@@ -169,13 +361,46 @@ enum Commands {
     },
 }
 
+#[derive(Subcommand, Debug)]
+enum PrCmd {
+    /// Push the current branch and open a pull request via the GitHub API
+    Create {
+        /// Base branch to merge into
+        #[arg(long, default_value = "main")]
+        base: String,
+        /// PR title (defaults to HEAD's commit subject)
+        #[arg(long)]
+        title: Option<String>,
+        /// Open the pull request as a draft
+        #[arg(long)]
+        draft: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum MrCmd {
+    /// Push the current branch and open a merge request via the GitLab API
+    Create {
+        /// Target branch to merge into
+        #[arg(long, default_value = "main")]
+        target: String,
+        /// MR title (defaults to HEAD's commit subject)
+        #[arg(long)]
+        title: Option<String>,
+        /// Open the merge request as a draft
+        #[arg(long)]
+        draft: bool,
+    },
+}
+
 #[derive(Subcommand, Debug)]
 enum ToolCmd {
     /// List available tools (JSON)
     List,
     /// Call a tool
     Call {
-        /// Tool name (fs_patch_apply | shell_exec)
+        /// Tool name (fs_patch_apply | shell_exec), or '-' for a JSON request on stdin
+        #[arg(value_parser = clap::builder::PossibleValuesParser::new(["fs_patch_apply", "shell_exec", "-"]))]
         name: String,
         /// Read diff from file, or '-' for stdin (fs_patch_apply), or command for shell_exec after '--'
         #[arg(default_value = "-")]
@@ -189,6 +414,16 @@ enum ToolCmd {
         /// Only run precommit pipeline and exit (only for fs_patch_apply)
         #[arg(long = "precommit-only")]
         precommit_only: bool,
+        /// Force every precommit tool to re-run, ignoring `.devit/cache/precommit.json`
+        #[arg(long = "no-cache")]
+        no_cache: bool,
+        /// Run formatters/fixers (cargo fmt, eslint --fix, ruff --fix) and stage the result
+        #[arg(long)]
+        autofix: bool,
+        /// shell_exec only: evaluate policy and print what would happen,
+        /// without executing the command
+        #[arg(long)]
+        explain: bool,
     },
 }
 
@@ -202,6 +437,9 @@ enum RecipeCmd {
         id: String,
         #[arg(long = "dry-run", default_value_t = false)]
         dry_run: bool,
+        /// Override declared params (CSV, e.g. "name=value,other=42")
+        #[arg(long)]
+        param: Option<String>,
     },
 }
 
@@ -232,6 +470,45 @@ enum CtxCmd {
         /// Output JSON path (default: .devit/index.json)
         #[arg(long = "json-out")]
         json_out: Option<PathBuf>,
+        /// Write a compact NDJSON index (+ .offsets sidecar) instead of
+        /// pretty JSON — for 100k+ file repos where the pretty document
+        /// gets too large to parse in one go. Ignored if --json-out
+        /// already ends in .ndjson.
+        #[arg(long, default_value_t = false)]
+        compact: bool,
+        /// Keep the index up to date via filesystem notifications instead
+        /// of exiting after the first build (Ctrl-C to stop).
+        #[arg(long, default_value_t = false)]
+        watch: bool,
+    },
+    /// Show per-region authorship/recency for a file (`git blame`, grouped)
+    Blame {
+        file: String,
+    },
+    /// Semantic search over the context index (embedding similarity + score)
+    Search {
+        query: String,
+        /// Max results to return (default: 20)
+        #[arg(long, default_value_t = 20)]
+        top: usize,
+    },
+    /// List a file's top-level symbols with their line ranges
+    Symbols {
+        path: String,
+    },
+    /// Pack the most relevant files for a goal into a prompt-ready bundle
+    Pack {
+        #[arg(long)]
+        goal: String,
+        /// Token budget for the bundle (default: 8000)
+        #[arg(long, default_value_t = context::DEFAULT_PACK_BUDGET)]
+        budget: usize,
+    },
+    /// Generate (or reuse cached) one-paragraph per-directory summaries
+    Summarize {
+        /// Root path (default: .)
+        #[arg(default_value = ".")]
+        path: String,
     },
 }
 
@@ -244,7 +521,7 @@ enum TestCmd {
         /// Compare from this git ref to HEAD to detect changes (optional)
         #[arg(long = "changed-from")]
         changed_from: Option<String>,
-        /// Framework: auto|cargo|npm|pnpm|pytest|ctest
+        /// Framework: auto|cargo|npm|pnpm|pytest|ctest|go|dotnet
         #[arg(long, default_value = "auto")]
         framework: String,
         /// Timeout seconds per run (default DEVIT_TIMEOUT_SECS or 300)
@@ -253,6 +530,40 @@ enum TestCmd {
         /// Max jobs/threads (hint, not all frameworks use it)
         #[arg(long = "max-jobs")]
         max_jobs: Option<usize>,
+        /// Retries for an individually failing test before treating it as a
+        /// real failure (cargo only); a test that passes on retry is
+        /// recorded as flaky in `.devit/flaky_tests.txt` (default: 2)
+        #[arg(long = "retries")]
+        retries: Option<u32>,
+        /// Total number of shards for CI-matrix sharding (cargo only); each
+        /// shard writes its own `.devit/reports/junit-shard-<i>.xml` for the
+        /// CI job to merge. Ignored if `--max-jobs` requests local sharding.
+        #[arg(long = "shards")]
+        shards: Option<u32>,
+        /// Which shard (0-based) this invocation covers, out of `--shards`
+        #[arg(long = "shard-index")]
+        shard_index: Option<u32>,
+    },
+    /// Run coverage (llvm-cov/coverage.py/nyc per detected stack) and
+    /// report totals under `.devit/reports/coverage/`
+    Coverage {
+        /// Framework: auto|cargo|npm|pnpm|pytest
+        #[arg(long, default_value = "auto")]
+        framework: String,
+        /// Timeout seconds (default DEVIT_TIMEOUT_SECS or 300)
+        #[arg(long = "timeout-secs")]
+        timeout_secs: Option<u64>,
+    },
+    /// Watch the workspace and rerun only the impacted tests for files that
+    /// just changed, streaming pass/fail events to the journal so the TUI
+    /// shows them live
+    Watch {
+        /// Framework: auto|cargo|npm|pnpm|pytest|ctest|go|dotnet
+        #[arg(long, default_value = "auto")]
+        framework: String,
+        /// Timeout seconds per rerun (default DEVIT_TIMEOUT_SECS or 300)
+        #[arg(long = "timeout-secs")]
+        timeout_secs: Option<u64>,
     },
 }
 
@@ -262,6 +573,26 @@ enum ReportCmd {
         /// Source selector (currently supports: latest)
         #[arg(long = "from", default_value = "latest")]
         from: String,
+        /// Merge clippy/ESLint/ruff/custom SARIF files into one combined
+        /// `.devit/reports/sarif.json`, deduping results by fingerprint,
+        /// instead of just resolving `--from`.
+        #[arg(long = "merge")]
+        merge: bool,
+        /// Explicit SARIF files to merge (repeatable); defaults to
+        /// whichever of `.devit/reports/{clippy,eslint,ruff}.sarif.json`
+        /// exist when omitted. Only used with `--merge`.
+        #[arg(long = "input")]
+        inputs: Vec<String>,
+        /// Normalize an ESLint `--format json` file into
+        /// `.devit/reports/eslint.sarif.json` before resolving `--merge`/
+        /// `--from`, so JS findings reach the quality gate without manual
+        /// conversion.
+        #[arg(long = "ingest-eslint")]
+        ingest_eslint: Option<String>,
+        /// Normalize a ruff `--output-format=json` file into
+        /// `.devit/reports/ruff.sarif.json`, same as `--ingest-eslint`.
+        #[arg(long = "ingest-ruff")]
+        ingest_ruff: Option<String>,
     },
     Junit {
         /// Source selector (currently supports: latest)
@@ -277,6 +608,83 @@ enum ReportCmd {
         #[arg(long = "out", default_value = ".devit/reports/summary.md")]
         out: String,
     },
+    /// List the slowest tests and the most-regressed ones, from
+    /// `.devit/history/tests.jsonl` (populated by `devit test impacted`)
+    SlowTests {
+        /// How many tests to list per section
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+        #[arg(long = "json", default_value_t = false)]
+        json: bool,
+    },
+    /// Convert JUnit failures and SARIF results into GitHub annotations so
+    /// they show up inline on the PR diff.
+    GithubAnnotations {
+        #[arg(long = "junit", default_value = ".devit/reports/junit.xml")]
+        junit: String,
+        #[arg(long = "sarif", default_value = ".devit/reports/sarif.json")]
+        sarif: String,
+        /// `commands` prints `::error file=...,line=...::message` lines
+        /// straight to stdout (for use inside a workflow step); `checks`
+        /// writes a Checks API `annotations` JSON payload to `--out`.
+        #[arg(long = "format", default_value = "commands")]
+        format: String,
+        #[arg(long = "out", default_value = ".devit/reports/github_annotations.json")]
+        out: String,
+    },
+    /// Generate a concise markdown block (gate verdict, new findings, flaky
+    /// tests, coverage delta, attestation hashes) for posting as a single
+    /// sticky PR comment.
+    PrComment {
+        #[arg(long = "junit", default_value = ".devit/reports/junit.xml")]
+        junit: String,
+        #[arg(long = "sarif", default_value = ".devit/reports/sarif.json")]
+        sarif: String,
+        /// Config path with [quality] thresholds
+        #[arg(long = "config", default_value = ".devit/devit.toml")]
+        config: String,
+        #[arg(long = "out", default_value = ".devit/reports/pr_comment.md")]
+        out: String,
+    },
+    /// Run `cargo clippy --message-format=json` and convert its
+    /// diagnostics (rule IDs, messages, suggested fixes) into SARIF,
+    /// without depending on external `clippy-sarif`/`sarif-fmt` tools.
+    ClippySarif {
+        #[arg(long = "out", default_value = ".devit/reports/clippy.sarif.json")]
+        out: String,
+    },
+    /// Generate a CycloneDX SBOM from Cargo.lock/package-lock.json/
+    /// requirements.txt into `.devit/reports/sbom.json`, referenced from
+    /// commits' `DevIt-Attest` footer via a `DevIt-SBOM` line.
+    Sbom {
+        #[arg(long = "out", default_value = ".devit/reports/sbom.json")]
+        out: String,
+    },
+    /// Inventory dependency licenses (cargo metadata, package-lock.json)
+    /// against the `[licenses]` allow/deny policy in config.
+    Licenses {
+        /// Config path with the `[licenses]` policy
+        #[arg(long = "config", default_value = ".devit/devit.toml")]
+        config: String,
+        #[arg(long = "out", default_value = ".devit/reports/licenses.json")]
+        out: String,
+    },
+    /// Cyclomatic complexity / length per top-level function, via the
+    /// tree-sitter symbol scan also used by `devit context symbols`.
+    Complexity {
+        #[arg(long = "root", default_value = ".")]
+        root: String,
+        #[arg(long = "out", default_value = ".devit/reports/complexity.json")]
+        out: String,
+    },
+    /// Public items whose name has no other reference in the repo --
+    /// candidates for a cleanup recipe, not a guaranteed-dead-code proof.
+    Deadcode {
+        #[arg(long = "root", default_value = ".")]
+        root: String,
+        #[arg(long = "out", default_value = ".devit/reports/deadcode.json")]
+        out: String,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -289,6 +697,92 @@ enum SbomCmd {
     },
 }
 
+#[derive(Subcommand, Debug)]
+enum ScanCmd {
+    /// Scan the currently staged diff for secrets (AWS keys, tokens,
+    /// private keys, high-entropy strings)
+    Secrets {
+        /// Scan an explicit patch file instead of `git diff --cached`
+        #[arg(long = "patch")]
+        patch: Option<String>,
+        #[arg(long = "out", default_value = ".devit/reports/secrets.sarif.json")]
+        out: String,
+        /// Print JSON findings
+        #[arg(long = "json", default_value_t = true)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum HooksCmd {
+    /// Write `pre-commit`/`commit-msg` hooks delegating to devit's gates
+    Install {
+        /// Overwrite existing hooks not managed by devit
+        #[arg(long)]
+        force: bool,
+    },
+    /// Remove devit-managed hooks previously written by `install`
+    Uninstall,
+}
+
+#[derive(Subcommand, Debug)]
+enum CommitMsgCmd {
+    /// Generate a Conventional Commit message from staged or diff
+    Generate {
+        /// Use staged changes (git diff --cached)
+        #[arg(long = "from-staged", default_value_t = true)]
+        from_staged: bool,
+        /// Or compare from this ref to HEAD
+        #[arg(long = "from-ref")]
+        from_ref: Option<String>,
+        /// Force type (feat|fix|refactor|docs|test|chore|perf|ci)
+        #[arg(long = "type")]
+        typ: Option<String>,
+        /// Force scope (path or token)
+        #[arg(long)]
+        scope: Option<String>,
+        /// Write to .git/COMMIT_EDITMSG instead of stdout
+        #[arg(long)]
+        write: bool,
+        /// Include a small body template
+        #[arg(long = "with-template")]
+        with_template: bool,
+    },
+    /// Validate a commit message file against `[commit]`'s Conventional
+    /// Commits rules (syntax, `max_subject`, `types`, `allowed_scopes`)
+    Lint {
+        #[arg(long = "file", default_value = ".git/COMMIT_EDITMSG")]
+        file: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigCmd {
+    /// Check devit.toml for unknown sections (warnings) and deserialization
+    /// errors (with line/column info), without applying it
+    Validate {
+        #[arg(long = "config", default_value = "devit.toml")]
+        config: String,
+    },
+    /// Print the config, resolved with defaults applied
+    Show {
+        #[arg(long = "config", default_value = "devit.toml")]
+        config: String,
+        /// Print the fully-resolved config (currently the only supported mode)
+        #[arg(long = "effective", default_value_t = false)]
+        effective: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum JournalCmd {
+    /// Recompute the HMAC of every journal line and report tampering/gaps
+    Verify {
+        #[arg(long = "path", default_value = ".devit/journal.jsonl")]
+        path: String,
+    },
+}
+
 #[derive(Subcommand, Debug)]
 enum QualityCmd {
     Gate {
@@ -302,6 +796,28 @@ enum QualityCmd {
         /// Print JSON summary
         #[arg(long = "json", default_value_t = true)]
         json: bool,
+        /// Only fail on failures/lints not already present in the
+        /// `devit quality baseline` snapshot -- lets legacy repos adopt the
+        /// gate without fixing pre-existing debt first.
+        #[arg(long = "against-baseline", default_value_t = false)]
+        against_baseline: bool,
+    },
+    /// Snapshot current JUnit failures and SARIF findings to
+    /// `.devit/quality_baseline.json`, for `quality gate --against-baseline`.
+    Baseline {
+        #[arg(long = "junit", default_value = ".devit/reports/junit.xml")]
+        junit: String,
+        #[arg(long = "sarif", default_value = ".devit/reports/sarif.json")]
+        sarif: String,
+    },
+    /// Flag statistically notable regressions (tests failed, lint counts,
+    /// coverage, duration) across the last N `quality gate` runs recorded
+    /// in `.devit/history/quality.jsonl`.
+    Trend {
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+        #[arg(long = "json", default_value_t = false)]
+        json: bool,
     },
 }
 
@@ -318,96 +834,509 @@ enum MergeCmd {
         #[arg(long = "plan")]
         plan: String,
     },
-    /// One-shot resolve: explain -> auto plan -> apply
+    /// One-shot resolve: explain -> plan -> apply. `--strategy llm` asks the
+    /// LLM for a resolution per hunk instead of the "auto" heuristic.
     Resolve {
         #[arg(long = "strategy", default_value = "auto")]
         strategy: String,
     },
 }
 
+#[derive(Subcommand, Debug)]
+enum RebaseCmd {
+    /// Drive an interrupted `git rebase` to completion: per stopped commit,
+    /// explain its conflicts, propose a plan (LLM/AST/rerere-backed, same as
+    /// `devit merge explain`/`apply`), run impacted tests, then continue --
+    /// with an approval checkpoint before each commit.
+    Assist {
+        /// Auto-approve every checkpoint (no prompt)
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> std::process::ExitCode {
+    let cli = Cli::parse();
+    let json = cli.json;
+    match run(cli).await {
+        Ok(()) => std::process::ExitCode::SUCCESS,
+        Err(e) => {
+            let (exit, code, details) = exit_code::describe(&e);
+            if json {
+                let mut payload = serde_json::json!({
+                    "type": "tool.error",
+                    "error": format!("{e:#}"),
+                    "code": code,
+                });
+                if let Some(details) = details {
+                    payload["details"] = details;
+                }
+                let _ = emit_json(&payload);
+            } else {
+                eprintln!("Error: {e:?}");
+            }
+            std::process::ExitCode::from(exit.code())
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
     tracing_subscriber::fmt().with_env_filter("info").init();
 
-    let cli = Cli::parse();
+    let json = cli.json;
+    if let Some(Commands::Completions { shell }) = &cli.command {
+        let mut cmd = <Cli as clap::CommandFactory>::command();
+        let name = cmd.get_name().to_string();
+        clap_complete::generate(*shell, &mut cmd, name, &mut std::io::stdout());
+        return Ok(());
+    }
+    if let Some(Commands::Journal { action }) = &cli.command {
+        return match action {
+            JournalCmd::Verify { path } => {
+                let key_path = Path::new(".devit/hmac.key");
+                let key = if key_path.exists() {
+                    Some(fs::read(key_path)?)
+                } else {
+                    None
+                };
+                let report = journal_check::verify(Path::new(path), key.as_deref())?;
+                emit_json(&report)?;
+                if report["ok"].as_bool() != Some(true) {
+                    anyhow::bail!("devit journal verify: integrity check failed");
+                }
+                Ok(())
+            }
+        };
+    }
+    if let Some(Commands::History { since, tool, failed }) = &cli.command {
+        let entries = history::collect(since.as_deref(), tool.as_deref(), *failed)?;
+        return emit_json(&serde_json::Value::Array(entries));
+    }
+    // `config validate`/`config show` must work even when devit.toml is
+    // broken, so they run before the eager `load_cfg` below.
+    if let Some(Commands::Config { action }) = &cli.command {
+        return match action {
+            ConfigCmd::Validate { config } => {
+                let report = config_check::validate(config)?;
+                emit_json(&report)
+            }
+            ConfigCmd::Show { config, effective } => {
+                if !effective {
+                    anyhow::bail!("devit config show: seul --effective est pris en charge");
+                }
+                let cfg = config_check::effective(config)?;
+                emit_json(&serde_json::to_value(&cfg)?)
+            }
+        };
+    }
     let cfg: Config = load_cfg("devit.toml").context("load config")?;
+    if std::env::var("DEVIT_LANG").is_err() {
+        if let Some(lang) = &cfg.i18n.lang {
+            std::env::set_var("DEVIT_LANG", lang);
+        }
+    }
     let agent = Agent::new(cfg.clone());
     let json_only = cli.json_only;
 
     match cli.command {
-        Some(Commands::Suggest { path, goal }) => {
-            let ctx = collect_context(&path)?;
+        Some(Commands::Suggest {
+            path,
+            goal,
+            out,
+            apply,
+            yes,
+            force,
+        }) => {
+            let ctx_progress = progress::Progress::start(json, "context");
+            let ctx = collect_context(&path, &goal, &agent, &cfg).await?;
+            ctx_progress.finish();
+            let llm_progress = progress::Progress::start(json, "llm");
             let diff = agent.suggest_patch(&goal, &ctx).await?;
-            println!("{}", diff);
+            llm_progress.finish();
+            if let Some(out_path) = &out {
+                fs::write(out_path, &diff)
+                    .with_context(|| format!("écriture de l'artifact {out_path}"))?;
+                eprintln!("✅ Diff écrit dans {out_path}");
+            } else {
+                println!("{}", diff);
+            }
+            if apply {
+                ensure_git_repo()?;
+                if cfg.policy.sandbox.to_lowercase() == "read-only" {
+                    anyhow::bail!(
+                        "policy.sandbox=read-only: apply refusé (aucune écriture autorisée)"
+                    );
+                }
+                if diff.trim().is_empty() {
+                    anyhow::bail!("Le backend n'a pas produit de diff, rien à appliquer.");
+                }
+                run_apply_pipeline(
+                    &diff, &cfg, yes, force, false, false, Some(&goal), json, false, false,
+                )?;
+            }
         }
-        Some(Commands::Apply { input, yes, force }) => {
+        Some(Commands::Apply {
+            input,
+            yes,
+            force,
+            interactive,
+            preview,
+            autostash,
+            worktree,
+            no_commit,
+            only,
+            exclude,
+            batch,
+            split_commits,
+        }) => {
             ensure_git_repo()?;
             if cfg.policy.sandbox.to_lowercase() == "read-only" {
                 anyhow::bail!("policy.sandbox=read-only: apply refusé (aucune écriture autorisée)");
             }
+            if let Some(dir) = batch {
+                return apply_batch(&dir, &cfg, yes, force, json);
+            }
+            if split_commits {
+                return apply_split_commits(&input, only.as_deref(), exclude.as_deref(), &cfg, yes, force, json);
+            }
+            let mut patch = read_patch(&input)?;
+            if only.is_some() || exclude.is_some() {
+                patch = patch_filter::filter_patch(&patch, only.as_deref(), exclude.as_deref())?;
+                if patch.trim().is_empty() {
+                    anyhow::bail!("Aucun fichier ne correspond aux filtres --only/--exclude.");
+                }
+            }
+            if interactive {
+                patch = interactive_apply::select_hunks(&patch)?;
+                if patch.trim().is_empty() {
+                    anyhow::bail!("Aucun hunk accepté, rien à appliquer.");
+                }
+            }
+            run_apply_pipeline(
+                &patch, &cfg, yes, force, worktree, no_commit, None, json, preview, autostash,
+            )?;
+        }
+        Some(Commands::ExplainPatch { input, narrate }) => {
             let patch = read_patch(&input)?;
-            // 0) index propre ?
-            if !git::is_worktree_clean() && !force {
+            let files = explain_patch::analyze(&patch).map_err(|e| anyhow::anyhow!(e))?;
+            let mut data = serde_json::json!({ "files": files });
+            if narrate {
+                let summary = files
+                    .iter()
+                    .map(|f| {
+                        let syms = f
+                            .symbols
+                            .as_ref()
+                            .map(|s| {
+                                s.iter()
+                                    .map(|d| format!("{:?} {} {}", d.change, d.kind, d.name))
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            })
+                            .unwrap_or_else(|| "(pas d'index de symboles)".to_string());
+                        format!("{}: {}", f.path, syms)
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                let diff_head = patch.lines().take(120).collect::<Vec<_>>().join("\n");
+                let fut = agent.explain_patch(&summary, &diff_head);
+                if let Ok(Ok(narrative)) =
+                    tokio::time::timeout(std::time::Duration::from_secs(5), fut).await
+                {
+                    data["narrative"] = serde_json::Value::String(narrative);
+                }
+            }
+            let human = files
+                .iter()
+                .map(|f| {
+                    let syms = f
+                        .symbols
+                        .as_ref()
+                        .map(|s| {
+                            if s.is_empty() {
+                                "aucun changement de symbole détecté".to_string()
+                            } else {
+                                s.iter()
+                                    .map(|d| format!("{:?} {} {}", d.change, d.kind, d.name))
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            }
+                        })
+                        .unwrap_or_else(|| "index de symboles indisponible".to_string());
+                    format!("{}: {}", f.path, syms)
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            finish(json, &human, data)?;
+        }
+        Some(Commands::Revert { last, sha }) => {
+            ensure_git_repo()?;
+            let target = if last {
+                git::head_short()
+                    .ok_or_else(|| anyhow::anyhow!("impossible de résoudre HEAD"))?
+            } else {
+                sha.ok_or_else(|| anyhow::anyhow!("devit revert: précise --last ou un SHA"))?
+            };
+            let message = git::commit_message(&target)?;
+            let attest = message
+                .lines()
+                .find_map(|l| l.strip_prefix("DevIt-Attest: "))
+                .map(|h| h.trim().to_string())
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "commit {}: aucun footer DevIt-Attest, revert refusé",
+                        target
+                    )
+                })?;
+            if !journal_has_attest(&attest)? {
                 anyhow::bail!(
-                    "Le worktree ou l'index contient des modifications.\n\
-                     - Commit/stash tes changements OU relance avec --force (tentative 3-way)."
+                    "commit {}: footer DevIt-Attest ({}) introuvable dans .devit/journal.jsonl, revert refusé",
+                    target,
+                    attest
                 );
             }
-            // 1) dry-run
-            git::apply_check(&patch)?; // renvoie Err(...) avec le message Git détaillé
-            let ns = git::numstat(&patch)?;
-            let files = ns.len();
-            let added: u64 = ns.iter().map(|e| e.added).sum();
-            let deleted: u64 = ns.iter().map(|e| e.deleted).sum();
-            let summary = format!("{} fichier(s), +{}, -{}", files, added, deleted);
-            // 3) approval (sauf policy 'never' ou --yes)
-            let must_ask = !yes && cfg.policy.approval.to_lowercase() != "never";
-            if must_ask {
-                eprintln!("Patch prêt: {summary}");
-                for e in ns.iter().take(10) {
-                    eprintln!("  - {}", e.path);
+            git::revert_commit(&target)?;
+            let new_sha = git::head_short().unwrap_or_default();
+            journal_event(&Event::Revert {
+                reverted: target.clone(),
+                sha: new_sha.clone(),
+                hash: attest,
+            })?;
+            finish(
+                json,
+                &format!("✅ Revert {} -> {}", target, new_sha),
+                serde_json::json!({"reverted": target, "sha": new_sha}),
+            )?;
+        }
+        Some(Commands::Bisect { bad, good, test_cmd }) => {
+            ensure_git_repo()?;
+            let mut log = git::bisect_start(&bad, &good)?;
+            let culprit = loop {
+                if let Some(sha) = git::parse_bisect_culprit(&log) {
+                    break sha;
                 }
-                if ns.len() > 10 {
-                    eprintln!("  … ({} autres)", ns.len() - 10);
+                let (code, _out) = sandbox::run_shell_sandboxed_capture(
+                    &test_cmd,
+                    &cfg.policy,
+                    &cfg.sandbox,
+                    &cfg.secrets,
+                    None,
+                )?;
+                log = git::bisect_mark(code == 0)?;
+            };
+            let diff = git::commit_diff(&culprit).unwrap_or_default();
+            let files = explain_patch::analyze(&diff).unwrap_or_default();
+            let mut data = serde_json::json!({ "culprit": culprit, "files": files });
+            let diff_head = diff.lines().take(120).collect::<Vec<_>>().join("\n");
+            let symbol_summary = format!("Culprit commit: {culprit}");
+            let fut = agent.explain_patch(&symbol_summary, &diff_head);
+            if let Ok(Ok(narrative)) =
+                tokio::time::timeout(std::time::Duration::from_secs(5), fut).await
+            {
+                data["narrative"] = serde_json::Value::String(narrative);
+            }
+            finish(
+                json,
+                &format!("🔍 Premier commit fautif : {culprit} (repo laissé en HEAD détachée sur ce commit ; `git bisect reset` pour revenir)"),
+                data,
+            )?;
+        }
+        Some(Commands::Rollback) => {
+            ensure_git_repo()?;
+            let cp = checkpoint::latest()?;
+            checkpoint::restore(&cp)?;
+            journal_event(&Event::Info {
+                message: format!("rollback vers le checkpoint {} ({})", cp.id, cp.base_sha),
+            })?;
+            finish(
+                json,
+                &format!("✅ Rollback vers {} (checkpoint {})", cp.base_sha, cp.id),
+                serde_json::json!({"base_sha": cp.base_sha, "id": cp.id}),
+            )?;
+        }
+        Some(Commands::Status) => {
+            let st = status::collect(&cfg).await;
+            if json_only || json {
+                emit_json(&serde_json::to_value(&st)?)?;
+            } else {
+                println!(
+                    "Worktree: {}",
+                    if st.dirty {
+                        format!("dirty ({} fichier(s))", st.dirty_files.len())
+                    } else {
+                        "clean".to_string()
+                    }
+                );
+                println!("Reports en attente: {}", st.pending_reports.len());
+                for r in &st.pending_reports {
+                    println!("  - {r}");
                 }
-                if !ask_approval()? {
-                    anyhow::bail!("Annulé par l'utilisateur.");
+                println!("Derniers événements du journal: {}", st.last_events.len());
+                match &st.last_quality_verdict {
+                    Some(v) => println!(
+                        "Dernier gate qualité: {}",
+                        if v["pass"].as_bool() == Some(true) {
+                            "PASS"
+                        } else {
+                            "FAIL"
+                        }
+                    ),
+                    None => println!("Dernier gate qualité: n/a"),
                 }
+                println!("Recettes disponibles: {}", st.recipes.join(", "));
+                println!(
+                    "Backend ({}): {}",
+                    cfg.backend.base_url,
+                    if st.backend_reachable {
+                        "joignable"
+                    } else {
+                        "injoignable"
+                    }
+                );
             }
-            // 4) apply + commit
-            if !git::apply_index(&patch)? {
-                anyhow::bail!("Échec git apply --index.");
-            }
-            // Génère un titre de commit (LLM si dispo, sinon fallback)
-            let _diff_head = patch.lines().take(60).collect::<Vec<_>>().join(
-                "
-",
-            );
-            // Pas de goal ici → fallback générique
-            let commit_msg = default_commit_msg(None, &summary);
-            let attest = compute_attest_hash(&patch);
-            let full_msg = if cfg.provenance.footer {
-                format!("{}\n\nDevIt-Attest: {}", commit_msg, attest)
+        }
+        Some(Commands::Clean {
+            reports,
+            cache,
+            sessions,
+            all,
+            dry_run,
+        }) => {
+            let (reports, cache, sessions) = if all || !(reports || cache || sessions) {
+                (true, true, true)
             } else {
-                commit_msg.clone()
+                (reports, cache, sessions)
             };
-            if !git::commit(&full_msg)? {
-                anyhow::bail!("Échec git commit.");
+            let report = clean::clean(reports, cache, sessions, dry_run);
+            let verb = if dry_run { "à supprimer" } else { "supprimé(s)" };
+            finish(
+                json,
+                &format!(
+                    "🧹 {} fichier(s) {verb} ({} octets)",
+                    report.reports.files.len() + report.cache.files.len() + report.sessions.files.len(),
+                    report.bytes_total
+                ),
+                serde_json::to_value(&report)?,
+            )?;
+        }
+        Some(Commands::Pr {
+            action: PrCmd::Create { base, title, draft },
+        }) => {
+            ensure_git_repo()?;
+            let branch = git::current_branch()?;
+            if branch == base {
+                anyhow::bail!(
+                    "branche courante ({branch}) identique à la base ({base}); crée d'abord une branche dédiée"
+                );
             }
-            if cfg.git.use_notes {
-                let _ = git::add_note(&format!("DevIt-Attest: {}", attest));
+            let remote = git::remote_url("origin")?;
+            let (owner, repo) = github::parse_owner_repo(&remote).ok_or_else(|| {
+                anyhow::anyhow!("remote 'origin' non reconnu comme un dépôt GitHub: {remote}")
+            })?;
+            let token = std::env::var("GITHUB_TOKEN")
+                .context("variable GITHUB_TOKEN manquante (nécessaire pour l'API GitHub)")?;
+            git::push("origin", &branch)?;
+            let pr_title = title.unwrap_or_else(|| {
+                git::commit_message("HEAD")
+                    .ok()
+                    .and_then(|m| m.lines().next().map(str::to_string))
+                    .filter(|l| !l.is_empty())
+                    .unwrap_or_else(|| branch.clone())
+            });
+            let summary_path = Path::new(".devit/reports/pr_summary.md");
+            let body = report::summary_markdown(
+                Path::new(".devit/reports/junit.xml"),
+                Path::new(".devit/reports/sarif.json"),
+                summary_path,
+            )
+            .ok()
+            .and_then(|_| fs::read_to_string(summary_path).ok())
+            .unwrap_or_default();
+            let api_base = cfg
+                .github
+                .api_base
+                .clone()
+                .unwrap_or_else(|| "https://api.github.com".to_string());
+            let client = github::GitHubClient::new(token, api_base);
+            let pr = client
+                .create_pull_request(&owner, &repo, &pr_title, &branch, &base, &body, draft)
+                .await?;
+            if !cfg.github.labels.is_empty() {
+                client
+                    .add_labels(&owner, &repo, pr.number, &cfg.github.labels)
+                    .await?;
+            }
+            finish(
+                json,
+                &format!("✅ PR #{} ouverte: {}", pr.number, pr.html_url),
+                serde_json::json!({"number": pr.number, "url": pr.html_url}),
+            )?;
+        }
+        Some(Commands::Mr {
+            action: MrCmd::Create { target, title, draft },
+        }) => {
+            ensure_git_repo()?;
+            let branch = git::current_branch()?;
+            if branch == target {
+                anyhow::bail!(
+                    "branche courante ({branch}) identique à la cible ({target}); crée d'abord une branche dédiée"
+                );
             }
-            journal_event(&Event::Attest {
-                hash: attest.clone(),
+            let remote = git::remote_url("origin")?;
+            let project_path = gitlab::parse_project_path(&remote).ok_or_else(|| {
+                anyhow::anyhow!("remote 'origin' non reconnu comme un dépôt GitLab: {remote}")
             })?;
-            let sha = git::head_short().unwrap_or_default();
-            println!("✅ Commit {}: {}", sha, commit_msg);
+            let token = std::env::var("GITLAB_TOKEN")
+                .context("variable GITLAB_TOKEN manquante (nécessaire pour l'API GitLab)")?;
+            git::push("origin", &branch)?;
+            let mut mr_title = title.unwrap_or_else(|| {
+                git::commit_message("HEAD")
+                    .ok()
+                    .and_then(|m| m.lines().next().map(str::to_string))
+                    .filter(|l| !l.is_empty())
+                    .unwrap_or_else(|| branch.clone())
+            });
+            if draft {
+                mr_title = format!("Draft: {mr_title}");
+            }
+            let summary_path = Path::new(".devit/reports/mr_summary.md");
+            let description = report::summary_markdown(
+                Path::new(".devit/reports/junit.xml"),
+                Path::new(".devit/reports/sarif.json"),
+                summary_path,
+            )
+            .ok()
+            .and_then(|_| fs::read_to_string(summary_path).ok())
+            .unwrap_or_default();
+            let api_base = cfg
+                .gitlab
+                .api_base
+                .clone()
+                .unwrap_or_else(|| "https://gitlab.com/api/v4".to_string());
+            let client = gitlab::GitLabClient::new(token, api_base);
+            let mr = client
+                .create_merge_request(
+                    &project_path,
+                    &branch,
+                    &target,
+                    &mr_title,
+                    &description,
+                    &cfg.gitlab.labels,
+                )
+                .await?;
+            finish(
+                json,
+                &format!("✅ MR !{} ouverte: {}", mr.iid, mr.web_url),
+                serde_json::json!({"iid": mr.iid, "url": mr.web_url}),
+            )?;
         }
         Some(Commands::Run {
             path,
             goal,
             yes,
             force,
+            no_commit,
         }) => {
             // OnRequest: aucune action automatique; nécessite --yes
             {
@@ -429,8 +1358,12 @@ async fn main() -> Result<()> {
             }
             ensure_git_repo()?;
             // 1) suggest
-            let ctx = collect_context(&path)?;
+            let ctx_progress = progress::Progress::start(json, "context");
+            let ctx = collect_context(&path, &goal, &agent, &cfg).await?;
+            ctx_progress.finish();
+            let llm_progress = progress::Progress::start(json, "llm");
             let patch = agent.suggest_patch(&goal, &ctx).await?;
+            llm_progress.finish();
             if patch.trim().is_empty() {
                 anyhow::bail!("Le backend n'a pas produit de diff.");
             }
@@ -457,13 +1390,32 @@ async fn main() -> Result<()> {
                     eprintln!("  … ({} autres)", ns.len() - 10);
                 }
                 if !ask_approval()? {
-                    anyhow::bail!("Annulé par l'utilisateur.");
+                    return exit_code::fail(exit_code::ExitCode::ApprovalRequired, "approval_required", t(MsgKey::ApplyCancelled));
                 }
             }
-            // 4) apply + commit
+            // 4) checkpoint (safety net for `devit rollback`) then apply + commit
+            let cp = checkpoint::create()?;
+            journal_event(&Event::Checkpoint {
+                id: cp.id.clone(),
+                base_sha: cp.base_sha.clone(),
+            })?;
             if !git::apply_index(&patch)? {
                 anyhow::bail!("Échec git apply --index (et fallback --3way).");
             }
+            if no_commit {
+                // No commit to attach a footer/note to yet, but the diff is
+                // staged and immutable at this point, so attest it now
+                // rather than waiting for a commit that may never happen.
+                let attest = compute_attest_hash(&patch);
+                journal_event(&Event::Attest {
+                    hash: attest.clone(),
+                })?;
+                return finish(
+                    json,
+                    &format!("✅ Patch indexé ({summary}), commit et tests laissés de côté (--no-commit)."),
+                    serde_json::json!({"mode": "staged", "summary": summary, "attest": attest}),
+                );
+            }
             // Structured commit message (run)
             let staged_list = std::process::Command::new("git")
                 .args(["diff", "--name-only", "--cached"])
@@ -489,6 +1441,11 @@ async fn main() -> Result<()> {
                 .and_then(|c| c.template_body.as_ref())
                 .and_then(|p| std::fs::read_to_string(p).ok());
             let scopes_alias = cfg.commit.as_ref().map(|c| c.scopes_alias.clone());
+            let issue_prefixes = cfg
+                .commit
+                .as_ref()
+                .map(|c| c.issue_prefixes.clone())
+                .unwrap_or_default();
             let input = crate::commit_msg::MsgInput {
                 staged_paths,
                 diff_summary: Some(summary.clone()),
@@ -497,6 +1454,7 @@ async fn main() -> Result<()> {
                 max_subject,
                 template_body,
                 scopes_alias,
+                issue_prefixes,
             };
             let mut msg = crate::commit_msg::generate_struct(&input)?;
             // Optional LLM subject synthesis (2s timeout; fallback heuristic)
@@ -511,20 +1469,44 @@ async fn main() -> Result<()> {
                     }
                 }
             }
+            // Optional LLM body synthesis ([commit] llm_body; 2s timeout,
+            // falls back to the heuristic/template body on error/empty).
+            if cfg.commit.as_ref().map(|c| c.llm_body).unwrap_or(false) && msg.body.trim().is_empty() {
+                let diff_head = patch.lines().take(120).collect::<Vec<_>>().join("\n");
+                let fut = agent.commit_body(&goal, &summary, &diff_head);
+                if let Ok(Ok(b)) =
+                    tokio::time::timeout(std::time::Duration::from_secs(2), fut).await
+                {
+                    if !b.trim().is_empty() {
+                        msg.body = b;
+                    }
+                }
+            }
             if cfg.provenance.footer {
                 let attest = compute_attest_hash(&patch);
                 msg.footers.push(format!("DevIt-Attest: {}", attest));
+                if let Some(sbom) = sbom_footer() {
+                    msg.footers.push(sbom);
+                }
+                msg.footers.extend(attribution_footers(&cfg));
                 if cfg.git.use_notes {
                     let _ = git::add_note(&format!("DevIt-Attest: {}", attest));
                 }
                 journal_event(&Event::Attest { hash: attest })?;
             }
             let msg_path = ".git/COMMIT_EDITMSG";
-            let subject_line = if let Some(sc) = &msg.scope {
-                format!("{}({}): {}", msg.ctype, sc, msg.subject)
-            } else {
-                format!("{}: {}", msg.ctype, msg.subject)
-            };
+            let commit_style = cfg
+                .commit
+                .as_ref()
+                .and_then(|c| c.style.clone())
+                .unwrap_or_else(|| "conventional".to_string());
+            let commit_subject_template =
+                cfg.commit.as_ref().and_then(|c| c.subject_template.clone());
+            let subject_line = crate::commit_msg::format_subject_line(
+                &msg,
+                &commit_style,
+                commit_subject_template.as_deref(),
+            );
             let body = msg.body.clone();
             let foot = if msg.footers.is_empty() {
                 String::new()
@@ -546,8 +1528,22 @@ async fn main() -> Result<()> {
             let sha = git::head_short().unwrap_or_default();
             println!("✅ Commit {}: {}", sha, subject_line);
             // 5) tests
-            let (code, out) = codeexec::run_tests_with_output()?;
+            let tests_progress = progress::Progress::start(json, "tests");
+            let (code, out) = codeexec::run_tests_with_output(&cfg.test)?;
+            tests_progress.finish();
             println!("{}", out);
+            let post_test_env = std::collections::HashMap::from([
+                ("DEVIT_REPORT", ".devit/reports/junit.xml".to_string()),
+                ("DEVIT_TEST_EXIT_CODE", code.to_string()),
+            ]);
+            if let Err(f) = hooks::run(&cfg, hooks::Point::PostTest, &post_test_env) {
+                return exit_code::fail_with(
+                    exit_code::ExitCode::PrecommitFailed,
+                    "hook_failed",
+                    format!("hook post_test `{}` a échoué (exit {})", f.command, f.exit_code),
+                    serde_json::json!({"point": f.point, "command": f.command, "exit_code": f.exit_code, "stderr": f.stderr}),
+                );
+            }
             if code == 0 {
                 println!("✅ Tests PASS");
             } else {
@@ -561,9 +1557,27 @@ async fn main() -> Result<()> {
                         "policy.sandbox=read-only: test refusé (exécution/écriture interdites)"
                     );
                 }
-                match codeexec::run_tests_with_output() {
+                let tests_progress = progress::Progress::start(json, "tests");
+                let result = codeexec::run_tests_with_output(&cfg.test);
+                tests_progress.finish();
+                match result {
                     Ok((code, out)) => {
                         println!("{}", out);
+                        let post_test_env = std::collections::HashMap::from([
+                            ("DEVIT_REPORT", ".devit/reports/junit.xml".to_string()),
+                            ("DEVIT_TEST_EXIT_CODE", code.to_string()),
+                        ]);
+                        if let Err(f) = hooks::run(&cfg, hooks::Point::PostTest, &post_test_env) {
+                            return exit_code::fail_with(
+                                exit_code::ExitCode::PrecommitFailed,
+                                "hook_failed",
+                                format!(
+                                    "hook post_test `{}` a échoué (exit {})",
+                                    f.command, f.exit_code
+                                ),
+                                serde_json::json!({"point": f.point, "command": f.command, "exit_code": f.exit_code, "stderr": f.stderr}),
+                            );
+                        }
                         if code == 0 {
                             println!("✅ Tests PASS");
                         } else {
@@ -580,6 +1594,9 @@ async fn main() -> Result<()> {
                 framework,
                 timeout_secs,
                 max_jobs,
+                retries,
+                shards,
+                shard_index,
             } => {
                 if cfg.policy.sandbox.to_lowercase() == "read-only" {
                     anyhow::bail!(
@@ -592,8 +1609,16 @@ async fn main() -> Result<()> {
                     max_jobs,
                     framework: Some(framework),
                     timeout_secs,
+                    retries,
+                    shards,
+                    shard_index,
+                    custom_command: cfg.test.impacted_command.clone(),
+                    custom_env: cfg.test.env.clone(),
                 };
-                match test_runner::run_impacted(&opts) {
+                let tests_progress = progress::Progress::start(json, "tests");
+                let result = test_runner::run_impacted(&opts);
+                tests_progress.finish();
+                match result {
                     Ok(rep) => {
                         println!(
                             "{}",
@@ -618,23 +1643,91 @@ async fn main() -> Result<()> {
                                 "{}",
                                 serde_json::to_string(&serde_json::json!({
                                     "type": "tool.error",
+                                    "code": "timeout",
                                     "payload": { "timeout": true }
                                 }))?
                             );
-                            std::process::exit(124);
+                            std::process::exit(exit_code::ExitCode::Timeout.code() as i32);
                         } else {
                             println!(
                                 "{}",
                                 serde_json::to_string(&serde_json::json!({
                                     "type": "tool.error",
+                                    "code": "tests_failed",
                                     "payload": { "tests_failed": true, "report": ".devit/reports/junit.xml" }
                                 }))?
                             );
-                            std::process::exit(2);
+                            std::process::exit(exit_code::ExitCode::TestsFailed.code() as i32);
                         }
                     }
                 }
             }
+            TestCmd::Coverage {
+                framework,
+                timeout_secs,
+            } => {
+                if cfg.policy.sandbox.to_lowercase() == "read-only" {
+                    anyhow::bail!(
+                        "policy.sandbox=read-only: test refusé (exécution/écriture interdites)"
+                    );
+                }
+                let opts = coverage::CoverageOpts {
+                    framework: Some(framework),
+                    timeout_secs,
+                };
+                let cov_progress = progress::Progress::start(json, "coverage");
+                let result = coverage::run_coverage(&opts);
+                cov_progress.finish();
+                match result {
+                    Ok(rep) => {
+                        println!(
+                            "{}",
+                            serde_json::to_string(&serde_json::json!({
+                                "type": "tool.result",
+                                "payload": {
+                                    "ok": true,
+                                    "framework": rep.framework,
+                                    "lines_pct": rep.lines_pct,
+                                    "report_path": rep.report_path,
+                                    "duration_ms": rep.duration_ms
+                                }
+                            }))?
+                        );
+                    }
+                    Err(e) => {
+                        println!(
+                            "{}",
+                            serde_json::to_string(&serde_json::json!({
+                                "type": "tool.error",
+                                "code": "coverage_failed",
+                                "payload": { "ok": false, "error": e.to_string() }
+                            }))?
+                        );
+                        std::process::exit(exit_code::ExitCode::GenericError.code() as i32);
+                    }
+                }
+            }
+            TestCmd::Watch {
+                framework,
+                timeout_secs,
+            } => {
+                if cfg.policy.sandbox.to_lowercase() == "read-only" {
+                    anyhow::bail!(
+                        "policy.sandbox=read-only: test refusé (exécution/écriture interdites)"
+                    );
+                }
+                if let Err(e) = watch::run_watch(framework, timeout_secs, cfg.test.clone()) {
+                    println!(
+                        "{}",
+                        serde_json::to_string(&serde_json::json!({
+                            "type": "tool.error",
+                            "code": "watch_failed",
+                            "payload": { "ok": false, "error": e.to_string() }
+                        }))?
+                    );
+                    std::process::exit(exit_code::ExitCode::GenericError.code() as i32);
+                }
+            }
         },
         Some(Commands::Tool { action }) => match action {
             ToolCmd::List => {
@@ -652,8 +1745,35 @@ async fn main() -> Result<()> {
                 yes,
                 no_precommit,
                 precommit_only,
+                no_cache,
+                autofix,
+                explain,
             } => {
-                if name == "-" {
+                if explain {
+                    let cmd = if name == "-" {
+                        let mut s = String::new();
+                        stdin().lock().read_to_string(&mut s)?;
+                        let req: serde_json::Value = serde_json::from_str(&s)
+                            .context("tool call: JSON invalide sur stdin")?;
+                        req.get("args")
+                            .and_then(|a| a.get("cmd"))
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("")
+                            .to_string()
+                    } else if name == "shell_exec" {
+                        input.clone()
+                    } else {
+                        anyhow::bail!("--explain: seul shell_exec est pris en charge");
+                    };
+                    let explanation =
+                        sandbox::explain_policy(&cmd, &cfg.policy, &cfg.sandbox);
+                    emit_json(&serde_json::json!({
+                        "allowed": explanation.allowed,
+                        "profile": explanation.profile,
+                        "commands": explanation.commands,
+                        "blocked_by": explanation.blocked_by,
+                    }))?;
+                } else if name == "-" {
                     let mut s = String::new();
                     stdin().lock().read_to_string(&mut s)?;
                     let req: serde_json::Value =
@@ -677,6 +1797,8 @@ async fn main() -> Result<()> {
                         yes,
                         no_precommit,
                         precommit_only,
+                        no_cache,
+                        autofix,
                         json_only,
                     );
                     if let Err(e) = out {
@@ -698,15 +1820,36 @@ async fn main() -> Result<()> {
                 let recipes = list_recipes()?;
                 emit_json(&serde_json::json!({"recipes": recipes}))?;
             }
-            RecipeCmd::Run { id, dry_run } => match run_recipe(&id, dry_run) {
-                Ok(report) => {
-                    emit_json(&serde_json::json!({"ok": true, "recipe": report}))?;
+            RecipeCmd::Run { id, dry_run, param } => {
+                let mut overrides = std::collections::HashMap::new();
+                if let Some(csv) = &param {
+                    for pair in csv.split(',') {
+                        let pair = pair.trim();
+                        if pair.is_empty() {
+                            continue;
+                        }
+                        let (k, v) = pair.split_once('=').ok_or_else(|| {
+                            anyhow::anyhow!("--param {pair:?}: expected key=value")
+                        })?;
+                        overrides.insert(k.trim().to_string(), v.trim().to_string());
+                    }
                 }
-                Err(RecipeRunError { payload, exit_code }) => {
-                    emit_json(&serde_json::json!({"type":"tool.error","payload": payload}))?;
-                    std::process::exit(exit_code);
+                match run_recipe(&id, dry_run, &overrides) {
+                    Ok(report) => {
+                        emit_json(&serde_json::json!({"ok": true, "recipe": report}))?;
+                    }
+                    Err(RecipeRunError {
+                        payload,
+                        exit_code,
+                        code,
+                    }) => {
+                        emit_json(
+                            &serde_json::json!({"type":"tool.error","code": code,"payload": payload}),
+                        )?;
+                        std::process::exit(exit_code);
+                    }
                 }
-            },
+            }
         },
         Some(Commands::Context { action }) => match action {
             CtxCmd::Map {
@@ -715,49 +1858,191 @@ async fn main() -> Result<()> {
                 max_files,
                 ext_allow,
                 json_out,
+                compact,
+                watch,
             } => {
+                let indexing = progress::Progress::start(json, "indexing");
+                let json_out = json_out.or_else(|| {
+                    compact.then(|| PathBuf::from(".devit/index.ndjson"))
+                });
                 let written = build_context_index_adv(
                     &path,
                     max_bytes_per_file,
                     max_files,
                     ext_allow.as_deref(),
                     json_out.as_deref(),
+                    watch,
+                    &cfg,
+                )?;
+                indexing.finish();
+                if !watch {
+                    println!("index écrit: {}", written.display());
+                }
+            }
+            CtxCmd::Blame { file } => {
+                let regions = blame::blame_regions(&file).map_err(|e| anyhow::anyhow!(e))?;
+                let human = regions
+                    .iter()
+                    .map(|r| {
+                        format!(
+                            "L{}-{} {} ({}, {}): {}",
+                            r.start_line, r.end_line, r.author, r.sha, r.date, r.summary
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                finish(json, &human, serde_json::json!({ "file": file, "regions": regions }))?;
+            }
+            CtxCmd::Search { query, top } => {
+                let timeout = std::env::var("DEVIT_TIMEOUT_SECS")
+                    .ok()
+                    .and_then(|s| s.parse::<u64>().ok())
+                    .map(Duration::from_secs);
+                let opts = crate::context::ContextOpts {
+                    max_bytes_per_file: 262_144,
+                    max_files: 5000,
+                    ext_allow: None,
+                    timeout,
+                    out_path: ensure_devit_dir()?.join("index.json"),
+                    scoring: scoring_rules_from_cfg(&cfg),
+                };
+                let hits = crate::context::search(Path::new("."), &opts, &query, top, &agent).await?;
+                let human = hits
+                    .iter()
+                    .map(|h| {
+                        format!(
+                            "{} (score={}, similarity={:.3}) [{}]",
+                            h.path,
+                            h.score,
+                            h.similarity,
+                            h.symbols.join(", ")
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                finish(json, &human, serde_json::json!({ "query": query, "hits": hits }))?;
+            }
+            CtxCmd::Symbols { path } => {
+                let symbols =
+                    crate::context::symbols_for_file(&path).map_err(|e| anyhow::anyhow!(e))?;
+                let human = symbols
+                    .iter()
+                    .map(|s| format!("L{}-{} {} {}", s.start_line, s.end_line, s.kind, s.name))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                finish(
+                    json,
+                    &human,
+                    serde_json::json!({ "path": path, "symbols": symbols }),
+                )?;
+            }
+            CtxCmd::Pack { goal, budget } => {
+                let opts = crate::context::ContextOpts {
+                    max_bytes_per_file: 262_144,
+                    max_files: 5000,
+                    ext_allow: None,
+                    timeout: None,
+                    out_path: ensure_devit_dir()?.join("index.json"),
+                    scoring: scoring_rules_from_cfg(&cfg),
+                };
+                let bundle =
+                    crate::context::pack(Path::new("."), &opts, &goal, budget, &agent).await?;
+                finish(
+                    json,
+                    &bundle,
+                    serde_json::json!({ "goal": goal, "budget": budget, "bundle": bundle }),
                 )?;
-                println!("index écrit: {}", written.display());
+            }
+            CtxCmd::Summarize { path } => {
+                let opts = crate::context::ContextOpts {
+                    max_bytes_per_file: 262_144,
+                    max_files: 5000,
+                    ext_allow: None,
+                    timeout: None,
+                    out_path: ensure_devit_dir()?.join("index.json"),
+                    scoring: scoring_rules_from_cfg(&cfg),
+                };
+                let summaries =
+                    crate::context::summarize_dirs(Path::new(&path), &opts, &agent).await?;
+                let human = crate::context::render_repo_map(&summaries);
+                finish(json, &human, serde_json::json!({ "summaries": summaries }))?;
             }
         },
-        Some(Commands::CommitMsg {
-            from_staged,
-            from_ref,
-            typ,
-            scope,
-            write,
-            with_template,
-        }) => {
-            let opts = commit_msg::Options {
+        Some(Commands::CommitMsg { action }) => match action {
+            CommitMsgCmd::Generate {
                 from_staged,
-                change_from: from_ref,
+                from_ref,
                 typ,
                 scope,
+                write,
                 with_template,
-            };
-            let msg = commit_msg::generate(&opts)?;
-            if write {
-                let path = ".git/COMMIT_EDITMSG";
-                std::fs::write(path, msg)?;
-                println!("wrote: {}", path);
-            } else {
-                println!("{}", msg);
+            } => {
+                let opts = commit_msg::Options {
+                    from_staged,
+                    change_from: from_ref,
+                    typ,
+                    scope,
+                    with_template,
+                };
+                let msg = commit_msg::generate(&opts)?;
+                if write {
+                    let path = ".git/COMMIT_EDITMSG";
+                    std::fs::write(path, msg)?;
+                    println!("wrote: {}", path);
+                } else {
+                    println!("{}", msg);
+                }
             }
-        }
+            CommitMsgCmd::Lint { file } => {
+                let message = fs::read_to_string(&file)
+                    .with_context(|| format!("lecture du message de commit {file}"))?;
+                let commit_cfg = cfg.commit.clone().unwrap_or_default();
+                let violations = commit_msg::lint(&message, &commit_cfg);
+                if violations.is_empty() {
+                    emit_json(&serde_json::json!({"ok": true}))?;
+                } else {
+                    return exit_code::fail_with(
+                        exit_code::ExitCode::CommitMsgInvalid,
+                        "commit_msg_invalid",
+                        format!("message de commit invalide: {}", violations.join("; ")),
+                        serde_json::json!({"violations": violations}),
+                    );
+                }
+            }
+        },
         Some(Commands::Report { kind }) => match kind {
-            ReportCmd::Sarif { from } => {
-                let p = if from == "latest" {
-                    report::sarif_latest()?
+            ReportCmd::Sarif {
+                from,
+                merge,
+                inputs,
+                ingest_eslint,
+                ingest_ruff,
+            } => {
+                if let Some(path) = ingest_eslint {
+                    let sarif = report::eslint_json_to_sarif(std::path::Path::new(&path))?;
+                    let out = std::path::Path::new(".devit/reports/eslint.sarif.json");
+                    std::fs::create_dir_all(out.parent().unwrap())?;
+                    std::fs::write(out, serde_json::to_vec_pretty(&sarif)?)?;
+                }
+                if let Some(path) = ingest_ruff {
+                    let sarif = report::ruff_json_to_sarif(std::path::Path::new(&path))?;
+                    let out = std::path::Path::new(".devit/reports/ruff.sarif.json");
+                    std::fs::create_dir_all(out.parent().unwrap())?;
+                    std::fs::write(out, serde_json::to_vec_pretty(&sarif)?)?;
+                }
+                if merge {
+                    let inputs = report::sarif_merge_inputs(&inputs);
+                    let out = std::path::Path::new(".devit/reports/sarif.json");
+                    let stats = report::sarif_merge(&inputs, out)?;
+                    println!("{}", serde_json::to_string(&stats)?);
                 } else {
-                    std::path::PathBuf::from(from)
-                };
-                println!("{}", p.display());
+                    let p = if from == "latest" {
+                        report::sarif_latest()?
+                    } else {
+                        std::path::PathBuf::from(from)
+                    };
+                    println!("{}", p.display());
+                }
             }
             ReportCmd::Junit { from } => {
                 let p = if from == "latest" {
@@ -775,6 +2060,147 @@ async fn main() -> Result<()> {
                 )?;
                 println!("{}", out);
             }
+            ReportCmd::SlowTests { limit, json } => {
+                let slowest = test_history::slowest(limit);
+                let regressed = test_history::most_regressed(limit);
+                if json {
+                    println!(
+                        "{}",
+                        serde_json::to_string_pretty(&serde_json::json!({
+                            "slowest": slowest,
+                            "most_regressed": regressed,
+                        }))?
+                    );
+                } else {
+                    println!("## Slowest tests\n");
+                    for t in &slowest {
+                        println!("- {} — {}ms ({} runs)", t.id, t.last_duration_ms, t.runs);
+                    }
+                    println!("\n## Most regressed\n");
+                    for t in &regressed {
+                        println!("- {} — +{}ms (now {}ms)", t.id, t.delta_ms, t.last_duration_ms);
+                    }
+                }
+            }
+            ReportCmd::GithubAnnotations {
+                junit,
+                sarif,
+                format,
+                out,
+            } => {
+                let mut annotations =
+                    report::junit_annotations(std::path::Path::new(&junit)).unwrap_or_default();
+                annotations
+                    .extend(report::sarif_annotations(std::path::Path::new(&sarif)).unwrap_or_default());
+                match format.as_str() {
+                    "checks" => {
+                        let payload = report::github_checks_payload(&annotations);
+                        if let Some(dir) = std::path::Path::new(&out).parent() {
+                            std::fs::create_dir_all(dir)?;
+                        }
+                        std::fs::write(&out, serde_json::to_vec_pretty(&payload)?)?;
+                        println!("{}", out);
+                    }
+                    _ => {
+                        println!("{}", report::github_workflow_commands(&annotations));
+                    }
+                }
+            }
+            ReportCmd::PrComment {
+                junit,
+                sarif,
+                config,
+                out,
+            } => {
+                let cfg_text = std::fs::read_to_string(&config).unwrap_or_default();
+                let tbl: toml::Value =
+                    toml::from_str(&cfg_text).unwrap_or(toml::Value::Table(Default::default()));
+                let qcfg: devit_common::QualityCfg = tbl
+                    .get("quality")
+                    .and_then(|v| v.clone().try_into().ok())
+                    .unwrap_or_default();
+                let md = report::pr_comment_markdown(
+                    std::path::Path::new(&junit),
+                    std::path::Path::new(&sarif),
+                    &qcfg,
+                )?;
+                if let Some(dir) = std::path::Path::new(&out).parent() {
+                    std::fs::create_dir_all(dir)?;
+                }
+                std::fs::write(&out, &md)?;
+                println!("{}", out);
+            }
+            ReportCmd::ClippySarif { out } => {
+                let count = clippy_sarif::run(std::path::Path::new(&out))?;
+                println!(
+                    "{}",
+                    serde_json::to_string(&serde_json::json!({
+                        "type": "tool.result",
+                        "code": "ok",
+                        "payload": { "ok": true, "out": out, "results": count }
+                    }))?
+                );
+            }
+            ReportCmd::Sbom { out } => {
+                sbom::generate(std::path::Path::new(&out))?;
+                println!("{}", out);
+            }
+            ReportCmd::Licenses { config, out } => {
+                let cfg_text = std::fs::read_to_string(&config).unwrap_or_default();
+                let tbl: toml::Value =
+                    toml::from_str(&cfg_text).unwrap_or(toml::Value::Table(Default::default()));
+                let lcfg: devit_common::LicensesCfg = tbl
+                    .get("licenses")
+                    .and_then(|v| v.clone().try_into().ok())
+                    .unwrap_or_default();
+                let report = licenses::report(&lcfg, std::path::Path::new(&out))?;
+                let ok = report.violations.is_empty();
+                println!(
+                    "{}",
+                    serde_json::to_string(&serde_json::json!({
+                        "type": if ok { "tool.result" } else { "tool.error" },
+                        "code": if ok { "ok" } else { "license_violations" },
+                        "payload": { "ok": ok, "report": report, "out": out }
+                    }))?
+                );
+                if !ok {
+                    std::process::exit(exit_code::ExitCode::GenericError.code() as i32);
+                }
+            }
+            ReportCmd::Complexity { root, out } => {
+                let report = complexity::report(std::path::Path::new(&root), std::path::Path::new(&out))?;
+                println!(
+                    "{}",
+                    serde_json::to_string(&serde_json::json!({
+                        "type": "tool.result",
+                        "code": "ok",
+                        "payload": {
+                            "ok": true,
+                            "functions": report.functions.len(),
+                            "max_complexity": report.max_complexity,
+                            "max_length": report.max_length,
+                            "out": out
+                        }
+                    }))?
+                );
+            }
+            ReportCmd::Deadcode { root, out } => {
+                let report = deadcode::report(std::path::Path::new(&root), std::path::Path::new(&out))?;
+                println!(
+                    "{}",
+                    serde_json::to_string(&serde_json::json!({
+                        "type": "tool.result",
+                        "code": "ok",
+                        "payload": {
+                            "ok": true,
+                            "candidates": report.candidates.len(),
+                            "scanned_files": report.scanned_files,
+                            "scanned_symbols": report.scanned_symbols,
+                            "out": out
+                        }
+                    }))?
+                );
+            }
         },
         Some(Commands::Quality { action }) => match action {
             QualityCmd::Gate {
@@ -782,6 +2208,7 @@ async fn main() -> Result<()> {
                 sarif,
                 config,
                 json: _,
+                against_baseline,
             } => {
                 // load quality cfg
                 let cfg_text = std::fs::read_to_string(&config).unwrap_or_default();
@@ -800,42 +2227,100 @@ async fn main() -> Result<()> {
                         .collect::<Vec<_>>()
                 });
                 let flaky_ref = flaky.as_deref();
-                let sum = report::summarize(
+                let mut sum = report::summarize(
                     std::path::Path::new(&junit),
                     std::path::Path::new(&sarif),
                     &qcfg,
                     flaky_ref,
                 )?;
+                if against_baseline {
+                    let baseline = report::load_quality_baseline().unwrap_or_default();
+                    report::apply_baseline_delta(
+                        &mut sum,
+                        std::path::Path::new(&junit),
+                        std::path::Path::new(&sarif),
+                        &baseline,
+                    );
+                }
                 let pass = report::check_thresholds(&sum, &qcfg);
+                if pass {
+                    if let Some(totals) = coverage::latest_coverage_totals() {
+                        coverage::save_baseline(&totals);
+                    }
+                }
+                quality_history::record(&sum, pass);
+                let _ = fs::write(
+                    ".devit/reports/quality_verdict.json",
+                    serde_json::to_vec(&serde_json::json!({"pass": pass, "summary": sum}))?,
+                );
                 if pass {
                     println!(
                         "{}",
                         serde_json::to_string(&serde_json::json!({
                             "type":"tool.result",
+                            "code": "ok",
                             "payload": { "ok": true, "summary": sum, "pass": pass }
                         }))?
                     );
-                    std::process::exit(0);
+                    std::process::exit(exit_code::ExitCode::Ok.code() as i32);
                 } else {
                     println!(
                         "{}",
                         serde_json::to_string(&serde_json::json!({
                             "type":"tool.error",
+                            "code": "quality_gate_failed",
                             "payload": { "ok": false, "summary": sum, "pass": pass, "reason":"thresholds_exceeded" }
                         }))?
                     );
-                    std::process::exit(1);
+                    std::process::exit(exit_code::ExitCode::GenericError.code() as i32);
+                }
+            }
+            QualityCmd::Baseline { junit, sarif } => {
+                let baseline = report::snapshot_baseline(
+                    std::path::Path::new(&junit),
+                    std::path::Path::new(&sarif),
+                );
+                report::save_quality_baseline(&baseline)?;
+                println!(
+                    "{}",
+                    serde_json::to_string(&serde_json::json!({
+                        "type": "tool.result",
+                        "code": "ok",
+                        "payload": { "ok": true, "baseline": baseline }
+                    }))?
+                );
+            }
+            QualityCmd::Trend { limit, json } => {
+                let regressions = quality_history::trend(limit);
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&regressions)?);
+                } else if regressions.is_empty() {
+                    println!("No notable regressions in the last {limit} runs.");
+                } else {
+                    println!("## Quality regressions (last {limit} runs)\n");
+                    for r in &regressions {
+                        println!(
+                            "- {}: {:.2} (mean {:.2}, stddev {:.2})",
+                            r.metric, r.latest, r.mean, r.stddev
+                        );
+                    }
                 }
             }
         },
         Some(Commands::Merge { action }) => match action {
             MergeCmd::Explain { paths } => {
-                let conf = merge_assist::explain(&paths)?;
+                let mut conf = merge_assist::explain(&paths)?;
+                enrich_with_llm(&agent, &mut conf).await;
+                let auto_resolved: usize = conf
+                    .iter()
+                    .flat_map(|fc| &fc.hunks)
+                    .filter(|h| h.auto_resolved.is_some())
+                    .count();
                 println!(
                     "{}",
                     serde_json::to_string(&serde_json::json!({
                         "type":"tool.result",
-                        "payload": {"ok": true, "conflicts": conf}
+                        "payload": {"ok": true, "conflicts": conf, "auto_resolved": auto_resolved}
                     }))?
                 );
             }
@@ -852,8 +2337,8 @@ async fn main() -> Result<()> {
                     }))?
                 );
             }
-            MergeCmd::Resolve { strategy: _ } => {
-                let conf = merge_assist::explain(&Vec::new())?;
+            MergeCmd::Resolve { strategy } => {
+                let mut conf = merge_assist::explain(&Vec::new())?;
                 if conf.is_empty() {
                     println!(
                         "{}",
@@ -863,7 +2348,12 @@ async fn main() -> Result<()> {
                         }))?
                     );
                 } else {
-                    let plan = merge_assist::propose_auto(&conf);
+                    let plan = if strategy == "llm" {
+                        enrich_with_llm(&agent, &mut conf).await;
+                        merge_assist::propose_llm(&conf)
+                    } else {
+                        merge_assist::propose_auto(&conf)
+                    };
                     let files = plan.len() as u32;
                     merge_assist::apply_plan(&plan).map_err(|e| anyhow::anyhow!(e.to_string()))?;
                     println!(
@@ -876,6 +2366,11 @@ async fn main() -> Result<()> {
                 }
             }
         },
+        Some(Commands::Rebase { action }) => match action {
+            RebaseCmd::Assist { yes } => {
+                rebase_assist(&agent, &cfg, yes, json).await?;
+            }
+        },
         Some(Commands::Sbom { action }) => match action {
             SbomCmd::Gen { out } => {
                 let outp = std::path::Path::new(&out);
@@ -886,6 +2381,60 @@ async fn main() -> Result<()> {
                 println!("{}", out);
             }
         },
+        Some(Commands::Scan { action }) => match action {
+            ScanCmd::Secrets { patch, out, json } => {
+                let patch_text = match patch {
+                    Some(p) => std::fs::read_to_string(&p)?,
+                    None => {
+                        let output = std::process::Command::new("git")
+                            .args(["diff", "--cached"])
+                            .output()?;
+                        String::from_utf8_lossy(&output.stdout).into_owned()
+                    }
+                };
+                let findings = secrets_scan::scan_patch(&patch_text);
+                let sarif = secrets_scan::to_sarif(&findings);
+                let outp = std::path::Path::new(&out);
+                if let Some(dir) = outp.parent() {
+                    let _ = std::fs::create_dir_all(dir);
+                }
+                std::fs::write(outp, serde_json::to_vec_pretty(&sarif)?)?;
+                if json {
+                    let payload: Vec<serde_json::Value> = findings
+                        .iter()
+                        .map(|f| {
+                            serde_json::json!({
+                                "detector": f.detector,
+                                "file": f.file,
+                                "line": f.line,
+                                "excerpt": f.excerpt,
+                            })
+                        })
+                        .collect();
+                    println!(
+                        "{}",
+                        serde_json::to_string(&serde_json::json!({
+                            "type": "tool.result",
+                            "code": "ok",
+                            "payload": { "ok": findings.is_empty(), "findings": payload, "out": out }
+                        }))?
+                    );
+                }
+                if !findings.is_empty() {
+                    std::process::exit(exit_code::ExitCode::SecretsFound.code() as i32);
+                }
+            }
+        },
+        Some(Commands::Hooks { action }) => match action {
+            HooksCmd::Install { force } => {
+                let installed = git_hooks::install(force)?;
+                emit_json(&serde_json::json!({"installed": installed}))?;
+            }
+            HooksCmd::Uninstall => {
+                let removed = git_hooks::uninstall()?;
+                emit_json(&serde_json::json!({"removed": removed}))?;
+            }
+        },
         Some(Commands::FsPatchApply {
             json_input,
             commit,
@@ -942,23 +2491,123 @@ fn load_cfg(path: &str) -> Result<Config> {
     Ok(cfg)
 }
 
-fn collect_context(path: &str) -> Result<String> {
-    // MVP: naive — list a few files with content; later: git-aware, size limits
-    let mut out = String::new();
-    for entry in walkdir::WalkDir::new(path).max_depth(2) {
-        let entry = entry?;
-        if entry.file_type().is_file() {
-            let p = entry.path().display().to_string();
-            if p.ends_with(".rs") || p.ends_with("Cargo.toml") {
-                if let Ok(content) = std::fs::read_to_string(entry.path()) {
-                    out.push_str(&format!("\n>>> FILE: {}\n{}\n", p, content));
+/// [`devit_common::ContextCfg::scoring`] as [`context::ScoringRule`]s, or
+/// [`context::default_scoring_rules`] when the config table is empty.
+fn scoring_rules_from_cfg(cfg: &Config) -> Vec<context::ScoringRule> {
+    if cfg.context.scoring.is_empty() {
+        return context::default_scoring_rules();
+    }
+    cfg.context
+        .scoring
+        .iter()
+        .map(|r| context::ScoringRule {
+            glob: r.glob.clone(),
+            weight: r.weight,
+        })
+        .collect()
+}
+
+/// Best-effort `devit merge explain` enrichment: ask the LLM for a proposed
+/// resolution per conflict hunk, parse it into a
+/// [`merge_assist::LlmResolution`] and attach it. Failures and timeouts are
+/// silently skipped, same as `explain-patch`'s `--narrate` narrative -- this
+/// is advisory only, never required for `merge apply` to work.
+async fn enrich_with_llm(agent: &Agent, conf: &mut [merge_assist::FileConflicts]) {
+    for fc in conf.iter_mut() {
+        for hunk in fc.hunks.iter_mut() {
+            if hunk.auto_resolved.is_some() {
+                continue;
+            }
+            let fut = agent.propose_merge_resolution(&hunk.ours, &hunk.theirs);
+            if let Ok(Ok(raw)) =
+                tokio::time::timeout(std::time::Duration::from_secs(5), fut).await
+            {
+                if let Ok(resolution) = serde_json::from_str(raw.trim()) {
+                    hunk.llm_resolution = Some(resolution);
                 }
             }
         }
     }
+}
+
+/// Build the prompt context for a goal: a cached per-directory
+/// [`context::render_repo_map`] as a cheap overview, then the
+/// [`context::pack`] of the most relevant files within the default token
+/// budget, plus recent `git blame` on any file the goal names directly.
+async fn collect_context(path: &str, goal: &str, agent: &Agent, cfg: &Config) -> Result<String> {
+    let opts = context::ContextOpts {
+        max_bytes_per_file: 262_144,
+        max_files: 5000,
+        ext_allow: None,
+        timeout: None,
+        out_path: ensure_devit_dir()?.join("index.json"),
+        scoring: scoring_rules_from_cfg(cfg),
+    };
+    let mut out = match context::summarize_dirs(Path::new(path), &opts, agent).await {
+        Ok(summaries) => context::render_repo_map(&summaries),
+        Err(_) => String::new(),
+    };
+    out.push_str(
+        &context::pack(
+            Path::new(path),
+            &opts,
+            goal,
+            context::DEFAULT_PACK_BUDGET,
+            agent,
+        )
+        .await?,
+    );
+    out.push_str(&blame_context_for_goal(goal));
+    out.push_str(&dependents_context_for_goal(path, goal, &opts));
     Ok(out)
 }
 
+/// If `goal` names a file that exists in the repo, append the direct
+/// dependents of that file (see [`context::dependents_of`]) so the agent
+/// knows who else would break, and `devit run`'s impacted-test selection
+/// has an obvious hint of the blast radius before it even runs tests.
+fn dependents_context_for_goal(root: &str, goal: &str, opts: &context::ContextOpts) -> String {
+    let mut out = String::new();
+    for word in goal.split_whitespace() {
+        let candidate = word.trim_matches(|c: char| {
+            !c.is_alphanumeric() && c != '.' && c != '/' && c != '_' && c != '-'
+        });
+        if candidate.contains('.') && Path::new(candidate).is_file() {
+            if let Ok(deps) =
+                context::dependents_of(Path::new(root), opts, &[candidate.to_string()])
+            {
+                if !deps.is_empty() {
+                    out.push_str(&format!(
+                        "\n>>> DEPENDENTS OF {}: {}\n",
+                        candidate,
+                        deps.join(", ")
+                    ));
+                }
+            }
+        }
+    }
+    out
+}
+
+/// If `goal` names a file that exists in the repo, append its recent
+/// `git blame` regions (who last touched which lines, and why) so the
+/// agent respects intentional recent changes instead of just rewriting
+/// whatever it sees on disk.
+fn blame_context_for_goal(goal: &str) -> String {
+    let mut out = String::new();
+    for word in goal.split_whitespace() {
+        let candidate = word.trim_matches(|c: char| !c.is_alphanumeric() && c != '.' && c != '/' && c != '_' && c != '-');
+        if candidate.contains('.') && Path::new(candidate).is_file() {
+            if let Ok(summary) = blame::recent_summary(candidate, 5) {
+                if !summary.trim().is_empty() {
+                    out.push_str(&format!("\n>>> BLAME: {}\n{}\n", candidate, summary));
+                }
+            }
+        }
+    }
+    out
+}
+
 fn read_patch(input: &str) -> Result<String> {
     if input == "-" {
         let mut s = String::new();
@@ -969,12 +2618,493 @@ fn read_patch(input: &str) -> Result<String> {
     }
 }
 
+/// Shared core of `devit apply` (and `devit suggest --apply`): dry-run,
+/// approval, then apply as worktree-only / staged / staged+commit.
+#[allow(clippy::too_many_arguments)]
+fn run_apply_pipeline(
+    patch: &str,
+    cfg: &Config,
+    yes: bool,
+    force: bool,
+    worktree: bool,
+    no_commit: bool,
+    goal: Option<&str>,
+    json: bool,
+    preview: bool,
+    autostash: bool,
+) -> Result<()> {
+    // 0) index propre ?
+    let _autostash_guard = if autostash {
+        Some(autostash::AutoStash::engage()?)
+    } else {
+        if !git::is_worktree_clean() && !force {
+            anyhow::bail!(
+                "Le worktree ou l'index contient des modifications.\n\
+                 - Commit/stash tes changements OU relance avec --force (tentative 3-way)."
+            );
+        }
+        None
+    };
+    // 1) dry-run
+    git::apply_check(patch)?; // renvoie Err(...) avec le message Git détaillé
+    let ns = git::numstat(patch)?;
+    let files = ns.len();
+    let added: u64 = ns.iter().map(|e| e.added).sum();
+    let deleted: u64 = ns.iter().map(|e| e.deleted).sum();
+    let summary = format!("{} fichier(s), +{}, -{}", files, added, deleted);
+    // 3) approval (sauf policy 'never' ou --yes)
+    let must_ask = !yes && cfg.policy.approval.to_lowercase() != "never";
+    if must_ask {
+        eprintln!("Patch prêt: {summary}");
+        if preview {
+            match diff_preview::render(patch) {
+                Ok(rendered) => eprint!("{rendered}"),
+                Err(e) => eprintln!("(aperçu indisponible: {e})"),
+            }
+        } else {
+            for e in ns.iter().take(10) {
+                eprintln!("  - {}", e.path);
+            }
+            if ns.len() > 10 {
+                eprintln!("  … ({} autres)", ns.len() - 10);
+            }
+        }
+        if !ask_approval()? {
+            return exit_code::fail(exit_code::ExitCode::ApprovalRequired, "approval_required", t(MsgKey::ApplyCancelled));
+        }
+    }
+    // 3b) pre_apply hooks (can veto the apply)
+    let patch_tempfile = hooks::write_patch_tempfile(patch)?;
+    let hook_env = std::collections::HashMap::from([(
+        "DEVIT_PATCH",
+        patch_tempfile.display().to_string(),
+    )]);
+    let pre_apply_result = hooks::run(cfg, hooks::Point::PreApply, &hook_env);
+    let _ = std::fs::remove_file(&patch_tempfile);
+    if let Err(f) = pre_apply_result {
+        return exit_code::fail_with(
+            exit_code::ExitCode::PrecommitFailed,
+            "hook_failed",
+            format!("hook pre_apply `{}` a échoué (exit {})", f.command, f.exit_code),
+            serde_json::json!({"point": f.point, "command": f.command, "exit_code": f.exit_code, "stderr": f.stderr}),
+        );
+    }
+    // 4) apply (worktree only / staged / staged+commit)
+    let apply_progress = progress::Progress::start(json, "apply");
+    if worktree {
+        let applied = git::apply_worktree(patch)?;
+        apply_progress.finish();
+        if !applied {
+            anyhow::bail!("Échec git apply (worktree).");
+        }
+        return finish(
+            json,
+            &format!("✅ Patch appliqué au worktree ({summary}), rien n'est indexé ni commité."),
+            serde_json::json!({"mode": "worktree", "summary": summary}),
+        );
+    }
+    let indexed = git::apply_index(patch)?;
+    apply_progress.finish();
+    if !indexed {
+        anyhow::bail!("Échec git apply --index.");
+    }
+    if no_commit {
+        // No commit to attach a footer/note to yet, but the diff is staged
+        // and immutable at this point, so attest it now rather than
+        // waiting for a commit that may never happen.
+        let attest = compute_attest_hash(patch);
+        journal_event(&Event::Attest {
+            hash: attest.clone(),
+        })?;
+        return finish(
+            json,
+            &format!("✅ Patch indexé ({summary}), commit laissé de côté (--no-commit)."),
+            serde_json::json!({"mode": "staged", "summary": summary, "attest": attest}),
+        );
+    }
+    let commit_msg = default_commit_msg(goal, &summary);
+    let attest = compute_attest_hash(patch);
+    let full_msg = if cfg.provenance.footer {
+        let mut footers = vec![format!("DevIt-Attest: {}", attest)];
+        if let Some(sbom) = sbom_footer() {
+            footers.push(sbom);
+        }
+        footers.extend(attribution_footers(cfg));
+        format!("{}\n\n{}", commit_msg, footers.join("\n"))
+    } else {
+        commit_msg.clone()
+    };
+    git::commit(&full_msg)?;
+    if cfg.git.use_notes {
+        let _ = git::add_note(&format!("DevIt-Attest: {}", attest));
+    }
+    journal_event(&Event::Attest {
+        hash: attest.clone(),
+    })?;
+    let sha = git::head_short().unwrap_or_default();
+    let post_commit_env = std::collections::HashMap::from([
+        ("DEVIT_PATCH", hooks::write_patch_tempfile(patch)?.display().to_string()),
+        ("DEVIT_SHA", sha.clone()),
+    ]);
+    let post_commit_result = hooks::run(cfg, hooks::Point::PostCommit, &post_commit_env);
+    let _ = std::fs::remove_file(&post_commit_env["DEVIT_PATCH"]);
+    if let Err(f) = post_commit_result {
+        return exit_code::fail_with(
+            exit_code::ExitCode::PrecommitFailed,
+            "hook_failed",
+            format!("hook post_commit `{}` a échoué (exit {})", f.command, f.exit_code),
+            serde_json::json!({"point": f.point, "command": f.command, "exit_code": f.exit_code, "stderr": f.stderr}),
+        );
+    }
+    finish(
+        json,
+        &format!("✅ Commit {}: {}", sha, commit_msg),
+        serde_json::json!({"mode": "commit", "sha": sha, "message": commit_msg}),
+    )
+}
+
+/// `devit apply --batch DIR`: dry-run every patch file in `dir` (sorted by
+/// name), refuse if two patches touch the same file, then apply+commit them
+/// one by one in that order.
+fn apply_batch(dir: &str, cfg: &Config, yes: bool, force: bool, json: bool) -> Result<()> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .with_context(|| format!("impossible de lire le dossier {dir}"))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .collect();
+    paths.sort();
+    if paths.is_empty() {
+        anyhow::bail!("aucun fichier de patch trouvé dans {dir}");
+    }
+
+    let mut patches = Vec::with_capacity(paths.len());
+    for path in &paths {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("lecture de {}", path.display()))?;
+        patches.push((path.clone(), text));
+    }
+
+    if !git::is_worktree_clean() && !force {
+        anyhow::bail!(
+            "Le worktree ou l'index contient des modifications.\n\
+             - Commit/stash tes changements OU relance avec --force (tentative 3-way)."
+        );
+    }
+
+    // 1) dry-run every patch and refuse overlapping files between them
+    let mut owner: std::collections::HashMap<String, PathBuf> = std::collections::HashMap::new();
+    let mut per_patch_ns = Vec::with_capacity(patches.len());
+    for (path, patch) in &patches {
+        git::apply_check(patch)
+            .with_context(|| format!("{}: dry-run git apply --check a échoué", path.display()))?;
+        let ns = git::numstat(patch)?;
+        for entry in &ns {
+            if let Some(prev) = owner.insert(entry.path.clone(), path.clone()) {
+                anyhow::bail!(
+                    "conflit: {} est touché à la fois par {} et {}",
+                    entry.path,
+                    prev.display(),
+                    path.display()
+                );
+            }
+        }
+        per_patch_ns.push(ns);
+    }
+
+    // 3) one combined approval prompt for the whole batch
+    let total_files: usize = per_patch_ns.iter().map(|ns| ns.len()).sum();
+    let total_added: u64 = per_patch_ns.iter().flatten().map(|e| e.added).sum();
+    let total_deleted: u64 = per_patch_ns.iter().flatten().map(|e| e.deleted).sum();
+    let must_ask = !yes && cfg.policy.approval.to_lowercase() != "never";
+    if must_ask {
+        eprintln!(
+            "Batch prêt: {} patch(es), {} fichier(s), +{}, -{}",
+            patches.len(),
+            total_files,
+            total_added,
+            total_deleted
+        );
+        for (path, _) in &patches {
+            eprintln!("  - {}", path.display());
+        }
+        if !ask_approval()? {
+            return exit_code::fail(exit_code::ExitCode::ApprovalRequired, "approval_required", t(MsgKey::ApplyCancelled));
+        }
+    }
+
+    // 4) apply + commit each patch in order
+    let mut shas = Vec::with_capacity(patches.len());
+    for ((path, patch), ns) in patches.iter().zip(per_patch_ns.iter()) {
+        if !git::apply_index(patch)? {
+            anyhow::bail!("{}: échec git apply --index.", path.display());
+        }
+        let added: u64 = ns.iter().map(|e| e.added).sum();
+        let deleted: u64 = ns.iter().map(|e| e.deleted).sum();
+        let summary = format!("{} fichier(s), +{}, -{}", ns.len(), added, deleted);
+        let commit_msg = default_commit_msg(None, &summary);
+        let attest = compute_attest_hash(patch);
+        let full_msg = if cfg.provenance.footer {
+            let mut footers = vec![format!("DevIt-Attest: {}", attest)];
+            if let Some(sbom) = sbom_footer() {
+                footers.push(sbom);
+            }
+            footers.extend(attribution_footers(cfg));
+            format!("{}\n\n{}", commit_msg, footers.join("\n"))
+        } else {
+            commit_msg.clone()
+        };
+        git::commit(&full_msg)
+            .with_context(|| format!("{}: échec git commit", path.display()))?;
+        if cfg.git.use_notes {
+            let _ = git::add_note(&format!("DevIt-Attest: {}", attest));
+        }
+        journal_event(&Event::Attest {
+            hash: attest.clone(),
+        })?;
+        let sha = git::head_short().unwrap_or_default();
+        eprintln!("✅ {} -> commit {}: {}", path.display(), sha, commit_msg);
+        shas.push(sha);
+    }
+
+    finish(
+        json,
+        &format!(
+            "✅ Batch terminé: {} patch(es) commité(s) ({})",
+            shas.len(),
+            shas.join(", ")
+        ),
+        serde_json::json!({"commits": shas}),
+    )
+}
+
+/// `devit apply --split-commits`: dry-run the whole patch, split it into
+/// per-scope groups (`patch_filter::split_by_scope`, the same crate/
+/// directory inference `devit commit-msg` uses), then apply+commit each
+/// group as its own Conventional Commit with a freshly generated message.
+fn apply_split_commits(
+    input: &str,
+    only: Option<&str>,
+    exclude: Option<&str>,
+    cfg: &Config,
+    yes: bool,
+    force: bool,
+    json: bool,
+) -> Result<()> {
+    let mut patch = read_patch(input)?;
+    if only.is_some() || exclude.is_some() {
+        patch = patch_filter::filter_patch(&patch, only, exclude)?;
+        if patch.trim().is_empty() {
+            anyhow::bail!("Aucun fichier ne correspond aux filtres --only/--exclude.");
+        }
+    }
+    if !git::is_worktree_clean() && !force {
+        anyhow::bail!(
+            "Le worktree ou l'index contient des modifications.\n\
+             - Commit/stash tes changements OU relance avec --force (tentative 3-way)."
+        );
+    }
+    let groups = patch_filter::split_by_scope(&patch)?;
+    if groups.is_empty() {
+        anyhow::bail!("Patch vide, rien à scinder.");
+    }
+
+    let mut per_group_ns = Vec::with_capacity(groups.len());
+    for (scope, group_patch) in &groups {
+        git::apply_check(group_patch)
+            .with_context(|| format!("{scope}: dry-run git apply --check a échoué"))?;
+        per_group_ns.push(git::numstat(group_patch)?);
+    }
+
+    let total_files: usize = per_group_ns.iter().map(|ns| ns.len()).sum();
+    let total_added: u64 = per_group_ns.iter().flatten().map(|e| e.added).sum();
+    let total_deleted: u64 = per_group_ns.iter().flatten().map(|e| e.deleted).sum();
+    let must_ask = !yes && cfg.policy.approval.to_lowercase() != "never";
+    if must_ask {
+        eprintln!(
+            "Split en {} commit(s), {} fichier(s), +{}, -{}",
+            groups.len(),
+            total_files,
+            total_added,
+            total_deleted
+        );
+        for (scope, _) in &groups {
+            eprintln!("  - {}", scope);
+        }
+        if !ask_approval()? {
+            return exit_code::fail(exit_code::ExitCode::ApprovalRequired, "approval_required", t(MsgKey::ApplyCancelled));
+        }
+    }
+
+    let commit_style = cfg
+        .commit
+        .as_ref()
+        .and_then(|c| c.style.clone())
+        .unwrap_or_else(|| "conventional".to_string());
+    let commit_subject_template = cfg.commit.as_ref().and_then(|c| c.subject_template.clone());
+    let max_subject = cfg.commit.as_ref().map(|c| c.max_subject).unwrap_or(72usize);
+    let scopes_alias = cfg.commit.as_ref().map(|c| c.scopes_alias.clone());
+
+    let mut shas = Vec::with_capacity(groups.len());
+    for ((scope, group_patch), ns) in groups.iter().zip(per_group_ns.iter()) {
+        if !git::apply_index(group_patch)? {
+            anyhow::bail!("{scope}: échec git apply --index.");
+        }
+        let added: u64 = ns.iter().map(|e| e.added).sum();
+        let deleted: u64 = ns.iter().map(|e| e.deleted).sum();
+        let summary = format!("{} fichier(s), +{}, -{}", ns.len(), added, deleted);
+        let staged_paths: Vec<PathBuf> = ns.iter().map(|e| PathBuf::from(&e.path)).collect();
+        let msg_input = commit_msg::MsgInput {
+            staged_paths,
+            diff_summary: Some(summary.clone()),
+            forced_type: None,
+            forced_scope: Some(scope.clone()),
+            max_subject,
+            template_body: None,
+            scopes_alias: scopes_alias.clone(),
+            issue_prefixes: Vec::new(),
+        };
+        let msg = commit_msg::generate_struct(&msg_input)?;
+        let subject_line =
+            commit_msg::format_subject_line(&msg, &commit_style, commit_subject_template.as_deref());
+        let attest = compute_attest_hash(group_patch);
+        let full_msg = if cfg.provenance.footer {
+            let mut footers = vec![format!("DevIt-Attest: {}", attest)];
+            if let Some(sbom) = sbom_footer() {
+                footers.push(sbom);
+            }
+            footers.extend(attribution_footers(cfg));
+            format!("{}\n\n{}", subject_line, footers.join("\n"))
+        } else {
+            subject_line.clone()
+        };
+        git::commit(&full_msg).with_context(|| format!("{scope}: échec git commit"))?;
+        if cfg.git.use_notes {
+            let _ = git::add_note(&format!("DevIt-Attest: {}", attest));
+        }
+        journal_event(&Event::Attest {
+            hash: attest.clone(),
+        })?;
+        let sha = git::head_short().unwrap_or_default();
+        eprintln!("✅ {} -> commit {}: {}", scope, sha, subject_line);
+        shas.push(sha);
+    }
+
+    finish(
+        json,
+        &format!(
+            "✅ Split terminé: {} commit(s) créé(s) ({})",
+            shas.len(),
+            shas.join(", ")
+        ),
+        serde_json::json!({"mode": "split-commits", "commits": shas}),
+    )
+}
+
+/// `devit rebase assist`: drive an interrupted `git rebase` to completion,
+/// one stopped commit at a time -- explain its conflicts (AST/rerere/LLM,
+/// same pipeline as `devit merge explain`), propose and apply a plan, run
+/// impacted tests on the touched files, then `git rebase --continue`. Each
+/// commit gets its own approval checkpoint before anything is written.
+/// Human-readable preview of a [`merge_assist::ResolutionItem`] resolution
+/// for the `rebase assist` approval checkpoint -- the keyword as-is, or the
+/// first line of literal merged text (LLM/auto-resolved) so an approver can
+/// tell a "keep_both" duplication from an actual merge before committing to
+/// it.
+fn describe_resolution(resolution: &str) -> String {
+    match resolution {
+        "ours" | "theirs" | "keep_both" => resolution.to_string(),
+        text => {
+            let first_line = text.lines().next().unwrap_or("").trim();
+            let truncated: String = first_line.chars().take(60).collect();
+            let more = first_line.chars().count() > 60 || text.lines().count() > 1;
+            format!("texte fusionné: {truncated}{}", if more { "…" } else { "" })
+        }
+    }
+}
+
+async fn rebase_assist(agent: &Agent, cfg: &Config, yes: bool, json: bool) -> Result<()> {
+    ensure_git_repo()?;
+    if !git::rebase_in_progress() {
+        anyhow::bail!("Aucun rebase en cours (.git/rebase-merge ou .git/rebase-apply absent).");
+    }
+    let must_ask = !yes && cfg.policy.approval.to_lowercase() != "never";
+    let mut continued = 0u32;
+    loop {
+        let mut conf = merge_assist::explain(&Vec::new())?;
+        enrich_with_llm(agent, &mut conf).await;
+        let plan = merge_assist::propose_llm(&conf);
+        if must_ask {
+            if conf.is_empty() {
+                eprintln!("Rebase: aucun conflit pour ce commit.");
+            } else {
+                eprintln!("Rebase: {} fichier(s) en conflit:", conf.len());
+                for fc in &conf {
+                    eprintln!("  - {} ({} hunk(s))", fc.path, fc.hunks.len());
+                    if let Some(items) = plan.get(&fc.path) {
+                        for item in items {
+                            eprintln!(
+                                "      hunk {}: {}",
+                                item.hunk_index,
+                                describe_resolution(&item.resolution)
+                            );
+                        }
+                    }
+                }
+            }
+            if !ask_approval()? {
+                return exit_code::fail(
+                    exit_code::ExitCode::ApprovalRequired,
+                    "approval_required",
+                    t(MsgKey::ApplyCancelled),
+                );
+            }
+        }
+        if !conf.is_empty() {
+            merge_assist::apply_plan(&plan).map_err(|e| anyhow::anyhow!(e.to_string()))?;
+            git::add_all()?;
+            let changed: Vec<String> = conf.iter().map(|fc| fc.path.clone()).collect();
+            let opts = test_runner::ImpactedOpts {
+                changed_from: None,
+                changed_paths: Some(changed),
+                max_jobs: None,
+                framework: Some("auto".into()),
+                timeout_secs: None,
+                retries: None,
+                shards: None,
+                shard_index: None,
+                custom_command: cfg.test.impacted_command.clone(),
+                custom_env: cfg.test.env.clone(),
+            };
+            let rep = test_runner::run_impacted(&opts)?;
+            if rep.failed > 0 {
+                return exit_code::fail_with(
+                    exit_code::ExitCode::TestsFailed,
+                    "tests_failed",
+                    format!("tests impactés en échec après résolution ({} failed)", rep.failed),
+                    serde_json::json!({"logs_path": rep.logs_path}),
+                );
+            }
+        }
+        git::rebase_continue()?;
+        continued += 1;
+        if !git::rebase_in_progress() {
+            break;
+        }
+    }
+    finish(
+        json,
+        &format!("✅ Rebase terminé ({continued} commit(s) rejoué(s))"),
+        serde_json::json!({"mode": "rebase-assist", "commits_continued": continued}),
+    )
+}
+
 fn ensure_git_repo() -> Result<()> {
     if !git::is_git_available() {
-        anyhow::bail!("git n'est pas disponible dans le PATH.");
+        anyhow::bail!(t(MsgKey::GitUnavailable));
     }
     if !git::in_repo() {
-        anyhow::bail!("pas dans un dépôt git (git rev-parse --is-inside-work-tree).");
+        anyhow::bail!(t(MsgKey::NotGitRepo));
     }
     Ok(())
 }
@@ -996,6 +3126,23 @@ fn default_commit_msg(goal: Option<&str>, summary: &str) -> String {
     }
 }
 
+/// Terminal success message for a command: under `--json`, emit a single
+/// `tool.result` document on stdout instead of `human` (which still goes to
+/// stderr so scripts piping stdout never see mixed prose).
+fn finish(json: bool, human: &str, data: serde_json::Value) -> Result<()> {
+    if json {
+        eprintln!("{human}");
+        emit_json(&serde_json::json!({
+            "type": "tool.result",
+            "ok": true,
+            "result": data,
+        }))
+    } else {
+        println!("{human}");
+        Ok(())
+    }
+}
+
 fn emit_json(value: &serde_json::Value) -> Result<()> {
     let mut stdout = std::io::stdout().lock();
     serde_json::to_writer(&mut stdout, value)?;
@@ -1050,6 +3197,27 @@ fn compute_attest_hash(patch: &str) -> String {
     hex::encode(out)
 }
 
+/// `DevIt-SBOM: <sha256>` footer line referencing `.devit/reports/sbom.json`
+/// (from `devit report sbom`), appended alongside `DevIt-Attest` when the
+/// file exists -- `None` when no SBOM has been generated yet.
+fn sbom_footer() -> Option<String> {
+    sbom::sha256_hex(std::path::Path::new(".devit/reports/sbom.json"))
+        .map(|hash| format!("DevIt-SBOM: {hash}"))
+}
+
+/// `Co-authored-by`/`DevIt-Model` trailers for `[provenance] co_author`/
+/// `attribute_model`, appended alongside `DevIt-Attest`/`DevIt-SBOM`.
+fn attribution_footers(cfg: &Config) -> Vec<String> {
+    let mut out = Vec::new();
+    if let Some(co_author) = &cfg.provenance.co_author {
+        out.push(format!("Co-authored-by: {co_author}"));
+    }
+    if cfg.provenance.attribute_model {
+        out.push(format!("DevIt-Model: {}", cfg.backend.model));
+    }
+    out
+}
+
 fn compute_call_attest(tool: &str, args: &serde_json::Value) -> Result<String> {
     // HMAC(tool_name, sha256(args_json), timestamp_ms)
     let ts_ms: u128 = std::time::SystemTime::now()
@@ -1111,12 +3279,32 @@ fn journal_event(ev: &Event) -> Result<()> {
     Ok(())
 }
 
+/// Whether `.devit/journal.jsonl` records an `Attest` event for `hash`.
+fn journal_has_attest(hash: &str) -> Result<bool> {
+    let jpath = Path::new(".devit/journal.jsonl");
+    if !jpath.exists() {
+        return Ok(false);
+    }
+    let text = fs::read_to_string(jpath)?;
+    for line in text.lines() {
+        let Ok(rec) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        if rec["event"]["Attest"]["hash"].as_str() == Some(hash) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
 fn build_context_index_adv(
     root: &str,
     max_bytes_per_file: Option<usize>,
     max_files: Option<usize>,
     ext_allow: Option<&str>,
     json_out: Option<&Path>,
+    watch: bool,
+    cfg: &Config,
 ) -> Result<PathBuf> {
     let dir = ensure_devit_dir()?;
     let out = json_out
@@ -1138,13 +3326,21 @@ fn build_context_index_adv(
         }),
         timeout,
         out_path: out.clone(),
+        scoring: scoring_rules_from_cfg(cfg),
     };
+    if watch {
+        crate::context::watch_index(Path::new(root), &opts)?;
+        return Ok(out);
+    }
     match crate::context::generate_index(Path::new(root), &opts) {
         Ok(w) => Ok(w),
         Err(e) => {
             if e.to_string().contains("timeout") {
-                eprintln!("error: context map timeout");
-                std::process::exit(124);
+                return exit_code::fail(
+                    exit_code::ExitCode::Timeout,
+                    "timeout",
+                    "context map timeout",
+                );
             }
             Err(e)
         }
@@ -1153,6 +3349,27 @@ fn build_context_index_adv(
 
 // legacy helper removed; scanning now handled in context module
 
+/// `fs_patch_apply`'s optional `triage_on_failure` step: best-effort, never
+/// fails the apply itself. Feeds the applied diff and the JUnit report for
+/// the failing impacted-test run to the agent and writes its root-cause
+/// hypothesis plus suggested (not applied) fix patch to
+/// `.devit/reports/triage.md`.
+fn triage_test_failure(cfg: &Config, patch: &str, junit_path: &str) {
+    let diff_head = patch.lines().take(120).collect::<Vec<_>>().join("\n");
+    let test_output = std::fs::read_to_string(junit_path).unwrap_or_default();
+    let test_output: String = test_output.lines().take(200).collect::<Vec<_>>().join("\n");
+    let agent = Agent::new(cfg.clone());
+    let fut = agent.triage_test_failure(&diff_head, &test_output);
+    let result = tokio::runtime::Handle::current().block_on(async {
+        tokio::time::timeout(std::time::Duration::from_secs(20), fut).await
+    });
+    if let Ok(Ok(triage)) = result {
+        println!("🩺 Triage:\n{}", triage);
+        let _ = std::fs::create_dir_all(".devit/reports");
+        let _ = std::fs::write(".devit/reports/triage.md", triage);
+    }
+}
+
 fn tool_call_json(
     cfg: &Config,
     name: &str,
@@ -1175,6 +3392,14 @@ fn tool_call_json(
                 .get("precommit_only")
                 .and_then(|v| v.as_bool())
                 .unwrap_or(false);
+            let no_cache = args
+                .get("no_cache")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let autofix = args
+                .get("autofix")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
             let precommit_mode = args
                 .get("precommit")
                 .and_then(|v| v.as_str())
@@ -1193,6 +3418,10 @@ fn tool_call_json(
                 .get("allow_apply_on_tests_fail")
                 .and_then(|v| v.as_bool())
                 .unwrap_or(false);
+            let triage_on_failure = args
+                .get("triage_on_failure")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
             let check_only = args
                 .get("check_only")
                 .and_then(|v| v.as_bool())
@@ -1229,16 +3458,43 @@ fn tool_call_json(
             if patch.is_empty() {
                 anyhow::bail!("fs_patch_apply: champ 'patch' requis (contenu du diff)");
             }
+            // Secrets gate: scan the patch itself, not just the staged
+            // index, so `fs_patch_apply` vetoes before anything lands.
+            {
+                let findings = secrets_scan::scan_patch(patch);
+                let _ = std::fs::create_dir_all(".devit/reports");
+                let _ = std::fs::write(
+                    ".devit/reports/secrets.sarif.json",
+                    serde_json::to_vec_pretty(&secrets_scan::to_sarif(&findings))?,
+                );
+                let qcfg: devit_common::QualityCfg = std::fs::read_to_string(".devit/devit.toml")
+                    .ok()
+                    .and_then(|s| toml::from_str::<toml::Value>(&s).ok())
+                    .and_then(|tbl| tbl.get("quality").cloned())
+                    .and_then(|v| v.try_into().ok())
+                    .unwrap_or_default();
+                if findings.len() as u32 > qcfg.max_secrets {
+                    return exit_code::fail_with(
+                        exit_code::ExitCode::SecretsFound,
+                        "secrets_found",
+                        format!("secrets gate failed ({} finding(s))", findings.len()),
+                        serde_json::json!({"findings": findings.len(), "out": ".devit/reports/secrets.sarif.json"}),
+                    );
+                }
+            }
             // Precommit gate
+            let precommit_opts = precommit::RunOptions { no_cache, autofix };
             if precommit_only {
-                match precommit::run(cfg) {
+                match precommit::run(cfg, precommit_opts) {
                     Ok(()) => return Ok(serde_json::json!({"precommit_ok": true})),
-                    Err(f) => anyhow::bail!(format!(
-                        "{}",
-                        serde_json::json!({
-                            "precommit_failed": true, "tool": f.tool, "exit_code": f.exit_code, "stderr": f.stderr
-                        })
-                    )),
+                    Err(f) => {
+                        return exit_code::fail_with(
+                            exit_code::ExitCode::PrecommitFailed,
+                            "precommit_failed",
+                            format!("precommit gate failed ({})", f.tool),
+                            serde_json::json!({"tool": f.tool, "exit_code": f.exit_code, "stderr": f.stderr}),
+                        )
+                    }
                 }
             }
             // decide precommit enabled
@@ -1256,39 +3512,24 @@ fn tool_call_json(
             if no_precommit && precommit_enabled {
                 // Bypass policy check
                 if !yes || !precommit::bypass_allowed(cfg) {
-                    anyhow::bail!(format!(
-                        "{}",
-                        serde_json::json!({
-                            "approval_required": true, "policy": "on_request", "phase": "pre", "reason": "precommit_bypass"
-                        })
-                    ));
+                    return exit_code::fail_with(
+                        exit_code::ExitCode::ApprovalRequired,
+                        "approval_required",
+                        "bypassing the precommit gate requires approval",
+                        serde_json::json!({"policy": "on_request", "phase": "pre", "reason": "precommit_bypass"}),
+                    );
                 }
             } else if precommit_enabled {
-                if let Err(f) = precommit::run(cfg) {
-                    // write precommit report
-                    let _ = std::fs::create_dir_all(".devit/reports");
-                    let _ = std::fs::write(
-                        ".devit/reports/precommit.json",
-                        serde_json::to_vec(&serde_json::json!({
-                            "precommit_failed": true, "tool": f.tool, "exit_code": f.exit_code
-                        }))
-                        .unwrap_or_default(),
+                // `precommit::run` writes the detailed per-tool timing
+                // report to `.devit/reports/precommit.json` itself.
+                if let Err(f) = precommit::run(cfg, precommit_opts) {
+                    return exit_code::fail_with(
+                        exit_code::ExitCode::PrecommitFailed,
+                        "precommit_failed",
+                        format!("precommit gate failed ({})", f.tool),
+                        serde_json::json!({"tool": f.tool, "exit_code": f.exit_code, "stderr": f.stderr}),
                     );
-                    anyhow::bail!(format!(
-                        "{}",
-                        serde_json::json!({
-                            "precommit_failed": true, "tool": f.tool, "exit_code": f.exit_code, "stderr": f.stderr
-                        })
-                    ));
                 }
-                let _ = std::fs::create_dir_all(".devit/reports");
-                let _ = std::fs::write(
-                    ".devit/reports/precommit.json",
-                    serde_json::to_vec(&serde_json::json!({
-                        "ok": true
-                    }))
-                    .unwrap_or_default(),
-                );
             }
             git::apply_check(patch)?;
             if check_only {
@@ -1296,7 +3537,7 @@ fn tool_call_json(
             }
             let ask = requires_approval_tool(&cfg.policy, "git", yes, "write");
             if ask && !ask_approval()? {
-                anyhow::bail!("Annulé par l'utilisateur.");
+                return exit_code::fail(exit_code::ExitCode::ApprovalRequired, "approval_required", t(MsgKey::ApplyCancelled));
             }
             let ok = match mode {
                 "worktree" => git::apply_worktree(patch)?,
@@ -1320,6 +3561,11 @@ fn tool_call_json(
                     max_jobs: None,
                     framework: Some("auto".into()),
                     timeout_secs: Some(tests_timeout_secs),
+                    retries: None,
+                    shards: None,
+                    shard_index: None,
+                    custom_command: cfg.test.impacted_command.clone(),
+                    custom_env: cfg.test.env.clone(),
                 };
                 match test_runner::run_impacted(&opts) {
                     Ok(rep) => {
@@ -1327,6 +3573,9 @@ fn tool_call_json(
                             "ok": true, "framework": rep.framework, "ran": rep.ran, "failed": rep.failed, "logs_path": rep.logs_path
                         })).unwrap_or_default());
                         if rep.failed > 0 {
+                            if triage_on_failure {
+                                triage_test_failure(cfg, patch, &rep.logs_path);
+                            }
                             if !allow_apply_on_tests_fail {
                                 // revert
                                 use std::io::Write as _;
@@ -1418,6 +3667,11 @@ fn tool_call_json(
             };
             // scope alias mapping
             let scopes_alias = cfg.commit.as_ref().map(|c| c.scopes_alias.clone());
+            let issue_prefixes = cfg
+                .commit
+                .as_ref()
+                .map(|c| c.issue_prefixes.clone())
+                .unwrap_or_default();
             let input = crate::commit_msg::MsgInput {
                 staged_paths,
                 diff_summary: None,
@@ -1426,6 +3680,7 @@ fn tool_call_json(
                 max_subject,
                 template_body,
                 scopes_alias,
+                issue_prefixes,
             };
             let mut msg = crate::commit_msg::generate_struct(&input)
                 .map_err(|e| anyhow::anyhow!(e.to_string()))?;
@@ -1447,19 +3702,49 @@ fn tool_call_json(
                     }
                 }
             }
+            // Optional LLM body synthesis ([commit] llm_body; 2s timeout,
+            // falls back to the heuristic/template body on error/empty).
+            if cfg.commit.as_ref().map(|c| c.llm_body).unwrap_or(false) && msg.body.trim().is_empty() {
+                let ns = git::numstat(patch).unwrap_or_default();
+                let files = ns.len();
+                let added: u64 = ns.iter().map(|e| e.added).sum();
+                let deleted: u64 = ns.iter().map(|e| e.deleted).sum();
+                let summary_llm = format!("{} file(s), +{}, -{}", files, added, deleted);
+                let diff_head = patch.lines().take(120).collect::<Vec<_>>().join("\n");
+                let agent = devit_agent::Agent::new(cfg.clone());
+                let fut = agent.commit_body("", &summary_llm, &diff_head);
+                if let Ok(Ok(b)) = tokio::runtime::Handle::current().block_on(async {
+                    tokio::time::timeout(std::time::Duration::from_secs(2), fut).await
+                }) {
+                    if !b.trim().is_empty() {
+                        msg.body = b;
+                    }
+                }
+            }
             // provenance footer
             if cfg.provenance.footer && !no_prov_footer {
                 let hash = compute_attest_hash(patch);
                 msg.footers.push(format!("DevIt-Attest: {}", hash));
+                if let Some(sbom) = sbom_footer() {
+                    msg.footers.push(sbom);
+                }
+                msg.footers.extend(attribution_footers(cfg));
                 let _ = journal_event(&Event::Attest { hash });
             }
             let msg_path = ".git/COMMIT_EDITMSG";
             // build commit message text
-            let subject_line = if let Some(sc) = &msg.scope {
-                format!("{}({}): {}", msg.ctype, sc, msg.subject)
-            } else {
-                format!("{}: {}", msg.ctype, msg.subject)
-            };
+            let commit_style = cfg
+                .commit
+                .as_ref()
+                .and_then(|c| c.style.clone())
+                .unwrap_or_else(|| "conventional".to_string());
+            let commit_subject_template =
+                cfg.commit.as_ref().and_then(|c| c.subject_template.clone());
+            let subject_line = crate::commit_msg::format_subject_line(
+                &msg,
+                &commit_style,
+                commit_subject_template.as_deref(),
+            );
             let body = msg.body.clone();
             let foot = if msg.footers.is_empty() {
                 String::new()
@@ -1500,12 +3785,12 @@ fn tool_call_json(
             }
             // approval for commit step (safe requires --yes)
             if profile == "safe" && !yes {
-                anyhow::bail!(format!(
-                    "{}",
-                    serde_json::json!({
-                        "approval_required": true, "policy": "on_request", "phase": "pre", "reason": "commit"
-                    })
-                ));
+                return exit_code::fail_with(
+                    exit_code::ExitCode::ApprovalRequired,
+                    "approval_required",
+                    "committing under the safe profile requires approval",
+                    serde_json::json!({"policy": "on_request", "phase": "pre", "reason": "commit"}),
+                );
             }
             // write message file
             std::fs::write(msg_path, &full)
@@ -1519,12 +3804,12 @@ fn tool_call_json(
             let out = cmd.output().map_err(|e| anyhow::anyhow!(e))?;
             if !out.status.success() {
                 let stderr = String::from_utf8_lossy(&out.stderr).to_string();
-                anyhow::bail!(format!(
-                    "{}",
-                    serde_json::json!({
-                        "git_commit_failed": true, "exit_code": out.status.code().unwrap_or(1), "stderr": stderr
-                    })
-                ));
+                return exit_code::fail_with(
+                    exit_code::ExitCode::GenericError,
+                    "git_commit_failed",
+                    "git commit failed",
+                    serde_json::json!({"exit_code": out.status.code().unwrap_or(1), "stderr": stderr}),
+                );
             }
             let sha = git::head_short().unwrap_or_default();
             // Write commit_meta.json reflecting committed SHA
@@ -1557,9 +3842,34 @@ fn tool_call_json(
             }
             let ask = requires_approval_tool(&cfg.policy, "shell", yes, "exec");
             if ask && !ask_approval()? {
-                anyhow::bail!("Annulé par l'utilisateur.");
+                return exit_code::fail(exit_code::ExitCode::ApprovalRequired, "approval_required", t(MsgKey::ApplyCancelled));
             }
-            let (code, out) = sandbox::run_shell_sandboxed_capture(cmd, &cfg.policy, &cfg.sandbox)?;
+            let on_audit = |a: &sandbox::ExecAudit| {
+                let _ = journal_event(&Event::ToolCall {
+                    name: "shell_exec".into(),
+                    args: args.clone(),
+                });
+                let _ = journal_event(&Event::CommandOut {
+                    line: serde_json::json!({
+                        "cmd": a.cmd,
+                        "cwd": a.cwd.display().to_string(),
+                        "exit_code": a.exit_code,
+                        "duration_ms": a.duration_ms,
+                        "output_sha256": a.output_sha256,
+                        "user_cpu_ms": a.usage.user_cpu_ms,
+                        "sys_cpu_ms": a.usage.sys_cpu_ms,
+                        "max_rss_kb": a.usage.max_rss_kb,
+                    })
+                    .to_string(),
+                });
+            };
+            let (code, out) = sandbox::run_shell_sandboxed_capture(
+                cmd,
+                &cfg.policy,
+                &cfg.sandbox,
+                &cfg.secrets,
+                Some(&on_audit),
+            )?;
             // provenance: attest shell_exec call (tool+args+ts)
             if let Ok(hash) = compute_call_attest("shell_exec", &args) {
                 let _ = journal_event(&Event::Attest { hash });
@@ -1570,6 +3880,7 @@ fn tool_call_json(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn tool_call_legacy(
     cfg: &Config,
     name: &str,
@@ -1577,6 +3888,8 @@ fn tool_call_legacy(
     yes: bool,
     no_precommit: bool,
     precommit_only: bool,
+    no_cache: bool,
+    autofix: bool,
     json_only: bool,
 ) -> Result<()> {
     if json_only {
@@ -1589,41 +3902,66 @@ fn tool_call_legacy(
                 anyhow::bail!("policy.sandbox=read-only: apply refusé (aucune écriture autorisée)");
             }
             let patch = read_patch(input)?;
+            {
+                let findings = secrets_scan::scan_patch(&patch);
+                let _ = std::fs::create_dir_all(".devit/reports");
+                let _ = std::fs::write(
+                    ".devit/reports/secrets.sarif.json",
+                    serde_json::to_vec_pretty(&secrets_scan::to_sarif(&findings))?,
+                );
+                let qcfg: devit_common::QualityCfg = std::fs::read_to_string(".devit/devit.toml")
+                    .ok()
+                    .and_then(|s| toml::from_str::<toml::Value>(&s).ok())
+                    .and_then(|tbl| tbl.get("quality").cloned())
+                    .and_then(|v| v.try_into().ok())
+                    .unwrap_or_default();
+                if findings.len() as u32 > qcfg.max_secrets {
+                    return exit_code::fail_with(
+                        exit_code::ExitCode::SecretsFound,
+                        "secrets_found",
+                        format!("secrets gate failed ({} finding(s))", findings.len()),
+                        serde_json::json!({"findings": findings.len(), "out": ".devit/reports/secrets.sarif.json"}),
+                    );
+                }
+            }
+            let precommit_opts = precommit::RunOptions { no_cache, autofix };
             if precommit_only {
-                match precommit::run(cfg) {
+                match precommit::run(cfg, precommit_opts) {
                     Ok(()) => {
                         println!("precommit_ok: true");
                         return Ok(());
                     }
-                    Err(f) => anyhow::bail!(format!(
-                        "{}",
-                        serde_json::json!({
-                            "precommit_failed": true, "tool": f.tool, "exit_code": f.exit_code, "stderr": f.stderr
-                        })
-                    )),
+                    Err(f) => {
+                        return exit_code::fail_with(
+                            exit_code::ExitCode::PrecommitFailed,
+                            "precommit_failed",
+                            format!("precommit gate failed ({})", f.tool),
+                            serde_json::json!({"tool": f.tool, "exit_code": f.exit_code, "stderr": f.stderr}),
+                        )
+                    }
                 }
             }
             if no_precommit {
                 if !yes || !precommit::bypass_allowed(cfg) {
-                    anyhow::bail!(format!(
-                        "{}",
-                        serde_json::json!({
-                            "approval_required": true, "policy": "on_request", "phase": "pre", "reason": "precommit_bypass"
-                        })
-                    ));
+                    return exit_code::fail_with(
+                        exit_code::ExitCode::ApprovalRequired,
+                        "approval_required",
+                        "bypassing the precommit gate requires approval",
+                        serde_json::json!({"policy": "on_request", "phase": "pre", "reason": "precommit_bypass"}),
+                    );
                 }
-            } else if let Err(f) = precommit::run(cfg) {
-                anyhow::bail!(format!(
-                    "{}",
-                    serde_json::json!({
-                        "precommit_failed": true, "tool": f.tool, "exit_code": f.exit_code, "stderr": f.stderr
-                    })
-                ));
+            } else if let Err(f) = precommit::run(cfg, precommit_opts) {
+                return exit_code::fail_with(
+                    exit_code::ExitCode::PrecommitFailed,
+                    "precommit_failed",
+                    format!("precommit gate failed ({})", f.tool),
+                    serde_json::json!({"tool": f.tool, "exit_code": f.exit_code, "stderr": f.stderr}),
+                );
             }
             git::apply_check(&patch)?;
             let ask = requires_approval_tool(&cfg.policy, "git", yes, "write");
             if ask && !ask_approval()? {
-                anyhow::bail!("Annulé par l'utilisateur.");
+                return exit_code::fail(exit_code::ExitCode::ApprovalRequired, "approval_required", t(MsgKey::ApplyCancelled));
             }
             if !git::apply_index(&patch)? {
                 anyhow::bail!("Échec git apply --index (patch-only).");
@@ -1644,14 +3982,18 @@ fn tool_call_legacy(
                     max_jobs: None,
                     framework: Some("auto".into()),
                     timeout_secs: Some(300),
+                    retries: None,
+                    shards: None,
+                    shard_index: None,
+                    custom_command: cfg.test.impacted_command.clone(),
+                    custom_env: cfg.test.env.clone(),
                 };
-                if let Ok(rep) = test_runner::run_impacted(&opts) {
-                    if rep.failed > 0 {
-                        anyhow::bail!(format!(
-                            "{}",
-                            serde_json::json!({"tests_failed": true, "report": ".devit/reports/junit.xml"})
-                        ));
-                    }
+                let rep = test_runner::run_impacted(&opts)?;
+                if rep.failed > 0 {
+                    anyhow::bail!(format!(
+                        "{}",
+                        serde_json::json!({"tests_failed": true, "report": ".devit/reports/junit.xml"})
+                    ));
                 }
             }
             let attest = compute_attest_hash(&patch);
@@ -1662,14 +4004,39 @@ fn tool_call_legacy(
         "shell_exec" => {
             let ask = requires_approval_tool(&cfg.policy, "shell", yes, "exec");
             if ask && !ask_approval()? {
-                anyhow::bail!("Annulé par l'utilisateur.");
+                return exit_code::fail(exit_code::ExitCode::ApprovalRequired, "approval_required", t(MsgKey::ApplyCancelled));
             }
             let cmd = if input == "-" {
                 anyhow::bail!("shell_exec requires a command string as input");
             } else {
                 input.to_string()
             };
-            let code = sandbox::run_shell_sandboxed(&cmd, &cfg.policy, &cfg.sandbox)?;
+            let on_audit = |a: &sandbox::ExecAudit| {
+                let _ = journal_event(&Event::ToolCall {
+                    name: "shell_exec".into(),
+                    args: serde_json::json!({"cmd": a.cmd}),
+                });
+                let _ = journal_event(&Event::CommandOut {
+                    line: serde_json::json!({
+                        "cmd": a.cmd,
+                        "cwd": a.cwd.display().to_string(),
+                        "exit_code": a.exit_code,
+                        "duration_ms": a.duration_ms,
+                        "output_sha256": a.output_sha256,
+                        "user_cpu_ms": a.usage.user_cpu_ms,
+                        "sys_cpu_ms": a.usage.sys_cpu_ms,
+                        "max_rss_kb": a.usage.max_rss_kb,
+                    })
+                    .to_string(),
+                });
+            };
+            let code = sandbox::run_shell_sandboxed(
+                &cmd,
+                &cfg.policy,
+                &cfg.sandbox,
+                &cfg.secrets,
+                Some(&on_audit),
+            )?;
             if code != 0 {
                 anyhow::bail!(format!("shell_exec exit code {code}"));
             }
@@ -1682,3 +4049,30 @@ fn tool_call_legacy(
         _ => anyhow::bail!(format!("outil inconnu: {name}")),
     }
 }
+
+#[cfg(test)]
+mod rebase_assist_tests {
+    use super::*;
+
+    #[test]
+    fn describe_resolution_keeps_keywords_as_is() {
+        assert_eq!(describe_resolution("ours"), "ours");
+        assert_eq!(describe_resolution("theirs"), "theirs");
+        assert_eq!(describe_resolution("keep_both"), "keep_both");
+    }
+
+    #[test]
+    fn describe_resolution_previews_literal_text() {
+        assert_eq!(
+            describe_resolution("fn foo() {}"),
+            "texte fusionné: fn foo() {}"
+        );
+    }
+
+    #[test]
+    fn describe_resolution_truncates_long_or_multiline_text() {
+        let long = "x".repeat(120);
+        assert!(describe_resolution(&long).ends_with('…'));
+        assert!(describe_resolution("line one\nline two").ends_with('…'));
+    }
+}
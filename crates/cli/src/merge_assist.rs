@@ -3,6 +3,19 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
 
+/// An LLM-proposed resolution for a single [`ConflictHunk`], attached by
+/// `devit merge explain` on a best-effort basis (see the `agent` call site
+/// in `main.rs`; this module stays free of any LLM/async dependency).
+/// `resolution` is either one of `propose_auto`'s keywords (`"ours"`,
+/// `"theirs"`, `"keep_both"`) or literal merged text, and is consumed
+/// verbatim by `apply_plan` once copied into a [`ResolutionItem`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmResolution {
+    pub resolution: String,
+    pub confidence: f32,
+    pub rationale: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConflictHunk {
     pub start_line: usize,
@@ -10,6 +23,59 @@ pub struct ConflictHunk {
     pub ours: String,
     pub base: Option<String>,
     pub theirs: String,
+    #[serde(default)]
+    pub llm_resolution: Option<LlmResolution>,
+    /// Literal merged text when [`semantic_merge_rust`] could auto-resolve
+    /// this hunk (both sides only *added* distinct top-level items) --
+    /// `explain` tries this before `devit merge explain` bothers asking the
+    /// LLM, and `propose_auto`/`propose_llm` prefer it over their own guess.
+    #[serde(default)]
+    pub auto_resolved: Option<String>,
+    /// `crate::rerere::fingerprint` of this hunk's `ours`/`theirs` text --
+    /// the resolution-memory lookup key. `apply_plan` records the resolution
+    /// actually applied under this same key.
+    pub fingerprint: String,
+    /// The resolution `crate::rerere` has on file for this exact conflict
+    /// from a previous `merge apply`, if any -- takes priority over every
+    /// other proposal strategy since it reflects a choice already made.
+    #[serde(default)]
+    pub remembered_resolution: Option<String>,
+}
+
+/// Rust top-level items (function/type/impl/mod names from
+/// [`crate::context::extract_symbols`], plus whole `use` lines) found in a
+/// source fragment, used to tell apart two sides that added *distinct*
+/// items from two sides that both touched the *same* one.
+fn rust_top_level_items(src: &str) -> Vec<String> {
+    let mut items: Vec<String> = crate::context::extract_symbols(src, "rust")
+        .into_iter()
+        .map(|s| s.name)
+        .collect();
+    items.extend(
+        src.lines()
+            .map(str::trim)
+            .filter(|l| l.starts_with("use "))
+            .map(str::to_string),
+    );
+    items
+}
+
+/// Structure-aware merge for a Rust conflict hunk: if both sides only
+/// *added* distinct top-level functions/types/imports -- no name touched
+/// by both -- keep both sides' text verbatim instead of falling back to
+/// manual or LLM resolution. Returns `None` (decline to auto-resolve) when
+/// either side has no recognizable top-level items or the two sides share
+/// a name, since that means the same item was edited on both sides.
+fn semantic_merge_rust(ours: &str, theirs: &str) -> Option<String> {
+    let ours_items = rust_top_level_items(ours);
+    let theirs_items = rust_top_level_items(theirs);
+    if ours_items.is_empty() || theirs_items.is_empty() {
+        return None;
+    }
+    if ours_items.iter().any(|i| theirs_items.contains(i)) {
+        return None;
+    }
+    Some(format!("{}\n{}", ours.trim_end(), theirs.trim_end()))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,6 +109,7 @@ pub fn explain(paths: &[String]) -> Result<Vec<FileConflicts>> {
     };
     let mut out = Vec::new();
     for p in targets {
+        let is_rust = crate::context::detect_lang(&p) == "rust";
         let s = fs::read_to_string(&p).with_context(|| format!("read {}", p))?;
         let mut hunks = Vec::new();
         let mut i = 0usize;
@@ -67,12 +134,23 @@ pub fn explain(paths: &[String]) -> Result<Vec<FileConflicts>> {
                 };
                 let ours = lines[start..sep].join("\n");
                 let theirs = lines[sep + 1..end].join("\n");
+                let auto_resolved = if is_rust {
+                    semantic_merge_rust(&ours, &theirs)
+                } else {
+                    None
+                };
+                let fingerprint = crate::rerere::fingerprint(&ours, &theirs);
+                let remembered_resolution = crate::rerere::propose(&fingerprint);
                 hunks.push(ConflictHunk {
                     start_line: start,
                     end_line: end,
                     ours,
                     base: None,
                     theirs,
+                    llm_resolution: None,
+                    auto_resolved,
+                    fingerprint,
+                    remembered_resolution,
                 });
                 i = end + 1;
                 continue;
@@ -113,16 +191,18 @@ pub fn propose_auto(conflicts: &[FileConflicts]) -> Plan {
     for fc in conflicts {
         let mut items = Vec::new();
         for (idx, h) in fc.hunks.iter().enumerate() {
-            let ours_n = h.ours.trim();
-            let theirs_n = h.theirs.trim();
-            let resolution = if ours_n == theirs_n {
-                "ours"
+            let resolution = if let Some(remembered) = &h.remembered_resolution {
+                remembered.clone()
+            } else if let Some(auto) = &h.auto_resolved {
+                auto.clone()
+            } else if h.ours.trim() == h.theirs.trim() {
+                "ours".into()
             } else {
-                "keep_both"
+                "keep_both".into()
             };
             items.push(ResolutionItem {
                 hunk_index: idx,
-                resolution: resolution.into(),
+                resolution,
             });
         }
         plan.insert(fc.path.clone(), items);
@@ -130,6 +210,35 @@ pub fn propose_auto(conflicts: &[FileConflicts]) -> Plan {
     plan
 }
 
+/// Build a [`Plan`] from whatever LLM resolutions `devit merge explain`
+/// managed to attach, falling back to `"keep_both"` per hunk that has none
+/// -- the LLM counterpart to `propose_auto`/`propose_minimal`.
+pub fn propose_llm(conflicts: &[FileConflicts]) -> Plan {
+    let mut plan = Plan::new();
+    for fc in conflicts {
+        let items = fc
+            .hunks
+            .iter()
+            .enumerate()
+            .map(|(idx, h)| ResolutionItem {
+                hunk_index: idx,
+                resolution: h
+                    .remembered_resolution
+                    .clone()
+                    .or_else(|| h.auto_resolved.clone())
+                    .unwrap_or_else(|| {
+                        h.llm_resolution
+                            .as_ref()
+                            .map(|r| r.resolution.clone())
+                            .unwrap_or_else(|| "keep_both".into())
+                    }),
+            })
+            .collect();
+        plan.insert(fc.path.clone(), items);
+    }
+    plan
+}
+
 pub fn apply_plan(plan: &Plan) -> Result<()> {
     for (path, items) in plan.iter() {
         let s = fs::read_to_string(path)?;
@@ -175,14 +284,23 @@ pub fn apply_plan(plan: &Plan) -> Result<()> {
                         }
                         out.push_str(&theirs);
                     }
-                    _ => {
+                    "keep_both" => {
                         if !out.is_empty() {
                             out.push('\n');
                         }
                         // keep both with a simple separator for clarity
                         out.push_str(&format!("{}\n// --- theirs ---\n{}", ours, theirs));
                     }
+                    llm_text => {
+                        // Anything else is a literal merged resolution, e.g.
+                        // LLM-proposed text from `propose_llm`.
+                        if !out.is_empty() {
+                            out.push('\n');
+                        }
+                        out.push_str(llm_text);
+                    }
                 }
+                crate::rerere::record(&crate::rerere::fingerprint(&ours, &theirs), choice);
                 i = end + 1;
                 hunk_idx += 1;
                 continue;
@@ -203,3 +321,185 @@ pub fn apply_plan(plan: &Plan) -> Result<()> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CWD_LOCK;
+
+    struct CwdGuard(std::path::PathBuf);
+
+    impl CwdGuard {
+        fn enter(dir: &Path) -> Self {
+            let prev = std::env::current_dir().unwrap();
+            std::env::set_current_dir(dir).unwrap();
+            Self(prev)
+        }
+    }
+
+    impl Drop for CwdGuard {
+        fn drop(&mut self) {
+            let _ = std::env::set_current_dir(&self.0);
+        }
+    }
+
+    fn conflict_file(path: &Path, ours: &str, theirs: &str) {
+        let contents = format!("before\n<<<<<<< HEAD\n{ours}\n=======\n{theirs}\n>>>>>>> other\nafter\n");
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn explain_finds_one_hunk_and_no_remembered_resolution() {
+        let _lock = CWD_LOCK.lock().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let _cwd = CwdGuard::enter(dir.path());
+
+        conflict_file(&dir.path().join("f.txt"), "mine", "theirs");
+        let conflicts = explain(&["f.txt".to_string()]).unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].hunks.len(), 1);
+        assert_eq!(conflicts[0].hunks[0].ours, "mine");
+        assert_eq!(conflicts[0].hunks[0].theirs, "theirs");
+        assert_eq!(conflicts[0].hunks[0].remembered_resolution, None);
+    }
+
+    #[test]
+    fn explain_auto_resolves_distinct_rust_additions() {
+        let _lock = CWD_LOCK.lock().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let _cwd = CwdGuard::enter(dir.path());
+
+        conflict_file(&dir.path().join("f.rs"), "fn foo() {}", "fn bar() {}");
+        let conflicts = explain(&["f.rs".to_string()]).unwrap();
+        assert!(conflicts[0].hunks[0].auto_resolved.is_some());
+    }
+
+    #[test]
+    fn explain_declines_to_auto_resolve_same_item_touched_by_both() {
+        let _lock = CWD_LOCK.lock().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let _cwd = CwdGuard::enter(dir.path());
+
+        conflict_file(&dir.path().join("f.rs"), "fn foo() { 1 }", "fn foo() { 2 }");
+        let conflicts = explain(&["f.rs".to_string()]).unwrap();
+        assert_eq!(conflicts[0].hunks[0].auto_resolved, None);
+    }
+
+    #[test]
+    fn propose_auto_keeps_both_for_distinct_text() {
+        let hunk = ConflictHunk {
+            start_line: 0,
+            end_line: 0,
+            ours: "a".into(),
+            base: None,
+            theirs: "b".into(),
+            llm_resolution: None,
+            auto_resolved: None,
+            fingerprint: "fp".into(),
+            remembered_resolution: None,
+        };
+        let conflicts = vec![FileConflicts { path: "f.txt".into(), hunks: vec![hunk] }];
+        let plan = propose_auto(&conflicts);
+        assert_eq!(plan["f.txt"][0].resolution, "keep_both");
+    }
+
+    #[test]
+    fn propose_auto_prefers_remembered_over_auto_resolved() {
+        let hunk = ConflictHunk {
+            start_line: 0,
+            end_line: 0,
+            ours: "a".into(),
+            base: None,
+            theirs: "b".into(),
+            llm_resolution: None,
+            auto_resolved: Some("auto text".into()),
+            fingerprint: "fp".into(),
+            remembered_resolution: Some("ours".into()),
+        };
+        let conflicts = vec![FileConflicts { path: "f.txt".into(), hunks: vec![hunk] }];
+        let plan = propose_auto(&conflicts);
+        assert_eq!(plan["f.txt"][0].resolution, "ours");
+    }
+
+    #[test]
+    fn propose_llm_uses_llm_resolution_when_no_auto_or_remembered() {
+        let hunk = ConflictHunk {
+            start_line: 0,
+            end_line: 0,
+            ours: "a".into(),
+            base: None,
+            theirs: "b".into(),
+            llm_resolution: Some(LlmResolution {
+                resolution: "merged text".into(),
+                confidence: 0.9,
+                rationale: "r".into(),
+            }),
+            auto_resolved: None,
+            fingerprint: "fp".into(),
+            remembered_resolution: None,
+        };
+        let conflicts = vec![FileConflicts { path: "f.txt".into(), hunks: vec![hunk] }];
+        let plan = propose_llm(&conflicts);
+        assert_eq!(plan["f.txt"][0].resolution, "merged text");
+    }
+
+    #[test]
+    fn propose_llm_falls_back_to_keep_both_without_llm_resolution() {
+        let hunk = ConflictHunk {
+            start_line: 0,
+            end_line: 0,
+            ours: "a".into(),
+            base: None,
+            theirs: "b".into(),
+            llm_resolution: None,
+            auto_resolved: None,
+            fingerprint: "fp".into(),
+            remembered_resolution: None,
+        };
+        let conflicts = vec![FileConflicts { path: "f.txt".into(), hunks: vec![hunk] }];
+        let plan = propose_llm(&conflicts);
+        assert_eq!(plan["f.txt"][0].resolution, "keep_both");
+    }
+
+    #[test]
+    fn apply_plan_writes_chosen_side_and_records_rerere() {
+        let _lock = CWD_LOCK.lock().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let _cwd = CwdGuard::enter(dir.path());
+
+        conflict_file(&dir.path().join("f.txt"), "mine", "theirs");
+        let mut plan = Plan::new();
+        plan.insert(
+            "f.txt".to_string(),
+            vec![ResolutionItem { hunk_index: 0, resolution: "theirs".into() }],
+        );
+        apply_plan(&plan).unwrap();
+
+        let out = fs::read_to_string(dir.path().join("f.txt")).unwrap();
+        assert!(out.contains("theirs"));
+        assert!(!out.contains("mine"));
+        assert!(!out.contains("<<<<<<<"));
+
+        let fp = crate::rerere::fingerprint("mine", "theirs");
+        assert_eq!(crate::rerere::propose(&fp), Some("theirs".to_string()));
+    }
+
+    #[test]
+    fn apply_plan_keeps_both_sides_when_requested() {
+        let _lock = CWD_LOCK.lock().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let _cwd = CwdGuard::enter(dir.path());
+
+        conflict_file(&dir.path().join("f.txt"), "mine", "theirs");
+        let mut plan = Plan::new();
+        plan.insert(
+            "f.txt".to_string(),
+            vec![ResolutionItem { hunk_index: 0, resolution: "keep_both".into() }],
+        );
+        apply_plan(&plan).unwrap();
+
+        let out = fs::read_to_string(dir.path().join("f.txt")).unwrap();
+        assert!(out.contains("mine"));
+        assert!(out.contains("theirs"));
+    }
+}
@@ -0,0 +1,90 @@
+// # -----------------------------
+// # crates/cli/src/patch_filter.rs
+// # -----------------------------
+// Path-scoped filtering for `devit apply --only/--exclude`, reusing the
+// TUI's unified-diff parser to split the patch file-by-file.
+
+use anyhow::Result;
+use devit_tui::parse_unified_diff;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+
+fn build_globset(csv: &str) -> Result<GlobSet> {
+    let mut gs = GlobSetBuilder::new();
+    for pat in csv.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        gs.add(Glob::new(pat)?);
+    }
+    Ok(gs.build()?)
+}
+
+/// Keep only the files matching `only` (if set) and not matching `exclude`
+/// (if set), rebuilding a valid patch from the surviving files.
+/// Split `patch` into one sub-patch per commit scope (crate/directory, same
+/// inference `devit commit-msg` uses), preserving each group's file order
+/// and group order by first appearance -- for `devit apply --split-commits`.
+pub fn split_by_scope(patch: &str) -> Result<Vec<(String, String)>> {
+    let files = parse_unified_diff(patch).map_err(|e| anyhow::anyhow!("diff invalide: {e}"))?;
+    let mut order: Vec<String> = Vec::new();
+    let mut groups: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+    for file in &files {
+        let scope = crate::commit_msg::scope_for_path(&file.display_name);
+        if !groups.contains_key(&scope) {
+            order.push(scope.clone());
+        }
+        let buf = groups.entry(scope).or_default();
+        for line in &file.header {
+            buf.push_str(line);
+            buf.push('\n');
+        }
+        for hunk in &file.hunks {
+            buf.push_str(&hunk.header);
+            buf.push('\n');
+            for line in &hunk.lines {
+                buf.push_str(line);
+                buf.push('\n');
+            }
+        }
+    }
+    Ok(order
+        .into_iter()
+        .map(|scope| {
+            let text = groups.remove(&scope).unwrap_or_default();
+            (scope, text)
+        })
+        .collect())
+}
+
+pub fn filter_patch(patch: &str, only: Option<&str>, exclude: Option<&str>) -> Result<String> {
+    if only.is_none() && exclude.is_none() {
+        return Ok(patch.to_string());
+    }
+    let only_set = only.map(build_globset).transpose()?;
+    let exclude_set = exclude.map(build_globset).transpose()?;
+
+    let files = parse_unified_diff(patch).map_err(|e| anyhow::anyhow!("diff invalide: {e}"))?;
+    let mut out = String::new();
+    for file in &files {
+        if let Some(gs) = &only_set {
+            if !gs.is_match(&file.display_name) {
+                continue;
+            }
+        }
+        if let Some(gs) = &exclude_set {
+            if gs.is_match(&file.display_name) {
+                continue;
+            }
+        }
+        for line in &file.header {
+            out.push_str(line);
+            out.push('\n');
+        }
+        for hunk in &file.hunks {
+            out.push_str(&hunk.header);
+            out.push('\n');
+            for line in &hunk.lines {
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+    }
+    Ok(out)
+}
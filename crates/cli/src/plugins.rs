@@ -40,6 +40,11 @@ pub struct PluginManifest {
     /// Variables d'environnement à propager (`--env key=value`).
     #[serde(default)]
     pub env: Vec<String>,
+    /// Point du cycle de vie où invoquer ce plugin automatiquement (ex:
+    /// `"precommit"`). `None` : le plugin n'est invoqué qu'explicitement
+    /// via `devit tool call`.
+    #[serde(default)]
+    pub hook: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -48,6 +53,7 @@ pub struct PluginInfo {
     pub name: String,
     pub version: Option<String>,
     pub manifest_path: String,
+    pub hook: Option<String>,
 }
 
 fn timeout_from_env() -> Duration {
@@ -100,6 +106,7 @@ pub fn discover_plugins(root: Option<&Path>) -> Result<Vec<PluginInfo>> {
             name: m.name.clone().unwrap_or_else(|| m.id.clone()),
             version: m.version.clone(),
             manifest_path: manifest.display().to_string(),
+            hook: m.hook.clone(),
         });
     }
     out.sort_by(|a, b| a.id.cmp(&b.id));
@@ -212,6 +219,10 @@ pub fn invoke_manifest(
 }
 
 /// Résout un plugin par ID dans le registry (DEVIT_PLUGINS_DIR) et l'invoque.
+/// Non utilisé par `devit` lui-même (le hook precommit invoque par manifeste
+/// directement) ; gardé pour `devit-plugin invoke --id` et les futurs appels
+/// par ID.
+#[allow(dead_code)]
 pub fn invoke_by_id(
     id: &str,
     stdin_json: &str,
@@ -233,7 +244,6 @@ pub fn invoke_by_id(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::io::Write as _;
     use tempfile::tempdir;
 
     #[test]
@@ -1,5 +1,9 @@
 // no anyhow import needed here
+use crate::plugins;
 use devit_common::{Config, PrecommitCfg};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::path::Path;
 use std::process::{Command, Stdio};
 use std::time::{Duration, Instant};
@@ -19,6 +23,17 @@ fn timeout() -> Duration {
     Duration::from_secs(secs)
 }
 
+/// Max precommit tools run concurrently -- tools are independent checks
+/// (fmt/clippy/eslint/ruff/...) so this is the only thing that used to
+/// serialize their latency into `fs_patch_apply`.
+fn max_parallel() -> usize {
+    std::env::var("DEVIT_PRECOMMIT_PARALLELISM")
+        .ok()
+        .and_then(|s| s.parse::<usize>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(4)
+}
+
 fn exists(p: &str) -> bool {
     Path::new(p).exists()
 }
@@ -43,20 +58,24 @@ fn has_prettier_config() -> bool {
     false
 }
 
-fn run_with_timeout(cmd: &str, tool_label: &str) -> std::result::Result<(), PrecommitFailure> {
-    let mut child = Command::new("bash")
-        .arg("-lc")
-        .arg(cmd)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .map_err(|e| PrecommitFailure {
-            tool: tool_label.into(),
-            exit_code: 127,
-            stderr: e.to_string(),
-        })?;
+fn run_with_timeout(
+    cmd: &str,
+    tool_label: &str,
+    timeout_override: Option<Duration>,
+    workdir: Option<&str>,
+) -> std::result::Result<(), PrecommitFailure> {
+    let mut command = Command::new("bash");
+    command.arg("-lc").arg(cmd).stdout(Stdio::piped()).stderr(Stdio::piped());
+    if let Some(dir) = workdir {
+        command.current_dir(dir);
+    }
+    let mut child = command.spawn().map_err(|e| PrecommitFailure {
+        tool: tool_label.into(),
+        exit_code: 127,
+        stderr: e.to_string(),
+    })?;
     let t0 = Instant::now();
-    let to = timeout();
+    let to = timeout_override.unwrap_or_else(timeout);
     while t0.elapsed() < to {
         match child.try_wait() {
             Ok(Some(status)) => {
@@ -88,6 +107,95 @@ fn run_with_timeout(cmd: &str, tool_label: &str) -> std::result::Result<(), Prec
     })
 }
 
+const CACHE_PATH: &str = ".devit/cache/precommit.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct CacheEntry {
+    hash: String,
+    ok: bool,
+}
+
+type Cache = HashMap<String, CacheEntry>;
+
+fn load_cache() -> Cache {
+    std::fs::read(CACHE_PATH)
+        .ok()
+        .and_then(|b| serde_json::from_slice(&b).ok())
+        .unwrap_or_default()
+}
+
+fn save_cache(cache: &Cache) {
+    let _ = std::fs::create_dir_all(".devit/cache");
+    let _ = std::fs::write(CACHE_PATH, serde_json::to_vec_pretty(cache).unwrap_or_default());
+}
+
+/// Staged file paths (added/copied/modified/renamed), relative to the repo root.
+fn staged_files() -> Vec<String> {
+    let out = Command::new("git")
+        .args(["diff", "--cached", "--name-only", "--diff-filter=ACMR"])
+        .output();
+    match out {
+        Ok(o) if o.status.success() => String::from_utf8_lossy(&o.stdout)
+            .lines()
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Content hash of the currently staged file set -- used to skip tools
+/// whose inputs haven't changed since their last successful run.
+fn hash_staged_files() -> String {
+    let mut files = staged_files();
+    files.sort();
+    let mut hasher = Sha256::new();
+    for f in &files {
+        hasher.update(f.as_bytes());
+        if let Ok(content) = std::fs::read(f) {
+            hasher.update(&content);
+        }
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Wrap `s` in single quotes for safe use inside a `bash -lc` command line.
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+fn quote_all<'a>(files: impl IntoIterator<Item = &'a String>) -> String {
+    files.into_iter().map(|f| shell_quote(f)).collect::<Vec<_>>().join(" ")
+}
+
+/// Walk up from `path` looking for the nearest `Cargo.toml` and return its
+/// `package.name` (naive line scan, no toml dependency here) -- used to
+/// scope `cargo clippy` to the workspace member(s) a staged file lives in.
+fn rust_package_for(path: &str) -> Option<String> {
+    let mut dir = Path::new(path).parent();
+    while let Some(d) = dir {
+        let manifest = d.join("Cargo.toml");
+        if manifest.exists() {
+            let text = std::fs::read_to_string(&manifest).ok()?;
+            for line in text.lines() {
+                let line = line.trim();
+                if let Some(rest) = line.strip_prefix("name") {
+                    let rest = rest.trim_start();
+                    if let Some(value) = rest.strip_prefix('=') {
+                        let name = value.trim().trim_matches('"').to_string();
+                        if !name.is_empty() {
+                            return Some(name);
+                        }
+                    }
+                }
+            }
+            return None;
+        }
+        dir = d.parent();
+    }
+    None
+}
+
 fn cfg_or_default(cfg: &Config) -> PrecommitCfg {
     cfg.precommit.clone().unwrap_or(PrecommitCfg {
         rust: true,
@@ -96,85 +204,433 @@ fn cfg_or_default(cfg: &Config) -> PrecommitCfg {
         additional: vec![],
         fail_on: vec!["rust".into(), "javascript".into(), "python".into()],
         allow_bypass_profiles: vec!["danger".into()],
+        commands: Default::default(),
+        autofix: false,
     })
 }
 
-pub fn run(cfg: &Config) -> std::result::Result<(), PrecommitFailure> {
-    let pc = cfg_or_default(cfg);
-    // Rust
-    if pc.rust && exists("Cargo.toml") {
-        run_with_timeout("cargo fmt --all -- --check", "fmt").map_err(|e| {
-            if pc.fail_on.contains(&"rust".into()) {
-                e
-            } else {
-                PrecommitFailure {
-                    tool: e.tool,
-                    exit_code: 0,
-                    stderr: e.stderr,
+/// One precommit tool invocation -- independent of every other `Job`, so
+/// [`run_jobs`] is free to run them concurrently.
+#[derive(Debug, Clone)]
+struct Job {
+    tool: String,
+    cmd: String,
+    timeout: Option<Duration>,
+    workdir: Option<String>,
+    /// Whether a failure of this job should fail the whole gate (mirrors
+    /// `PrecommitCfg::fail_on`).
+    blocking: bool,
+    /// Whether `run_autofix` knows how to fix this job's language
+    /// automatically (built-in rust/javascript/python fallback commands
+    /// only -- a `[precommit.commands]` override is arbitrary and can't be
+    /// assumed fixable).
+    fixable: bool,
+    /// Content hash of the job's inputs (today: the staged file set) --
+    /// a cache hit with this same hash and a prior `ok: true` skips the run.
+    cache_key: String,
+}
+
+fn is_false(b: &bool) -> bool {
+    !*b
+}
+
+/// Naive per-line split of a tool's raw stderr into individual findings --
+/// good enough to summarize without parsing each tool's own output format.
+fn parse_findings(stderr: &str) -> Vec<String> {
+    stderr
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(|l| l.to_string())
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ToolTiming {
+    tool: String,
+    ok: bool,
+    blocking: bool,
+    exit_code: i32,
+    duration_ms: u64,
+    fixable: bool,
+    #[serde(skip_serializing_if = "is_false")]
+    cached: bool,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    stderr: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    findings: Vec<String>,
+}
+
+/// Run `jobs` through a pool bounded by [`max_parallel`], chunking the
+/// job list into waves instead of pulling in a thread-pool crate -- at
+/// most `max_parallel()` tools are ever running at once. A job whose
+/// `cache_key` hit the cache with `ok: true` is skipped entirely.
+fn run_jobs(jobs: &[Job], cache: &Cache, no_cache: bool) -> Vec<ToolTiming> {
+    let pool_size = max_parallel();
+    let mut results: Vec<Option<ToolTiming>> = vec![None; jobs.len()];
+    let mut runnable: Vec<usize> = Vec::new();
+    for (idx, job) in jobs.iter().enumerate() {
+        let hit = (!no_cache)
+            .then(|| cache.get(&job.tool))
+            .flatten()
+            .filter(|e| e.ok && e.hash == job.cache_key);
+        if let Some(_entry) = hit {
+            results[idx] = Some(ToolTiming {
+                tool: job.tool.clone(),
+                ok: true,
+                blocking: job.blocking,
+                exit_code: 0,
+                duration_ms: 0,
+                fixable: job.fixable,
+                cached: true,
+                stderr: String::new(),
+                findings: Vec::new(),
+            });
+        } else {
+            runnable.push(idx);
+        }
+    }
+    for batch in runnable.chunks(pool_size) {
+        let handles: Vec<_> = batch
+            .iter()
+            .map(|&idx| {
+                let job = jobs[idx].clone();
+                std::thread::spawn(move || {
+                    let t0 = Instant::now();
+                    let r = run_with_timeout(&job.cmd, &job.tool, job.timeout, job.workdir.as_deref());
+                    let duration_ms = t0.elapsed().as_millis() as u64;
+                    let timing = match r {
+                        Ok(()) => ToolTiming {
+                            tool: job.tool,
+                            ok: true,
+                            blocking: job.blocking,
+                            exit_code: 0,
+                            duration_ms,
+                            fixable: job.fixable,
+                            cached: false,
+                            stderr: String::new(),
+                            findings: Vec::new(),
+                        },
+                        Err(e) => ToolTiming {
+                            tool: job.tool,
+                            ok: false,
+                            blocking: job.blocking,
+                            exit_code: e.exit_code,
+                            duration_ms,
+                            fixable: job.fixable,
+                            cached: false,
+                            findings: parse_findings(&e.stderr),
+                            stderr: e.stderr,
+                        },
+                    };
+                    (idx, timing)
+                })
+            })
+            .collect();
+        for h in handles {
+            match h.join() {
+                Ok((idx, timing)) => results[idx] = Some(timing),
+                Err(_) => {
+                    let idx = *batch
+                        .iter()
+                        .find(|&&i| results[i].is_none())
+                        .unwrap_or(&batch[0]);
+                    results[idx] = Some(ToolTiming {
+                        tool: jobs[idx].tool.clone(),
+                        ok: false,
+                        blocking: jobs[idx].blocking,
+                        exit_code: 1,
+                        duration_ms: 0,
+                        fixable: jobs[idx].fixable,
+                        cached: false,
+                        stderr: "tool thread panicked".into(),
+                        findings: Vec::new(),
+                    });
                 }
             }
-        })?;
-        run_with_timeout("cargo clippy --all-targets -- -D warnings", "clippy").map_err(|e| {
-            if pc.fail_on.contains(&"rust".into()) {
-                e
-            } else {
-                PrecommitFailure {
-                    tool: e.tool,
-                    exit_code: 0,
-                    stderr: e.stderr,
+        }
+    }
+    results.into_iter().flatten().collect()
+}
+
+fn write_report(timings: &[ToolTiming], gate_ok: bool, autofixed: &[String]) {
+    let _ = std::fs::create_dir_all(".devit/reports");
+    let total_duration_ms: u64 = timings.iter().map(|t| t.duration_ms).sum();
+    let payload = serde_json::json!({
+        "ok": gate_ok,
+        "tools": timings,
+        "total_duration_ms": total_duration_ms,
+        "parallelism": max_parallel(),
+        "autofixed": autofixed,
+    });
+    let _ = std::fs::write(
+        ".devit/reports/precommit.json",
+        serde_json::to_vec_pretty(&payload).unwrap_or_default(),
+    );
+}
+
+/// Per-language jobs: the explicit `[precommit.commands]` list for `lang`
+/// if configured, else `fallback`.
+fn jobs_for(
+    lang: &str,
+    pc: &PrecommitCfg,
+    staged_hash: &str,
+    fallback: impl FnOnce() -> Vec<(String, String)>,
+) -> Vec<Job> {
+    let blocking = pc.fail_on.contains(&lang.to_string());
+    if let Some(specs) = pc.commands.get(lang) {
+        specs
+            .iter()
+            .enumerate()
+            .map(|(i, spec)| Job {
+                tool: format!("{lang}[{i}]"),
+                cmd: spec.cmd().to_string(),
+                timeout: spec.timeout_secs().map(Duration::from_secs),
+                workdir: spec.workdir().map(|s| s.to_string()),
+                blocking,
+                fixable: false,
+                cache_key: staged_hash.to_string(),
+            })
+            .collect()
+    } else {
+        fallback()
+            .into_iter()
+            .map(|(tool, cmd)| Job {
+                tool,
+                cmd,
+                timeout: None,
+                workdir: None,
+                blocking,
+                fixable: true,
+                cache_key: staged_hash.to_string(),
+            })
+            .collect()
+    }
+}
+
+/// `no_cache` forces every tool to re-run, bypassing
+/// `.devit/cache/precommit.json`; `autofix` runs formatters/fixers first
+/// and stages what they change.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunOptions {
+    pub no_cache: bool,
+    pub autofix: bool,
+}
+
+/// List of `(changed path) -> content` hashes, used to tell whether a
+/// fixer actually touched anything.
+fn tree_fingerprint() -> String {
+    let out = Command::new("git").args(["diff", "--name-only"]).output();
+    match out {
+        Ok(o) if o.status.success() => String::from_utf8_lossy(&o.stdout).to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Run the fixer variant of each enabled language's tools (skipped for
+/// languages with a `[precommit.commands]` override, since we don't know
+/// how to turn an arbitrary configured command into a fixer) and stage
+/// whatever they changed. Returns the languages that were actually fixed.
+fn run_autofix(pc: &PrecommitCfg) -> Vec<String> {
+    let mut fixers: Vec<(&str, &str)> = Vec::new();
+    if pc.rust && exists("Cargo.toml") && !pc.commands.contains_key("rust") {
+        fixers.push(("rust", "cargo fmt --all"));
+    }
+    if pc.javascript && exists("package.json") && !pc.commands.contains_key("javascript") {
+        fixers.push(("javascript", "npx eslint --fix . ; npx prettier -w . >/dev/null 2>&1 || true"));
+    }
+    if pc.python
+        && !pc.commands.contains_key("python")
+        && (exists("pyproject.toml") || exists("tox.ini") || exists("pytest.ini"))
+    {
+        fixers.push(("python", "ruff check --fix ."));
+    }
+
+    let mut fixed = Vec::new();
+    for (lang, cmd) in fixers {
+        let before = tree_fingerprint();
+        let _ = run_with_timeout(cmd, &format!("autofix:{lang}"), None, None);
+        if tree_fingerprint() != before {
+            fixed.push(lang.to_string());
+        }
+    }
+    if !fixed.is_empty() {
+        let _ = Command::new("git").args(["add", "-u"]).output();
+    }
+    fixed
+}
+
+/// Invoke every registered plugin that declares `hook = "precommit"`,
+/// passing the staged file list as JSON stdin (`{"staged_files": [...]}`)
+/// and folding its response into the same [`ToolTiming`] shape as the
+/// shell-command jobs, so org-specific checks show up in the gate and in
+/// `.devit/reports/precommit.json` alongside `rustfmt`/`eslint`/etc.
+///
+/// A plugin is expected to answer `{"ok": bool, "findings": [...]}` on
+/// stdout; `ok: false` (or the plugin failing to run at all) blocks the
+/// gate, same as any other blocking tool.
+fn run_precommit_plugins(staged: &[String]) -> Vec<ToolTiming> {
+    let discovered = match plugins::discover_plugins(None) {
+        Ok(p) => p,
+        Err(_) => return Vec::new(),
+    };
+    let stdin_json = serde_json::json!({ "staged_files": staged }).to_string();
+    discovered
+        .into_iter()
+        .filter(|p| p.hook.as_deref() == Some("precommit"))
+        .map(|p| {
+            let tool = format!("plugin:{}", p.id);
+            let t0 = Instant::now();
+            let result = plugins::invoke_manifest(Path::new(&p.manifest_path), &stdin_json, None);
+            let duration_ms = t0.elapsed().as_millis() as u64;
+            match result {
+                Ok(v) => {
+                    let ok = v.get("ok").and_then(|b| b.as_bool()).unwrap_or(true);
+                    let findings: Vec<String> = v
+                        .get("findings")
+                        .and_then(|f| f.as_array())
+                        .map(|arr| {
+                            arr.iter()
+                                .map(|f| f.as_str().map(str::to_string).unwrap_or_else(|| f.to_string()))
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    ToolTiming {
+                        tool,
+                        ok,
+                        blocking: true,
+                        exit_code: if ok { 0 } else { 1 },
+                        duration_ms,
+                        fixable: false,
+                        cached: false,
+                        stderr: String::new(),
+                        findings,
+                    }
                 }
+                Err(e) => ToolTiming {
+                    tool,
+                    ok: false,
+                    blocking: true,
+                    exit_code: 1,
+                    duration_ms,
+                    fixable: false,
+                    cached: false,
+                    stderr: e.to_string(),
+                    findings: Vec::new(),
+                },
             }
-        })?;
-    }
-    // JS/TS
-    if pc.javascript && exists("package.json") {
-        // Prefer npm run lint; fallback to npx eslint .
-        let r = run_with_timeout("npm run -s lint || npx eslint .", "eslint");
-        if let Err(e) = r {
-            if pc.fail_on.contains(&"javascript".into()) {
-                return Err(e);
-            }
-        }
-        if has_prettier_config() {
-            let r = run_with_timeout("npx prettier -c .", "prettier");
-            if let Err(e) = r {
-                if pc.fail_on.contains(&"javascript".into()) {
-                    return Err(e);
+        })
+        .collect()
+}
+
+pub fn run(cfg: &Config, opts: RunOptions) -> std::result::Result<(), PrecommitFailure> {
+    let pc = cfg_or_default(cfg);
+    let autofixed = if opts.autofix || pc.autofix {
+        run_autofix(&pc)
+    } else {
+        Vec::new()
+    };
+    let staged_hash = hash_staged_files();
+    let staged = staged_files();
+    let mut jobs: Vec<Job> = Vec::new();
+
+    // Scope each language's fallback commands to its staged files, so
+    // gate latency tracks the size of the patch, not the whole repo.
+    let rust_files: Vec<String> = staged.iter().filter(|f| f.ends_with(".rs")).cloned().collect();
+    if pc.rust && exists("Cargo.toml") && !rust_files.is_empty() {
+        jobs.extend(jobs_for("rust", &pc, &staged_hash, || {
+            let mut packages: Vec<String> =
+                rust_files.iter().filter_map(|f| rust_package_for(f)).collect();
+            packages.sort();
+            packages.dedup();
+            let mut v = vec![("fmt".to_string(), format!("rustfmt --check {}", quote_all(&rust_files)))];
+            if packages.is_empty() {
+                v.push(("clippy".into(), "cargo clippy --all-targets -- -D warnings".into()));
+            } else {
+                for pkg in &packages {
+                    v.push((
+                        format!("clippy:{pkg}"),
+                        format!("cargo clippy -p {pkg} --all-targets -- -D warnings"),
+                    ));
                 }
             }
-        }
+            v
+        }));
     }
-    // Python
-    if pc.python && (exists("pyproject.toml") || exists("tox.ini") || exists("pytest.ini")) {
-        // Prefer ruff check
-        let r = if exists("pyproject.toml") {
-            run_with_timeout("ruff check", "ruff")
-        } else {
-            run_with_timeout("ruff -q .", "ruff")
-        };
-        if let Err(e) = r {
-            if pc.fail_on.contains(&"python".into()) {
-                return Err(e);
+    let js_files: Vec<String> = staged
+        .iter()
+        .filter(|f| {
+            f.ends_with(".js") || f.ends_with(".jsx") || f.ends_with(".ts") || f.ends_with(".tsx")
+        })
+        .cloned()
+        .collect();
+    if pc.javascript && exists("package.json") && !js_files.is_empty() {
+        jobs.extend(jobs_for("javascript", &pc, &staged_hash, || {
+            let file_args = quote_all(&js_files);
+            let mut v = vec![("eslint".into(), format!("npx eslint {file_args}"))];
+            if has_prettier_config() {
+                v.push(("prettier".into(), format!("npx prettier -c {file_args}")));
             }
-        }
+            v
+        }));
+    }
+    let py_files: Vec<String> = staged.iter().filter(|f| f.ends_with(".py")).cloned().collect();
+    if pc.python
+        && !py_files.is_empty()
+        && (exists("pyproject.toml") || exists("tox.ini") || exists("pytest.ini"))
+    {
+        jobs.extend(jobs_for("python", &pc, &staged_hash, || {
+            vec![("ruff".into(), format!("ruff check {}", quote_all(&py_files)))]
+        }));
     }
-    // C/C++
     if exists("CMakeLists.txt") {
         // best-effort, non-blocking by default
-        let _ = run_with_timeout(
-            "command -v cmake-lint >/dev/null 2>&1 && cmake-lint || true",
-            "cmake-lint",
-        );
+        jobs.push(Job {
+            tool: "cmake-lint".into(),
+            cmd: "command -v cmake-lint >/dev/null 2>&1 && cmake-lint || true".into(),
+            timeout: None,
+            workdir: None,
+            blocking: false,
+            fixable: false,
+            cache_key: staged_hash.clone(),
+        });
     }
-    // Additional
     for (i, cmd) in pc.additional.iter().enumerate() {
-        let label = format!("additional[{}]", i);
-        let r = run_with_timeout(cmd, &label);
-        if let Err(e) = r {
-            // treat additional as blocking if listed in fail_on as "additional"
-            if pc.fail_on.iter().any(|s| s == "additional") {
-                return Err(e);
-            }
+        jobs.push(Job {
+            tool: format!("additional[{i}]"),
+            cmd: cmd.clone(),
+            timeout: None,
+            workdir: None,
+            blocking: pc.fail_on.iter().any(|s| s == "additional"),
+            fixable: false,
+            cache_key: staged_hash.clone(),
+        });
+    }
+
+    let mut cache = load_cache();
+    let mut timings = run_jobs(&jobs, &cache, opts.no_cache);
+    timings.extend(run_precommit_plugins(&staged));
+    for t in &timings {
+        cache.insert(
+            t.tool.clone(),
+            CacheEntry {
+                hash: staged_hash.clone(),
+                ok: t.ok,
+            },
+        );
+    }
+    save_cache(&cache);
+
+    let gate_ok = timings.iter().all(|t| t.ok || !t.blocking);
+    write_report(&timings, gate_ok, &autofixed);
+
+    for timing in &timings {
+        if timing.blocking && !timing.ok {
+            return Err(PrecommitFailure {
+                tool: timing.tool.clone(),
+                exit_code: timing.exit_code,
+                stderr: timing.stderr.clone(),
+            });
         }
     }
     Ok(())
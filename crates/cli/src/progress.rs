@@ -0,0 +1,61 @@
+// # -----------------------------
+// # crates/cli/src/progress.rs
+// # -----------------------------
+// Feedback for long-running phases (context collection, LLM calls, test
+// runs, indexing), which otherwise give no signal until they complete: a
+// spinner on an interactive TTY, `{"type":"progress",...}` JSON lines under
+// `--json`, silence when piped without `--json`.
+
+use std::io::{IsTerminal, Write};
+
+pub struct Progress {
+    json: bool,
+    tty: bool,
+    phase: &'static str,
+}
+
+impl Progress {
+    /// Start reporting progress for `phase`; call `finish()` (or just drop)
+    /// once it completes.
+    pub fn start(json: bool, phase: &'static str) -> Self {
+        let tty = !json && std::io::stderr().is_terminal();
+        let p = Self { json, tty, phase };
+        p.emit(None);
+        p
+    }
+
+    fn emit(&self, message: Option<&str>) {
+        if self.json {
+            let mut payload = serde_json::json!({"type": "progress", "phase": self.phase});
+            if let Some(m) = message {
+                payload["message"] = serde_json::Value::String(m.to_string());
+            }
+            let mut stdout = std::io::stdout().lock();
+            if serde_json::to_writer(&mut stdout, &payload).is_ok() {
+                let _ = stdout.write_all(b"\n");
+                let _ = stdout.flush();
+            }
+        } else if self.tty {
+            let text = message.unwrap_or(self.phase);
+            eprint!("\r\x1b[2K⏳ {text}...");
+            let _ = std::io::stderr().flush();
+        }
+    }
+
+    /// Clear the spinner line early, before the phase would otherwise end
+    /// (e.g. right before printing the phase's own result). A no-op on
+    /// JSON/non-interactive output; harmless to skip since `Drop` also
+    /// clears the line, including on an early `?` return.
+    pub fn finish(self) {
+        drop(self);
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        if self.tty {
+            eprint!("\r\x1b[2K");
+            let _ = std::io::stderr().flush();
+        }
+    }
+}
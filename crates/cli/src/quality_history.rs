@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+/// One `quality gate` run's headline numbers, appended to
+/// `.devit/history/quality.jsonl` so `devit quality trend` can flag
+/// statistically notable regressions across runs — same append-only-JSONL
+/// pattern as [`crate::test_history`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QualityRunEntry {
+    pub tests_total: u32,
+    pub tests_failed: u32,
+    pub lint_errors: u32,
+    pub lint_warnings: u32,
+    pub line_coverage_pct: Option<f64>,
+    pub branch_coverage_pct: Option<f64>,
+    pub duration_ms: u64,
+    pub pass: bool,
+    pub ts: String,
+}
+
+fn history_path() -> PathBuf {
+    Path::new(".devit/history/quality.jsonl").to_path_buf()
+}
+
+/// Append one gate run's summary. Called from `quality gate` regardless of
+/// pass/fail, so `quality trend` sees the full run history.
+pub fn record(sum: &crate::report::QualitySummary, pass: bool) {
+    let path = history_path();
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let entry = QualityRunEntry {
+        tests_total: sum.tests_total,
+        tests_failed: sum.tests_failed,
+        lint_errors: sum.lint_errors,
+        lint_warnings: sum.lint_warnings,
+        line_coverage_pct: sum.line_coverage_pct,
+        branch_coverage_pct: sum.branch_coverage_pct,
+        duration_ms: sum.duration_ms,
+        pass,
+        ts: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+    };
+    if let Ok(line) = serde_json::to_string(&entry) {
+        if let Ok(mut f) = fs::OpenOptions::new().create(true).append(true).open(&path) {
+            let _ = writeln!(f, "{line}");
+        }
+    }
+}
+
+fn load() -> Vec<QualityRunEntry> {
+    let content = fs::read_to_string(history_path()).unwrap_or_default();
+    content
+        .lines()
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect()
+}
+
+/// A metric whose latest run moved [`STDDEV_THRESHOLD`] standard deviations
+/// or more away from the mean of the preceding runs in the window --
+/// "statistically notable", not just "worse than last time".
+#[derive(Debug, Clone, Serialize)]
+pub struct Regression {
+    pub metric: String,
+    pub latest: f64,
+    pub mean: f64,
+    pub stddev: f64,
+}
+
+const STDDEV_THRESHOLD: f64 = 2.0;
+
+/// Flag metrics whose latest run, among the last `limit` runs, sits
+/// [`STDDEV_THRESHOLD`] standard deviations past the mean of the runs
+/// before it. Needs at least 3 runs in the window (2 for the baseline
+/// stats, 1 to compare) or nothing is flagged.
+pub fn trend(limit: usize) -> Vec<Regression> {
+    let mut entries = load();
+    entries.sort_by(|a, b| a.ts.cmp(&b.ts));
+    if entries.len() > limit {
+        entries = entries[entries.len() - limit..].to_vec();
+    }
+    if entries.len() < 3 {
+        return Vec::new();
+    }
+    let (history, latest) = entries.split_at(entries.len() - 1);
+    let latest = &latest[0];
+
+    let mut out = Vec::new();
+    check_metric(
+        "tests_failed",
+        &history.iter().map(|e| e.tests_failed as f64).collect::<Vec<_>>(),
+        latest.tests_failed as f64,
+        false,
+        &mut out,
+    );
+    check_metric(
+        "lint_errors",
+        &history.iter().map(|e| e.lint_errors as f64).collect::<Vec<_>>(),
+        latest.lint_errors as f64,
+        false,
+        &mut out,
+    );
+    check_metric(
+        "lint_warnings",
+        &history.iter().map(|e| e.lint_warnings as f64).collect::<Vec<_>>(),
+        latest.lint_warnings as f64,
+        false,
+        &mut out,
+    );
+    check_metric(
+        "duration_ms",
+        &history.iter().map(|e| e.duration_ms as f64).collect::<Vec<_>>(),
+        latest.duration_ms as f64,
+        false,
+        &mut out,
+    );
+    if let Some(l) = latest.line_coverage_pct {
+        let hist: Vec<f64> = history.iter().filter_map(|e| e.line_coverage_pct).collect();
+        if hist.len() == history.len() {
+            check_metric("line_coverage_pct", &hist, l, true, &mut out);
+        }
+    }
+    out
+}
+
+/// `higher_is_better` flips the comparison direction — coverage regresses
+/// by *dropping* below the mean, not rising above it.
+fn check_metric(
+    name: &str,
+    history: &[f64],
+    latest: f64,
+    higher_is_better: bool,
+    out: &mut Vec<Regression>,
+) {
+    if history.is_empty() {
+        return;
+    }
+    let mean = history.iter().sum::<f64>() / history.len() as f64;
+    let variance = history.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / history.len() as f64;
+    let stddev = variance.sqrt();
+    let regressed = if stddev > 0.0 {
+        let z = (latest - mean) / stddev;
+        if higher_is_better {
+            z <= -STDDEV_THRESHOLD
+        } else {
+            z >= STDDEV_THRESHOLD
+        }
+    } else if higher_is_better {
+        latest < mean
+    } else {
+        latest > mean
+    };
+    if regressed {
+        out.push(Regression {
+            metric: name.to_string(),
+            latest,
+            mean,
+            stddev,
+        });
+    }
+}
@@ -1,6 +1,7 @@
 use anyhow::{anyhow, Context, Result};
 use serde::Deserialize;
 use serde_json::json;
+use std::collections::HashMap;
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -13,9 +14,51 @@ struct RecipeFile {
     #[serde(default)]
     description: Option<String>,
     #[serde(default)]
+    params: Vec<RecipeParam>,
+    #[serde(default)]
     steps: Vec<RecipeStep>,
 }
 
+#[derive(Deserialize, Debug)]
+struct RecipeParam {
+    name: String,
+    #[serde(rename = "type", default)]
+    param_type: ParamType,
+    #[serde(default)]
+    default: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum ParamType {
+    #[default]
+    String,
+    Bool,
+    Number,
+}
+
+impl ParamType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ParamType::String => "string",
+            ParamType::Bool => "bool",
+            ParamType::Number => "number",
+        }
+    }
+
+    fn validate(&self, value: &str) -> Result<(), String> {
+        match self {
+            ParamType::String => Ok(()),
+            ParamType::Bool => value.parse::<bool>().map(|_| ()).map_err(|_| {
+                format!("invalid {} value {value:?}", self.as_str())
+            }),
+            ParamType::Number => value.parse::<f64>().map(|_| ()).map_err(|_| {
+                format!("invalid {} value {value:?}", self.as_str())
+            }),
+        }
+    }
+}
+
 #[derive(Deserialize, Debug)]
 struct RecipeStep {
     #[serde(rename = "kind")]
@@ -26,6 +69,14 @@ struct RecipeStep {
     run: Option<String>,
     #[serde(default)]
     args: Option<Vec<String>>,
+    /// `context` steps only: extensions to keep (CSV, e.g. "rs,toml"),
+    /// forwarded to `context::generate_index` as `ext_allow`.
+    #[serde(default)]
+    only: Option<String>,
+    /// `prompt` steps only: goal text with `{{param}}` placeholders, passed
+    /// to `devit suggest --goal` once rendered.
+    #[serde(default)]
+    template: Option<String>,
 }
 
 #[derive(Deserialize, Debug, Clone, Copy)]
@@ -34,6 +85,14 @@ enum RecipeKind {
     Shell,
     Git,
     Devit,
+    /// Regenerate `.devit/index.json`, optionally restricted to `only`.
+    Context,
+    /// Render `template` against the resolved params and run it through
+    /// `devit suggest --apply --yes`.
+    Prompt,
+    /// A shell command that must exit 0; semantically a post-check, run
+    /// identically to a `shell` step.
+    Check,
 }
 
 #[derive(serde::Serialize)]
@@ -54,10 +113,14 @@ pub struct RecipeRunReport {
 pub struct RecipeRunError {
     pub payload: serde_json::Value,
     pub exit_code: i32,
+    /// Stable machine-readable failure kind (see `devit recipe run`'s
+    /// exit-code contract), distinct from `payload.reason`'s free-form text.
+    pub code: &'static str,
 }
 
 const DEFAULT_RECIPES_DIR: &str = ".devit/recipes";
 const ENV_RECIPES_DIR: &str = "DEVIT_RECIPES_DIR";
+const BUILTIN_RECIPES_DIR: &str = "recipes";
 
 fn recipes_dir() -> PathBuf {
     if let Ok(custom) = env::var(ENV_RECIPES_DIR) {
@@ -86,30 +149,83 @@ fn load_recipe_files(dir: &Path) -> Vec<PathBuf> {
     out
 }
 
+/// Recipes shipped with DevIt (`recipes/*.yml` at the repo root), listed and
+/// runnable alongside user recipes from `recipes_dir()`. User recipes win on
+/// id collisions since they're searched first.
+fn builtin_recipe_files() -> Vec<PathBuf> {
+    load_recipe_files(Path::new(BUILTIN_RECIPES_DIR))
+}
+
+/// Validate a parsed recipe, reporting the offending field on failure.
+fn validate_recipe(recipe: &RecipeFile) -> Result<()> {
+    if recipe.id.trim().is_empty() {
+        return Err(anyhow!("id: must not be empty"));
+    }
+    if recipe.name.trim().is_empty() {
+        return Err(anyhow!("name: must not be empty"));
+    }
+    for (idx, param) in recipe.params.iter().enumerate() {
+        if param.name.trim().is_empty() {
+            return Err(anyhow!("params[{idx}].name: must not be empty"));
+        }
+        if let Some(default) = &param.default {
+            if let Err(e) = param.param_type.validate(default) {
+                return Err(anyhow!("params[{idx}].default: {e}"));
+            }
+        }
+    }
+    for (idx, step) in recipe.steps.iter().enumerate() {
+        match step.kind {
+            RecipeKind::Shell | RecipeKind::Check => {
+                if step.run.is_none() {
+                    return Err(anyhow!(
+                        "steps[{idx}].run: required for a {} step",
+                        step.kind.as_str()
+                    ));
+                }
+            }
+            RecipeKind::Git | RecipeKind::Devit => {
+                if step.args.as_ref().is_none_or(|a| a.is_empty()) {
+                    return Err(anyhow!(
+                        "steps[{idx}].args: required for a {} step",
+                        step.kind.as_str()
+                    ));
+                }
+            }
+            RecipeKind::Context => {}
+            RecipeKind::Prompt => {
+                if step.template.is_none() {
+                    return Err(anyhow!("steps[{idx}].template: required for a prompt step"));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
 fn load_recipe(path: &Path) -> Result<RecipeFile> {
     let contents =
         fs::read_to_string(path).with_context(|| format!("read recipe {}", path.display()))?;
     let recipe: RecipeFile = serde_yaml::from_str(&contents)
         .with_context(|| format!("parse recipe {}", path.display()))?;
-    if recipe.id.trim().is_empty() {
-        return Err(anyhow!("recipe id is empty"));
-    }
-    if recipe.name.trim().is_empty() {
-        return Err(anyhow!("recipe name is empty"));
-    }
+    validate_recipe(&recipe)?;
     Ok(recipe)
 }
 
 pub fn list_recipes() -> Result<Vec<RecipeSummary>> {
-    let dir = recipes_dir();
     let mut recipes = Vec::new();
-    for path in load_recipe_files(&dir) {
+    let mut seen = std::collections::HashSet::new();
+    for path in load_recipe_files(&recipes_dir()).into_iter().chain(builtin_recipe_files()) {
         match load_recipe(&path) {
-            Ok(file) => recipes.push(RecipeSummary {
-                id: file.id,
-                name: file.name,
-                description: file.description,
-            }),
+            Ok(file) => {
+                if seen.insert(file.id.clone()) {
+                    recipes.push(RecipeSummary {
+                        id: file.id,
+                        name: file.name,
+                        description: file.description,
+                    });
+                }
+            }
             Err(e) => {
                 eprintln!("warn: skip recipe {} ({})", path.display(), e);
             }
@@ -118,15 +234,12 @@ pub fn list_recipes() -> Result<Vec<RecipeSummary>> {
     Ok(recipes)
 }
 
-pub fn run_recipe(id: &str, dry_run: bool) -> Result<RecipeRunReport, RecipeRunError> {
-    let dir = recipes_dir();
-    let mut selected: Option<RecipeFile> = None;
-    for path in load_recipe_files(&dir) {
+fn find_recipe(id: &str) -> Option<RecipeFile> {
+    for path in load_recipe_files(&recipes_dir()).into_iter().chain(builtin_recipe_files()) {
         match load_recipe(&path) {
             Ok(file) => {
                 if file.id == id {
-                    selected = Some(file);
-                    break;
+                    return Some(file);
                 }
             }
             Err(e) => {
@@ -134,8 +247,53 @@ pub fn run_recipe(id: &str, dry_run: bool) -> Result<RecipeRunReport, RecipeRunE
             }
         }
     }
+    None
+}
+
+/// Merge declared param defaults with CLI `--param key=value` overrides,
+/// type-checking the resolved value against each param's declared type.
+fn resolve_params(
+    recipe: &RecipeFile,
+    overrides: &HashMap<String, String>,
+) -> Result<HashMap<String, String>, String> {
+    let mut resolved = HashMap::new();
+    for param in &recipe.params {
+        let value = overrides
+            .get(&param.name)
+            .cloned()
+            .or_else(|| param.default.clone());
+        let Some(value) = value else {
+            return Err(format!("param {:?} has no value and no default", param.name));
+        };
+        param
+            .param_type
+            .validate(&value)
+            .map_err(|e| format!("param {:?}: {e}", param.name))?;
+        resolved.insert(param.name.clone(), value);
+    }
+    for key in overrides.keys() {
+        if !resolved.contains_key(key) {
+            return Err(format!("param {key:?} is not declared by this recipe"));
+        }
+    }
+    Ok(resolved)
+}
+
+/// Substitute `{{name}}` placeholders in `template` with resolved param values.
+fn render(template: &str, params: &HashMap<String, String>) -> String {
+    let mut out = template.to_string();
+    for (name, value) in params {
+        out = out.replace(&format!("{{{{{name}}}}}"), value);
+    }
+    out
+}
 
-    let recipe = match selected {
+pub fn run_recipe(
+    id: &str,
+    dry_run: bool,
+    overrides: &HashMap<String, String>,
+) -> Result<RecipeRunReport, RecipeRunError> {
+    let recipe = match find_recipe(id) {
         Some(r) => r,
         None => {
             return Err(RecipeRunError {
@@ -144,11 +302,22 @@ pub fn run_recipe(id: &str, dry_run: bool) -> Result<RecipeRunReport, RecipeRunE
                     "reason": "not_found",
                     "id": id,
                 }),
-                exit_code: 2,
+                exit_code: 1,
+                code: "recipe_not_found",
             });
         }
     };
 
+    let params = resolve_params(&recipe, overrides).map_err(|e| RecipeRunError {
+        payload: json!({
+            "recipe_require_failed": true,
+            "reason": e,
+            "id": id,
+        }),
+        exit_code: 1,
+        code: "recipe_invalid_param",
+    })?;
+
     for (idx, step) in recipe.steps.iter().enumerate() {
         let label = step.name.as_deref().unwrap_or_else(|| step.kind.as_str());
         if dry_run {
@@ -167,7 +336,7 @@ pub fn run_recipe(id: &str, dry_run: bool) -> Result<RecipeRunReport, RecipeRunE
             step.kind.as_str(),
             label
         );
-        if let Err(e) = execute_step(step) {
+        if let Err(e) = execute_step(step, &params) {
             return Err(RecipeRunError {
                 payload: json!({
                     "recipe_require_failed": true,
@@ -176,6 +345,7 @@ pub fn run_recipe(id: &str, dry_run: bool) -> Result<RecipeRunReport, RecipeRunE
                     "id": id,
                 }),
                 exit_code: 1,
+                code: "recipe_step_failed",
             });
         }
     }
@@ -187,25 +357,17 @@ pub fn run_recipe(id: &str, dry_run: bool) -> Result<RecipeRunReport, RecipeRunE
     })
 }
 
-fn execute_step(step: &RecipeStep) -> Result<(), String> {
+fn execute_step(step: &RecipeStep, params: &HashMap<String, String>) -> Result<(), String> {
     match step.kind {
-        RecipeKind::Shell => {
+        RecipeKind::Shell | RecipeKind::Check => {
             let command = step
                 .run
                 .as_ref()
-                .ok_or_else(|| "shell step missing 'run'".to_string())?;
-            Command::new("bash")
-                .arg("-lc")
-                .arg(command)
-                .status()
-                .map_err(|e| e.to_string())
-                .and_then(|status| {
-                    if status.success() {
-                        Ok(())
-                    } else {
-                        Err(format!("shell exit code {}", status.code().unwrap_or(-1)))
-                    }
-                })
+                .ok_or_else(|| format!("{} step missing 'run'", step.kind.as_str()))?;
+            run_status(
+                Command::new("bash").arg("-lc").arg(render(command, params)),
+                step.kind.as_str(),
+            )
         }
         RecipeKind::Git => {
             let args = step
@@ -215,17 +377,7 @@ fn execute_step(step: &RecipeStep) -> Result<(), String> {
             if args.is_empty() {
                 return Err("git step args empty".into());
             }
-            Command::new("git")
-                .args(args)
-                .status()
-                .map_err(|e| e.to_string())
-                .and_then(|status| {
-                    if status.success() {
-                        Ok(())
-                    } else {
-                        Err(format!("git exit code {}", status.code().unwrap_or(-1)))
-                    }
-                })
+            run_status(Command::new("git").args(args), "git")
         }
         RecipeKind::Devit => {
             let args = step
@@ -235,27 +387,67 @@ fn execute_step(step: &RecipeStep) -> Result<(), String> {
             if args.is_empty() {
                 return Err("devit step args empty".into());
             }
-            Command::new(env::current_exe().unwrap_or_else(|_| PathBuf::from("devit")))
-                .args(args)
-                .status()
+            run_status(
+                Command::new(env::current_exe().unwrap_or_else(|_| PathBuf::from("devit")))
+                    .args(args),
+                "devit",
+            )
+        }
+        RecipeKind::Context => {
+            let ext_allow = step.only.as_ref().map(|csv| {
+                csv.split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect::<Vec<_>>()
+            });
+            let opts = crate::context::ContextOpts {
+                max_bytes_per_file: 262_144,
+                max_files: 5000,
+                ext_allow,
+                timeout: None,
+                out_path: PathBuf::from(".devit/index.json"),
+                scoring: crate::context::default_scoring_rules(),
+            };
+            crate::context::generate_index(Path::new("."), &opts)
+                .map(|_| ())
                 .map_err(|e| e.to_string())
-                .and_then(|status| {
-                    if status.success() {
-                        Ok(())
-                    } else {
-                        Err(format!("devit exit code {}", status.code().unwrap_or(-1)))
-                    }
-                })
+        }
+        RecipeKind::Prompt => {
+            let template = step
+                .template
+                .as_ref()
+                .ok_or_else(|| "prompt step missing 'template'".to_string())?;
+            let goal = render(template, params);
+            run_status(
+                Command::new(env::current_exe().unwrap_or_else(|_| PathBuf::from("devit")))
+                    .args(["suggest", "--goal", &goal, "--apply", "--yes"]),
+                "prompt",
+            )
         }
     }
 }
 
+fn run_status(cmd: &mut Command, label: &str) -> Result<(), String> {
+    cmd.status()
+        .map_err(|e| e.to_string())
+        .and_then(|status| {
+            if status.success() {
+                Ok(())
+            } else {
+                Err(format!("{label} exit code {}", status.code().unwrap_or(-1)))
+            }
+        })
+}
+
 impl RecipeKind {
     fn as_str(&self) -> &'static str {
         match self {
             RecipeKind::Shell => "shell",
             RecipeKind::Git => "git",
             RecipeKind::Devit => "devit",
+            RecipeKind::Context => "context",
+            RecipeKind::Prompt => "prompt",
+            RecipeKind::Check => "check",
         }
     }
 }
@@ -39,6 +39,134 @@ pub struct QualitySummary {
     pub notes: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub flaky_failed: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_coverage_pct: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch_coverage_pct: Option<f64>,
+    /// True when either percentage above dropped versus
+    /// [`crate::coverage::load_baseline`].
+    #[serde(default)]
+    pub coverage_regressed: bool,
+    /// Failing tests not already present in [`load_quality_baseline`] --
+    /// only set when `quality gate` runs with `--against-baseline`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_tests_failed: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_lint_errors: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_lint_warnings: Option<u32>,
+    /// Findings from the latest `.devit/reports/secrets.sarif.json`
+    /// (`devit scan secrets`). `None` when that report doesn't exist.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secrets_findings: Option<u32>,
+    /// Violations from the latest `.devit/reports/licenses.json`
+    /// (`devit report licenses`). `None` when that report doesn't exist.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license_violations: Option<u32>,
+    /// Highest per-function cyclomatic complexity from the latest
+    /// `.devit/reports/complexity.json` (`devit report complexity`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_complexity: Option<u32>,
+    /// Longest function, in lines, from the same report.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_function_length: Option<usize>,
+}
+
+/// Snapshot of pre-existing failing tests and lint findings, written by
+/// `devit quality baseline` and consumed by `quality gate
+/// --against-baseline` so legacy debt doesn't fail the gate -- only
+/// regressions introduced since the snapshot do.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct QualityBaseline {
+    pub failing_tests: Vec<String>,
+    pub lint_errors: Vec<String>,
+    pub lint_warnings: Vec<String>,
+}
+
+fn quality_baseline_path() -> PathBuf {
+    Path::new(".devit/quality_baseline.json").to_path_buf()
+}
+
+pub fn load_quality_baseline() -> Option<QualityBaseline> {
+    let s = fs::read_to_string(quality_baseline_path()).ok()?;
+    serde_json::from_str(&s).ok()
+}
+
+pub fn save_quality_baseline(baseline: &QualityBaseline) -> Result<()> {
+    let p = quality_baseline_path();
+    if let Some(dir) = p.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(p, serde_json::to_vec_pretty(baseline)?)?;
+    Ok(())
+}
+
+/// Build a [`QualityBaseline`] from the current JUnit/SARIF reports --
+/// failing test titles and SARIF result fingerprints ([`result_fingerprint`]),
+/// the same identity used by [`sarif_merge`]'s dedup.
+pub fn snapshot_baseline(junit: &Path, sarif: &Path) -> QualityBaseline {
+    let failing_tests = junit_annotations(junit)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|a| a.title)
+        .collect();
+    let (lint_errors, lint_warnings) = sarif_result_fingerprints(sarif).unwrap_or_default();
+    QualityBaseline {
+        failing_tests,
+        lint_errors,
+        lint_warnings,
+    }
+}
+
+fn sarif_result_fingerprints(p: &Path) -> Result<(Vec<String>, Vec<String>)> {
+    let v: serde_json::Value = serde_json::from_slice(&fs::read(p)?)?;
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    let runs = v
+        .get("runs")
+        .and_then(|x| x.as_array())
+        .cloned()
+        .unwrap_or_default();
+    for run in runs {
+        if let Some(results) = run.get("results").and_then(|r| r.as_array()) {
+            for res in results {
+                let fp = result_fingerprint(res);
+                match res.get("level").and_then(|l| l.as_str()) {
+                    Some("error") => errors.push(fp),
+                    Some("warning") => warnings.push(fp),
+                    _ => {}
+                }
+            }
+        }
+    }
+    Ok((errors, warnings))
+}
+
+/// Narrow `sum`'s failure/lint counts down to just what's new versus
+/// `baseline`, so [`check_thresholds`] only fails the gate on regressions.
+pub fn apply_baseline_delta(sum: &mut QualitySummary, junit: &Path, sarif: &Path, baseline: &QualityBaseline) {
+    let failing_now: std::collections::HashSet<String> = junit_annotations(junit)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|a| a.title)
+        .collect();
+    let known: std::collections::HashSet<&String> = baseline.failing_tests.iter().collect();
+    sum.new_tests_failed = Some(failing_now.iter().filter(|t| !known.contains(t)).count() as u32);
+
+    let (errors_now, warnings_now) = sarif_result_fingerprints(sarif).unwrap_or_default();
+    let known_errors: std::collections::HashSet<&String> = baseline.lint_errors.iter().collect();
+    let known_warnings: std::collections::HashSet<&String> = baseline.lint_warnings.iter().collect();
+    sum.new_lint_errors = Some(errors_now.iter().filter(|f| !known_errors.contains(f)).count() as u32);
+    sum.new_lint_warnings =
+        Some(warnings_now.iter().filter(|f| !known_warnings.contains(f)).count() as u32);
+}
+
+/// Extract just the test name from a `.devit/flaky_tests.txt` line, which
+/// may be a bare name (hand-maintained, pre-existing format) or
+/// `<name>\t<rfc3339 timestamp>` (appended automatically by
+/// `test_runner::record_flaky` when a retried test passes).
+pub fn flaky_name(line: &str) -> &str {
+    line.split('\t').next().unwrap_or(line).trim()
 }
 
 pub fn read_junit<P: AsRef<Path>>(
@@ -71,7 +199,8 @@ pub fn read_junit<P: AsRef<Path>>(
     if let Some(flaky) = flaky_list {
         // Estimate flaky by matching test names in the XML; naive: search strings
         for name in flaky {
-            if s.contains(name) && s.contains("<failure") {
+            let name = flaky_name(name);
+            if !name.is_empty() && s.contains(name) && s.contains("<failure") {
                 flaky_failed += 1;
             }
         }
@@ -97,6 +226,14 @@ fn attr_num(line: &str, key: &str) -> Option<u32> {
     None
 }
 
+fn attr_str(line: &str, key: &str) -> Option<String> {
+    let pat = format!("{}=\"", key);
+    let i = line.find(&pat)?;
+    let rest = &line[i + pat.len()..];
+    let j = rest.find('"')?;
+    Some(rest[..j].to_string())
+}
+
 pub fn read_sarif<P: AsRef<Path>>(p: P) -> Result<(u32, u32, u32)> {
     let v: serde_json::Value = serde_json::from_slice(&fs::read(&p)?)?;
     let mut errors = 0u32;
@@ -127,6 +264,379 @@ pub fn read_sarif<P: AsRef<Path>>(p: P) -> Result<(u32, u32, u32)> {
     Ok((errors, warnings, rules))
 }
 
+/// Convert ESLint's `--format json` array (`[{filePath, messages:[{ruleId,
+/// severity, message, line, column}]}]`) into a SARIF run, so JS findings
+/// reach the shared `.devit/reports/sarif.json` store the same way clippy's
+/// do -- written to `.devit/reports/eslint.sarif.json`, one of
+/// [`DEFAULT_SARIF_INPUTS`], for `report sarif --merge` to pick up.
+pub fn eslint_json_to_sarif<P: AsRef<Path>>(p: P) -> Result<serde_json::Value> {
+    let v: serde_json::Value = serde_json::from_slice(&fs::read(&p)?)?;
+    let files = v.as_array().cloned().unwrap_or_default();
+    let mut seen_rules = std::collections::HashSet::new();
+    let mut rules = Vec::new();
+    let mut results = Vec::new();
+    for file in &files {
+        let path = file.get("filePath").and_then(|x| x.as_str()).unwrap_or("");
+        let messages = file
+            .get("messages")
+            .and_then(|m| m.as_array())
+            .cloned()
+            .unwrap_or_default();
+        for m in messages {
+            let rule_id = m
+                .get("ruleId")
+                .and_then(|x| x.as_str())
+                .unwrap_or("eslint")
+                .to_string();
+            if seen_rules.insert(rule_id.clone()) {
+                rules.push(serde_json::json!({ "id": rule_id }));
+            }
+            // ESLint severity: 1 = warning, 2 = error.
+            let level = match m.get("severity").and_then(|s| s.as_u64()) {
+                Some(2) => "error",
+                _ => "warning",
+            };
+            let message = m.get("message").and_then(|x| x.as_str()).unwrap_or("");
+            let line = m.get("line").and_then(|x| x.as_u64()).unwrap_or(1);
+            results.push(serde_json::json!({
+                "ruleId": rule_id,
+                "level": level,
+                "message": { "text": message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": path },
+                        "region": { "startLine": line },
+                    }
+                }],
+            }));
+        }
+    }
+    Ok(serde_json::json!({
+        "version": "2.1.0",
+        "runs": [{
+            "tool": { "driver": { "name": "eslint", "rules": rules } },
+            "results": results,
+        }]
+    }))
+}
+
+/// Convert ruff's `--output-format=json` array (`[{filename, code, message,
+/// location:{row, column}}]`) into a SARIF run, written to
+/// `.devit/reports/ruff.sarif.json` -- see [`eslint_json_to_sarif`].
+pub fn ruff_json_to_sarif<P: AsRef<Path>>(p: P) -> Result<serde_json::Value> {
+    let v: serde_json::Value = serde_json::from_slice(&fs::read(&p)?)?;
+    let items = v.as_array().cloned().unwrap_or_default();
+    let mut seen_rules = std::collections::HashSet::new();
+    let mut rules = Vec::new();
+    let mut results = Vec::new();
+    for item in &items {
+        let rule_id = item
+            .get("code")
+            .and_then(|x| x.as_str())
+            .unwrap_or("ruff")
+            .to_string();
+        if seen_rules.insert(rule_id.clone()) {
+            rules.push(serde_json::json!({ "id": rule_id }));
+        }
+        let path = item
+            .get("filename")
+            .and_then(|x| x.as_str())
+            .unwrap_or("");
+        let message = item.get("message").and_then(|x| x.as_str()).unwrap_or("");
+        let line = item
+            .get("location")
+            .and_then(|l| l.get("row"))
+            .and_then(|x| x.as_u64())
+            .unwrap_or(1);
+        // ruff check doesn't report a severity on its own findings; every
+        // reported violation is treated as a lint error.
+        results.push(serde_json::json!({
+            "ruleId": rule_id,
+            "level": "error",
+            "message": { "text": message },
+            "locations": [{
+                "physicalLocation": {
+                    "artifactLocation": { "uri": path },
+                    "region": { "startLine": line },
+                }
+            }],
+        }));
+    }
+    Ok(serde_json::json!({
+        "version": "2.1.0",
+        "runs": [{
+            "tool": { "driver": { "name": "ruff", "rules": rules } },
+            "results": results,
+        }]
+    }))
+}
+
+/// One GitHub Checks/workflow-command annotation: a file/line a failing
+/// test or lint finding maps to, with the message to show inline on the PR
+/// diff. `level` is one of GitHub's `error`/`warning`/`notice`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Annotation {
+    pub file: String,
+    pub line: u32,
+    pub level: String,
+    pub title: String,
+    pub message: String,
+}
+
+/// One annotation per JUnit `<failure>`/`<error>`, attributed to the
+/// nearest preceding `<testcase file="..." line="...">` (cargo-nextest and
+/// pytest both emit these); falls back to `classname`/line 1 when a
+/// framework's JUnit writer omits them.
+pub fn junit_annotations<P: AsRef<Path>>(p: P) -> Result<Vec<Annotation>> {
+    let s = fs::read_to_string(&p)
+        .with_context(|| format!("read junit at {}", p.as_ref().display()))?;
+    let mut out = Vec::new();
+    let mut file = String::new();
+    let mut line_no = 1u32;
+    let mut name = String::new();
+    for line in s.lines() {
+        if line.contains("<testcase") {
+            file = attr_str(line, "file")
+                .or_else(|| attr_str(line, "classname"))
+                .unwrap_or_else(|| "unknown".to_string());
+            line_no = attr_num(line, "line").unwrap_or(1);
+            name = attr_str(line, "name").unwrap_or_default();
+        }
+        if line.contains("<failure") || line.contains("<error") {
+            let message = attr_str(line, "message").unwrap_or_else(|| name.clone());
+            out.push(Annotation {
+                file: file.clone(),
+                line: line_no,
+                level: "error".to_string(),
+                title: name.clone(),
+                message,
+            });
+        }
+    }
+    Ok(out)
+}
+
+/// One annotation per SARIF result, using its primary location directly
+/// (SARIF already carries file/line, unlike JUnit).
+pub fn sarif_annotations<P: AsRef<Path>>(p: P) -> Result<Vec<Annotation>> {
+    let v: serde_json::Value = serde_json::from_slice(&fs::read(&p)?)?;
+    let mut out = Vec::new();
+    let runs = v
+        .get("runs")
+        .and_then(|x| x.as_array())
+        .cloned()
+        .unwrap_or_default();
+    for run in runs {
+        let results = run
+            .get("results")
+            .and_then(|r| r.as_array())
+            .cloned()
+            .unwrap_or_default();
+        for res in results {
+            let rule_id = res
+                .get("ruleId")
+                .and_then(|x| x.as_str())
+                .unwrap_or("")
+                .to_string();
+            let level = match res.get("level").and_then(|l| l.as_str()) {
+                Some("error") => "error",
+                Some("warning") => "warning",
+                _ => "notice",
+            }
+            .to_string();
+            let message = res
+                .get("message")
+                .and_then(|m| m.get("text"))
+                .and_then(|t| t.as_str())
+                .unwrap_or("")
+                .to_string();
+            let loc = res
+                .get("locations")
+                .and_then(|l| l.as_array())
+                .and_then(|l| l.first())
+                .and_then(|l| l.get("physicalLocation"));
+            let file = loc
+                .and_then(|l| l.get("artifactLocation"))
+                .and_then(|a| a.get("uri"))
+                .and_then(|u| u.as_str())
+                .unwrap_or("unknown")
+                .to_string();
+            let line = loc
+                .and_then(|l| l.get("region"))
+                .and_then(|r| r.get("startLine"))
+                .and_then(|l| l.as_u64())
+                .unwrap_or(1) as u32;
+            out.push(Annotation {
+                file,
+                line,
+                level,
+                title: rule_id,
+                message,
+            });
+        }
+    }
+    Ok(out)
+}
+
+/// Render annotations as GitHub Actions workflow commands
+/// (`::error file=...,line=...::message`), one per line, ready to print
+/// straight into a job log so GitHub annotates the PR diff.
+pub fn github_workflow_commands(annotations: &[Annotation]) -> String {
+    annotations
+        .iter()
+        .map(|a| {
+            format!(
+                "::{} file={},line={}::{}",
+                a.level,
+                a.file,
+                a.line,
+                a.message.replace('\n', "%0A")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Render annotations as a Checks API `annotations` array
+/// (https://docs.github.com/rest/checks/runs#create-a-check-run), capped at
+/// 50 per GitHub's own limit per request. The Checks API spells the error
+/// level `failure` where workflow commands spell it `error`.
+pub fn github_checks_payload(annotations: &[Annotation]) -> serde_json::Value {
+    let items: Vec<serde_json::Value> = annotations
+        .iter()
+        .take(50)
+        .map(|a| {
+            let level = if a.level == "error" { "failure" } else { &a.level };
+            serde_json::json!({
+                "path": a.file,
+                "start_line": a.line,
+                "end_line": a.line,
+                "annotation_level": level,
+                "title": a.title,
+                "message": a.message,
+            })
+        })
+        .collect();
+    serde_json::json!({ "annotations": items })
+}
+
+/// Conventional per-analyzer SARIF output paths `report sarif --merge`
+/// looks for when the caller doesn't pass explicit `--input` paths.
+const DEFAULT_SARIF_INPUTS: &[&str] = &[
+    ".devit/reports/clippy.sarif.json",
+    ".devit/reports/eslint.sarif.json",
+    ".devit/reports/ruff.sarif.json",
+];
+
+#[derive(Debug, Clone, serde::Serialize, Default)]
+pub struct SarifMergeStats {
+    pub files_merged: u32,
+    pub runs: u32,
+    pub results_in: u32,
+    pub results_deduped: u32,
+}
+
+/// Resolve the input set for `report sarif --merge`: the caller's
+/// `--input` paths if any were given, else whichever of
+/// [`DEFAULT_SARIF_INPUTS`] exist on disk.
+pub fn sarif_merge_inputs(explicit: &[String]) -> Vec<PathBuf> {
+    if !explicit.is_empty() {
+        return explicit.iter().map(PathBuf::from).collect();
+    }
+    DEFAULT_SARIF_INPUTS
+        .iter()
+        .map(PathBuf::from)
+        .filter(|p| p.exists())
+        .collect()
+}
+
+/// A result's dedup key across analyzers: SARIF's own `partialFingerprints`
+/// when the tool provided one, else a best-effort fingerprint from the rule
+/// id, primary location, and message text.
+fn result_fingerprint(res: &serde_json::Value) -> String {
+    if let Some(fp) = res.get("partialFingerprints").and_then(|f| f.as_object()) {
+        if let Some(v) = fp.values().next().and_then(|v| v.as_str()) {
+            return v.to_string();
+        }
+    }
+    let rule_id = res.get("ruleId").and_then(|x| x.as_str()).unwrap_or("");
+    let message = res
+        .get("message")
+        .and_then(|m| m.get("text"))
+        .and_then(|t| t.as_str())
+        .unwrap_or("");
+    let loc = res
+        .get("locations")
+        .and_then(|l| l.as_array())
+        .and_then(|l| l.first())
+        .and_then(|l| l.get("physicalLocation"));
+    let uri = loc
+        .and_then(|l| l.get("artifactLocation"))
+        .and_then(|a| a.get("uri"))
+        .and_then(|u| u.as_str())
+        .unwrap_or("");
+    let line = loc
+        .and_then(|l| l.get("region"))
+        .and_then(|r| r.get("startLine"))
+        .and_then(|l| l.as_u64())
+        .unwrap_or(0);
+    format!("{rule_id}|{uri}|{line}|{message}")
+}
+
+/// Merge `clippy`/`eslint`/`ruff`/custom SARIF files into one combined
+/// `.devit/reports/sarif.json`, deduping results across every input by
+/// [`result_fingerprint`] (keeping the first occurrence) before writing.
+pub fn sarif_merge(inputs: &[PathBuf], out: &Path) -> Result<SarifMergeStats> {
+    let mut stats = SarifMergeStats::default();
+    let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let mut runs: Vec<serde_json::Value> = Vec::new();
+    for p in inputs {
+        let Ok(bytes) = fs::read(p) else { continue };
+        let Ok(v) = serde_json::from_slice::<serde_json::Value>(&bytes) else {
+            continue;
+        };
+        let file_runs = v
+            .get("runs")
+            .and_then(|r| r.as_array())
+            .cloned()
+            .unwrap_or_default();
+        stats.files_merged += 1;
+        for mut run in file_runs {
+            let results = run
+                .get("results")
+                .and_then(|r| r.as_array())
+                .cloned()
+                .unwrap_or_default();
+            stats.results_in += results.len() as u32;
+            let deduped: Vec<serde_json::Value> = results
+                .into_iter()
+                .filter(|res| seen.insert(result_fingerprint(res)))
+                .collect();
+            if let Some(obj) = run.as_object_mut() {
+                obj.insert("results".to_string(), serde_json::Value::Array(deduped));
+            }
+            stats.runs += 1;
+            runs.push(run);
+        }
+    }
+    stats.results_deduped = stats.results_in - runs.iter().fold(0u32, |acc, r| {
+        acc + r
+            .get("results")
+            .and_then(|x| x.as_array())
+            .map(|a| a.len() as u32)
+            .unwrap_or(0)
+    });
+    let merged = serde_json::json!({
+        "version": "2.1.0",
+        "runs": runs,
+    });
+    if let Some(dir) = out.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(out, serde_json::to_vec_pretty(&merged)?)?;
+    Ok(stats)
+}
+
 pub fn summarize(
     junit_path: &Path,
     sarif_path: &Path,
@@ -169,23 +679,123 @@ pub fn summarize(
             sum.notes.push(format!("sarif missing: {}", e));
         }
     }
+    if let Some(totals) = crate::coverage::latest_coverage_totals() {
+        sum.line_coverage_pct = Some(totals.line_pct);
+        sum.branch_coverage_pct = totals.branch_pct;
+        if let Some(baseline) = crate::coverage::load_baseline() {
+            if totals.line_pct < baseline.line_pct {
+                sum.coverage_regressed = true;
+            }
+            if let (Some(b), Some(t)) = (baseline.branch_pct, totals.branch_pct) {
+                if t < b {
+                    sum.coverage_regressed = true;
+                }
+            }
+        }
+    } else if cfg.min_line_coverage.is_some() || cfg.min_branch_coverage.is_some() {
+        sum.notes.push("coverage missing".to_string());
+    }
+    let secrets_sarif = Path::new(".devit/reports/secrets.sarif.json");
+    if secrets_sarif.is_file() {
+        if let Ok((findings, _, _)) = read_sarif(secrets_sarif) {
+            sum.secrets_findings = Some(findings);
+        }
+    }
+    let licenses_report = Path::new(".devit/reports/licenses.json");
+    if let Ok(s) = fs::read_to_string(licenses_report) {
+        if let Ok(v) = serde_json::from_str::<serde_json::Value>(&s) {
+            if let Some(violations) = v.get("violations").and_then(|x| x.as_array()) {
+                sum.license_violations = Some(violations.len() as u32);
+            }
+        }
+    }
+    let complexity_report = Path::new(".devit/reports/complexity.json");
+    if let Ok(s) = fs::read_to_string(complexity_report) {
+        if let Ok(v) = serde_json::from_str::<serde_json::Value>(&s) {
+            sum.max_complexity = v.get("max_complexity").and_then(|x| x.as_u64()).map(|x| x as u32);
+            sum.max_function_length = v
+                .get("max_length")
+                .and_then(|x| x.as_u64())
+                .map(|x| x as usize);
+        }
+    }
     sum.duration_ms = dur.elapsed().as_millis() as u64;
     Ok(sum)
 }
 
 pub fn check_thresholds(sum: &QualitySummary, cfg: &QualityCfg) -> bool {
-    if sum.tests_failed > cfg.max_test_failures {
+    // With `--against-baseline`, apply_baseline_delta() fills the `new_*`
+    // fields and pre-existing debt is exempt from these checks.
+    let tests_failed = sum.new_tests_failed.unwrap_or(sum.tests_failed);
+    let lint_errors = sum.new_lint_errors.unwrap_or(sum.lint_errors);
+    let lint_warnings = sum.new_lint_warnings.unwrap_or(sum.lint_warnings);
+    if tests_failed > cfg.max_test_failures {
         return false;
     }
-    if sum.lint_errors > cfg.max_lint_errors {
+    if lint_errors > cfg.max_lint_errors {
         return false;
     }
-    if !cfg.allow_lint_warnings && sum.lint_warnings > 0 {
+    if !cfg.allow_lint_warnings && lint_warnings > 0 {
         return false;
     }
+    if let Some(min) = cfg.min_line_coverage {
+        if sum.line_coverage_pct.is_none_or(|p| p < min) {
+            return false;
+        }
+    }
+    if let Some(min) = cfg.min_branch_coverage {
+        if sum.branch_coverage_pct.is_none_or(|p| p < min) {
+            return false;
+        }
+    }
+    if sum.coverage_regressed {
+        return false;
+    }
+    if sum.secrets_findings.unwrap_or(0) > cfg.max_secrets {
+        return false;
+    }
+    if sum.license_violations.unwrap_or(0) > cfg.max_license_violations {
+        return false;
+    }
+    if let Some(max) = cfg.max_function_complexity {
+        if sum.max_complexity.is_some_and(|c| c > max) {
+            return false;
+        }
+    }
+    if let Some(max) = cfg.max_function_length {
+        if sum.max_function_length.is_some_and(|l| l > max) {
+            return false;
+        }
+    }
     true
 }
 
+/// One-line `- Pre-commit: ...` summary from the latest
+/// `.devit/reports/precommit.json` (`precommit::run`'s structured
+/// per-tool report), or `n/a` when the gate hasn't run yet.
+fn precommit_summary_line() -> String {
+    let Ok(s) = std::fs::read_to_string(".devit/reports/precommit.json") else {
+        return "- Pre-commit: n/a\n".to_string();
+    };
+    let Ok(v) = serde_json::from_str::<serde_json::Value>(&s) else {
+        return "- Pre-commit: n/a\n".to_string();
+    };
+    let ok = v.get("ok").and_then(|x| x.as_bool()).unwrap_or(true);
+    let tools = v.get("tools").and_then(|x| x.as_array()).cloned().unwrap_or_default();
+    if ok {
+        return format!("- Pre-commit: ok ({} tools)\n", tools.len());
+    }
+    let failed: Vec<String> = tools
+        .iter()
+        .filter(|t| {
+            !t.get("ok").and_then(|o| o.as_bool()).unwrap_or(true)
+                && t.get("blocking").and_then(|b| b.as_bool()).unwrap_or(false)
+        })
+        .map(|t| t.get("tool").and_then(|n| n.as_str()).unwrap_or("?").to_string())
+        .collect();
+    format!("- Pre-commit: failed ({})\n", failed.join(", "))
+}
+
 pub fn summary_markdown(junit: &Path, sarif: &Path, out: &Path) -> Result<()> {
     let q = QualityCfg::default();
     let sum = summarize(junit, sarif, &q, None)?;
@@ -217,16 +827,20 @@ pub fn summary_markdown(junit: &Path, sarif: &Path, out: &Path) -> Result<()> {
             ));
         }
     }
-    // Pre-commit not tracked here; keep placeholder
-    md.push_str("- Pre-commit: n/a\n");
+    md.push_str(&precommit_summary_line());
     md.push_str(&format!(
         "- Tests: {}/{} failed\n",
         sum.tests_failed, sum.tests_total
     ));
     md.push_str(&format!(
-        "- Lint: {} errors, {} warnings\n\n",
+        "- Lint: {} errors, {} warnings\n",
         sum.lint_errors, sum.lint_warnings
     ));
+    if let Some((fmt, pct)) = crate::coverage::latest_totals() {
+        md.push_str(&format!("- Coverage: {:.1}% lines ({})\n\n", pct, fmt));
+    } else {
+        md.push('\n');
+    }
     // Top files from .devit/index.json if present
     if let Ok(s) = std::fs::read_to_string(".devit/index.json") {
         if let Ok(v) = serde_json::from_str::<serde_json::Value>(&s) {
@@ -241,7 +855,7 @@ pub fn summary_markdown(junit: &Path, sarif: &Path, out: &Path) -> Result<()> {
                     let score = f.get("score").and_then(|x| x.as_i64()).unwrap_or(0);
                     rows.push((score, p));
                 }
-                rows.sort_by(|a, b| b.0.cmp(&a.0));
+                rows.sort_by_key(|r| std::cmp::Reverse(r.0));
                 md.push_str("## Top impacted files\n");
                 for (_s, p) in rows.into_iter().take(10) {
                     md.push_str(&format!("- {}\n", p));
@@ -256,3 +870,209 @@ pub fn summary_markdown(junit: &Path, sarif: &Path, out: &Path) -> Result<()> {
     std::fs::write(out, md)?;
     Ok(())
 }
+
+/// Build the markdown block for `devit report pr-comment`: gate verdict,
+/// new findings since the `quality baseline` snapshot (if any), flaky
+/// tests, coverage delta, and recent `DevIt-Attest` hashes -- sized and
+/// formatted to be posted as a single sticky PR comment rather than linked
+/// to as a separate report.
+pub fn pr_comment_markdown(junit: &Path, sarif: &Path, cfg: &QualityCfg) -> Result<String> {
+    let flaky_path = ".devit/flaky_tests.txt";
+    let flaky = std::fs::read_to_string(flaky_path).ok().map(|s| {
+        s.lines()
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<_>>()
+    });
+    let mut sum = summarize(junit, sarif, cfg, flaky.as_deref())?;
+    let against_baseline = load_quality_baseline();
+    if let Some(baseline) = &against_baseline {
+        apply_baseline_delta(&mut sum, junit, sarif, baseline);
+    }
+    let pass = check_thresholds(&sum, cfg);
+
+    let mut md = String::new();
+    md.push_str("### DevIt quality report\n\n");
+    md.push_str(if pass {
+        "**Verdict:** ✅ pass\n\n"
+    } else {
+        "**Verdict:** ❌ fail\n\n"
+    });
+    md.push_str(&format!(
+        "- Tests: {} failed / {} total\n",
+        sum.new_tests_failed.unwrap_or(sum.tests_failed),
+        sum.tests_total
+    ));
+    md.push_str(&format!(
+        "- Lint: {} errors, {} warnings\n",
+        sum.new_lint_errors.unwrap_or(sum.lint_errors),
+        sum.new_lint_warnings.unwrap_or(sum.lint_warnings)
+    ));
+    if against_baseline.is_some() {
+        md.push_str("  _(counts are new findings since the quality baseline)_\n");
+    }
+    if let Some(n) = sum.flaky_failed {
+        if n > 0 {
+            md.push_str(&format!("- Flaky: {n} failures ignored\n"));
+        }
+    }
+    if let Some(pct) = sum.line_coverage_pct {
+        let delta = crate::coverage::load_baseline()
+            .map(|b| format!(" ({:+.1} vs baseline)", pct - b.line_pct))
+            .unwrap_or_default();
+        md.push_str(&format!("- Coverage: {pct:.1}% lines{delta}\n"));
+    }
+    if sum.coverage_regressed {
+        md.push_str("- ⚠️ Coverage regressed versus baseline\n");
+    }
+    if !sum.notes.is_empty() {
+        md.push('\n');
+        for note in &sum.notes {
+            md.push_str(&format!("> {note}\n"));
+        }
+    }
+    let hashes: Vec<String> = crate::history::collect(None, None, false)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|e| e.get("type").and_then(|t| t.as_str()) == Some("commit"))
+        .filter_map(|e| {
+            e.get("attest_hash")
+                .and_then(|h| h.as_str())
+                .map(|s| s.to_string())
+        })
+        .take(5)
+        .collect();
+    if !hashes.is_empty() {
+        md.push_str("\n<details><summary>Recent DevIt-Attest hashes</summary>\n\n");
+        for h in &hashes {
+            md.push_str(&format!("- `{h}`\n"));
+        }
+        md.push_str("\n</details>\n");
+    }
+    Ok(md)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn junit_with_failures(names: &[&str]) -> String {
+        let cases: String = names
+            .iter()
+            .map(|n| {
+                format!(
+                    "<testcase name=\"{n}\" classname=\"c\">\n<failure message=\"boom\"/>\n</testcase>\n"
+                )
+            })
+            .collect();
+        format!(
+            "<testsuites>\n<testsuite name=\"s\" tests=\"{}\" failures=\"{}\">\n{cases}</testsuite>\n</testsuites>\n",
+            names.len(),
+            names.len()
+        )
+    }
+
+    fn sarif_with(errors: &[&str], warnings: &[&str]) -> serde_json::Value {
+        // `result_fingerprint` prefers `partialFingerprints` when present,
+        // so fixtures can use predictable fingerprint strings directly.
+        let result = |rule: &str, level: &str| {
+            serde_json::json!({
+                "ruleId": rule,
+                "level": level,
+                "message": {"text": "m"},
+                "partialFingerprints": {"fp": rule},
+            })
+        };
+        let results: Vec<_> = errors
+            .iter()
+            .map(|r| result(r, "error"))
+            .chain(warnings.iter().map(|r| result(r, "warning")))
+            .collect();
+        serde_json::json!({"version": "2.1.0", "runs": [{"results": results}]})
+    }
+
+    #[test]
+    fn snapshot_baseline_captures_current_failures_and_lints() {
+        let dir = tempfile::tempdir().unwrap();
+        let junit = dir.path().join("junit.xml");
+        let sarif = dir.path().join("sarif.json");
+        fs::write(&junit, junit_with_failures(&["test_a", "test_b"])).unwrap();
+        fs::write(&sarif, sarif_with(&["E1"], &["W1"]).to_string()).unwrap();
+
+        let baseline = snapshot_baseline(&junit, &sarif);
+        assert_eq!(baseline.failing_tests, vec!["test_a", "test_b"]);
+        assert_eq!(baseline.lint_errors, vec!["E1"]);
+        assert_eq!(baseline.lint_warnings, vec!["W1"]);
+    }
+
+    #[test]
+    fn apply_baseline_delta_excludes_known_failures_and_lints() {
+        let dir = tempfile::tempdir().unwrap();
+        let junit = dir.path().join("junit.xml");
+        let sarif = dir.path().join("sarif.json");
+        fs::write(&junit, junit_with_failures(&["test_a", "test_b"])).unwrap();
+        fs::write(&sarif, sarif_with(&["E1", "E2"], &["W1"]).to_string()).unwrap();
+
+        let baseline = QualityBaseline {
+            failing_tests: vec!["test_a".to_string()],
+            lint_errors: vec!["E1".to_string()],
+            lint_warnings: vec![],
+        };
+        let mut sum = QualitySummary::default();
+        apply_baseline_delta(&mut sum, &junit, &sarif, &baseline);
+
+        assert_eq!(sum.new_tests_failed, Some(1)); // only test_b is new
+        assert_eq!(sum.new_lint_errors, Some(1)); // only E2 is new
+        assert_eq!(sum.new_lint_warnings, Some(1)); // W1 wasn't in baseline
+    }
+
+    #[test]
+    fn check_thresholds_exempts_baseline_debt_but_catches_new_failures() {
+        let cfg = QualityCfg {
+            max_test_failures: 0,
+            max_lint_errors: 0,
+            allow_lint_warnings: true,
+            ..Default::default()
+        };
+        // `--against-baseline` absorbed all 5 failures as pre-existing debt
+        // (new_tests_failed/new_lint_errors both 0): gate passes despite the
+        // raw counts exceeding the thresholds.
+        let sum = QualitySummary {
+            tests_failed: 5,
+            new_tests_failed: Some(0),
+            lint_errors: 3,
+            new_lint_errors: Some(0),
+            ..Default::default()
+        };
+        assert!(check_thresholds(&sum, &cfg));
+
+        // One of those failures is new (not in the baseline): gate fails.
+        let sum_with_new_failure = QualitySummary {
+            tests_failed: 5,
+            new_tests_failed: Some(1),
+            ..Default::default()
+        };
+        assert!(!check_thresholds(&sum_with_new_failure, &cfg));
+    }
+
+    #[test]
+    fn quality_baseline_round_trips_through_disk() {
+        let _lock = crate::CWD_LOCK.lock().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let prev = std::env::current_dir().unwrap();
+        std::env::set_current_dir(dir.path()).unwrap();
+
+        let baseline = QualityBaseline {
+            failing_tests: vec!["t".to_string()],
+            lint_errors: vec!["E1".to_string()],
+            lint_warnings: vec![],
+        };
+        save_quality_baseline(&baseline).unwrap();
+        let loaded = load_quality_baseline().unwrap();
+
+        std::env::set_current_dir(prev).unwrap();
+
+        assert_eq!(loaded.failing_tests, baseline.failing_tests);
+        assert_eq!(loaded.lint_errors, baseline.lint_errors);
+    }
+}
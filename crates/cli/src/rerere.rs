@@ -0,0 +1,126 @@
+// # -----------------------------
+// # crates/cli/src/rerere.rs
+// # -----------------------------
+// Resolution memory for `devit merge`: remembers the resolution applied to
+// a conflict hunk, keyed by a fingerprint of its `ours`/`theirs` text, so
+// the identical conflict reappearing later -- typically while replaying the
+// same commit across a rebase -- can be auto-proposed instead of resolved
+// by hand (or by the LLM) a second time. Same idea as `git rerere`, one
+// JSON file per fingerprint under `.devit/rerere/` instead of git's own
+// `rr-cache`.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedResolution {
+    resolution: String,
+    recorded_at: String,
+}
+
+fn dir() -> PathBuf {
+    Path::new(".devit/rerere").to_path_buf()
+}
+
+/// Fingerprint a conflict hunk from its trimmed `ours`/`theirs` text --
+/// stable across the hunk's line numbers moving around, same as git
+/// rerere's own whitespace-insensitive conflict hash.
+pub fn fingerprint(ours: &str, theirs: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(ours.trim().as_bytes());
+    hasher.update(b"\0");
+    hasher.update(theirs.trim().as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+fn path_for(fp: &str) -> PathBuf {
+    dir().join(format!("{fp}.json"))
+}
+
+/// Record the resolution applied to a conflict hunk, keyed by its
+/// fingerprint, so [`propose`] recognizes it next time it recurs.
+pub fn record(fp: &str, resolution: &str) {
+    let _ = fs::create_dir_all(dir());
+    let entry = RecordedResolution {
+        resolution: resolution.to_string(),
+        recorded_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+    };
+    if let Ok(json) = serde_json::to_string_pretty(&entry) {
+        let _ = fs::write(path_for(fp), json);
+    }
+}
+
+/// The previously recorded resolution for this exact conflict, if any.
+pub fn propose(fp: &str) -> Option<String> {
+    let content = fs::read_to_string(path_for(fp)).ok()?;
+    let entry: RecordedResolution = serde_json::from_str(&content).ok()?;
+    Some(entry.resolution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CWD_LOCK;
+
+    struct CwdGuard(std::path::PathBuf);
+
+    impl CwdGuard {
+        fn enter(dir: &Path) -> Self {
+            let prev = std::env::current_dir().unwrap();
+            std::env::set_current_dir(dir).unwrap();
+            Self(prev)
+        }
+    }
+
+    impl Drop for CwdGuard {
+        fn drop(&mut self) {
+            let _ = std::env::set_current_dir(&self.0);
+        }
+    }
+
+    #[test]
+    fn fingerprint_is_stable_and_whitespace_insensitive() {
+        let a = fingerprint("foo\n", "bar\n");
+        let b = fingerprint("  foo  ", "  bar  ");
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn fingerprint_distinguishes_ours_from_theirs() {
+        assert_ne!(fingerprint("foo", "bar"), fingerprint("bar", "foo"));
+    }
+
+    #[test]
+    fn propose_is_none_before_any_record() {
+        let _lock = CWD_LOCK.lock().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let _cwd = CwdGuard::enter(dir.path());
+
+        assert_eq!(propose(&fingerprint("foo", "bar")), None);
+    }
+
+    #[test]
+    fn record_then_propose_round_trips() {
+        let _lock = CWD_LOCK.lock().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let _cwd = CwdGuard::enter(dir.path());
+
+        let fp = fingerprint("foo", "bar");
+        record(&fp, "theirs");
+        assert_eq!(propose(&fp), Some("theirs".to_string()));
+    }
+
+    #[test]
+    fn record_overwrites_previous_resolution() {
+        let _lock = CWD_LOCK.lock().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let _cwd = CwdGuard::enter(dir.path());
+
+        let fp = fingerprint("foo", "bar");
+        record(&fp, "ours");
+        record(&fp, "keep_both");
+        assert_eq!(propose(&fp), Some("keep_both".to_string()));
+    }
+}
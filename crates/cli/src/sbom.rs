@@ -5,6 +5,15 @@ use std::collections::BTreeSet;
 use std::fs;
 use std::path::Path;
 
+/// sha256 of a previously generated SBOM file, `None` if it doesn't exist
+/// -- used to reference the SBOM from the `DevIt-Attest` provenance footer.
+pub fn sha256_hex(path: &Path) -> Option<String> {
+    let bytes = fs::read(path).ok()?;
+    let mut h = Sha256::new();
+    h.update(&bytes);
+    Some(hex::encode(h.finalize()))
+}
+
 pub fn generate(out: &Path) -> Result<()> {
     let mut components = Vec::new();
 
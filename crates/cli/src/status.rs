@@ -0,0 +1,97 @@
+// # -----------------------------
+// # crates/cli/src/status.rs
+// # -----------------------------
+// Backing implementation for `devit status`: a single dashboard for where
+// the agent left off (dirty worktree, pending reports, recent journal
+// activity, last quality verdict, recipes, backend reachability).
+
+use crate::recipes::list_recipes;
+use devit_common::Config;
+use devit_tools::git;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+#[derive(Debug, Serialize)]
+pub struct Status {
+    pub dirty: bool,
+    pub dirty_files: Vec<String>,
+    pub pending_reports: Vec<String>,
+    pub last_events: Vec<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_quality_verdict: Option<Value>,
+    pub recipes: Vec<String>,
+    pub backend_reachable: bool,
+}
+
+fn dirty_worktree() -> (bool, Vec<String>) {
+    let files: Vec<String> = git::status_porcelain()
+        .unwrap_or_default()
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect();
+    (!files.is_empty(), files)
+}
+
+fn pending_reports() -> Vec<String> {
+    let Ok(entries) = fs::read_dir(Path::new(".devit/reports")) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path().display().to_string())
+        .collect()
+}
+
+fn last_events(limit: usize) -> Vec<Value> {
+    let Ok(text) = fs::read_to_string(".devit/journal.jsonl") else {
+        return Vec::new();
+    };
+    let lines: Vec<&str> = text.lines().collect();
+    let start = lines.len().saturating_sub(limit);
+    lines[start..]
+        .iter()
+        .filter_map(|l| serde_json::from_str::<Value>(l).ok())
+        .collect()
+}
+
+fn last_quality_verdict() -> Option<Value> {
+    fs::read_to_string(".devit/reports/quality_verdict.json")
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+}
+
+fn recipe_ids() -> Vec<String> {
+    list_recipes()
+        .map(|rs| rs.into_iter().map(|r| r.id).collect())
+        .unwrap_or_default()
+}
+
+async fn backend_reachable(cfg: &Config) -> bool {
+    if cfg.backend.base_url.trim().is_empty() {
+        return false;
+    }
+    let Ok(client) = reqwest::Client::builder()
+        .timeout(Duration::from_secs(2))
+        .build()
+    else {
+        return false;
+    };
+    client.get(&cfg.backend.base_url).send().await.is_ok()
+}
+
+pub async fn collect(cfg: &Config) -> Status {
+    let (dirty, dirty_files) = dirty_worktree();
+    Status {
+        dirty,
+        dirty_files,
+        pending_reports: pending_reports(),
+        last_events: last_events(10),
+        last_quality_verdict: last_quality_verdict(),
+        recipes: recipe_ids(),
+        backend_reachable: backend_reachable(cfg).await,
+    }
+}
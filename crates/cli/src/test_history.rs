@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+/// One test's duration from a single run, appended to
+/// `.devit/history/tests.jsonl` so `devit report slow-tests` can spot
+/// consistently-slow tests and regressions across runs without needing a
+/// database — same append-only-JSONL pattern as `.devit/journal.jsonl`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimingEntry {
+    pub id: String,
+    pub name: String,
+    pub suite: String,
+    pub passed: bool,
+    pub duration_ms: u128,
+    pub ts: String,
+}
+
+fn history_path() -> PathBuf {
+    Path::new(".devit/history/tests.jsonl").to_path_buf()
+}
+
+/// Append one run's per-test timings. `cases` is `(name, passed,
+/// duration_ms)`; callers that only have aggregate counts (npm, ctest, ...)
+/// have nothing worth recording here and should skip this entirely.
+pub fn record(suite: &str, cases: &[(String, bool, u128)]) {
+    if cases.is_empty() {
+        return;
+    }
+    let path = history_path();
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let ts = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    let mut out = String::new();
+    for (name, passed, duration_ms) in cases {
+        let entry = TimingEntry {
+            id: format!("{suite}::{name}"),
+            name: name.clone(),
+            suite: suite.to_string(),
+            passed: *passed,
+            duration_ms: *duration_ms,
+            ts: ts.clone(),
+        };
+        if let Ok(line) = serde_json::to_string(&entry) {
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+    if let Ok(mut f) = fs::OpenOptions::new().create(true).append(true).open(&path) {
+        let _ = f.write_all(out.as_bytes());
+    }
+}
+
+fn load() -> Vec<TimingEntry> {
+    let content = fs::read_to_string(history_path()).unwrap_or_default();
+    content
+        .lines()
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect()
+}
+
+/// One test's history summary: its most recent duration plus the delta
+/// versus its previous run (positive = got slower).
+#[derive(Debug, Clone, Serialize)]
+pub struct SlowTest {
+    pub id: String,
+    pub name: String,
+    pub suite: String,
+    pub last_duration_ms: u128,
+    pub delta_ms: i128,
+    pub runs: usize,
+}
+
+fn per_test_summaries() -> Vec<SlowTest> {
+    let entries = load();
+    let mut by_id: std::collections::HashMap<String, Vec<&TimingEntry>> =
+        std::collections::HashMap::new();
+    for e in &entries {
+        by_id.entry(e.id.clone()).or_default().push(e);
+    }
+    by_id
+        .into_iter()
+        .map(|(id, mut runs)| {
+            runs.sort_by(|a, b| a.ts.cmp(&b.ts));
+            let last = *runs.last().unwrap();
+            let delta_ms = if runs.len() >= 2 {
+                last.duration_ms as i128 - runs[runs.len() - 2].duration_ms as i128
+            } else {
+                0
+            };
+            SlowTest {
+                id,
+                name: last.name.clone(),
+                suite: last.suite.clone(),
+                last_duration_ms: last.duration_ms,
+                delta_ms,
+                runs: runs.len(),
+            }
+        })
+        .collect()
+}
+
+/// Rank tests by their most recent duration — the "slowest" half of `devit
+/// report slow-tests`.
+pub fn slowest(limit: usize) -> Vec<SlowTest> {
+    let mut out = per_test_summaries();
+    out.sort_by_key(|t| std::cmp::Reverse(t.last_duration_ms));
+    out.truncate(limit);
+    out
+}
+
+/// Rank tests by how much slower their most recent run was versus the one
+/// before it — the "most-regressed" half of `devit report slow-tests`.
+pub fn most_regressed(limit: usize) -> Vec<SlowTest> {
+    let mut out = per_test_summaries();
+    out.retain(|t| t.delta_ms > 0);
+    out.sort_by_key(|t| std::cmp::Reverse(t.delta_ms));
+    out.truncate(limit);
+    out
+}
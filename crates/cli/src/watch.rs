@@ -0,0 +1,114 @@
+use anyhow::Result;
+use devit_common::Event;
+use notify::{RecursiveMode, Watcher};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration as StdDuration;
+
+const DEBOUNCE: StdDuration = StdDuration::from_millis(300);
+
+/// `devit test watch`: watch the workspace and rerun only the impacted
+/// tests for files that just changed, streaming pass/fail events to
+/// `.devit/journal.jsonl` so the TUI shows them live.
+pub fn run_watch(
+    framework: String,
+    timeout_secs: Option<u64>,
+    test_cfg: devit_common::TestCfg,
+) -> Result<()> {
+    let root = std::env::current_dir()?;
+    let root_abs = fs::canonicalize(&root).unwrap_or_else(|_| root.clone());
+    let devit_dir = {
+        let joined = root_abs.join(".devit");
+        fs::canonicalize(&joined).unwrap_or(joined)
+    };
+    let git_dir = root_abs.join(".git");
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    watcher.watch(&root_abs, RecursiveMode::Recursive)?;
+
+    eprintln!("watching {} for changes…", root_abs.display());
+
+    let is_ignored = |event: &notify::Event| {
+        event.paths.iter().all(|p| {
+            let cp = fs::canonicalize(p).unwrap_or_else(|_| p.clone());
+            cp.starts_with(&devit_dir) || cp.starts_with(&git_dir)
+        })
+    };
+
+    loop {
+        let mut changed: Vec<PathBuf> = Vec::new();
+        let relevant = loop {
+            match rx.recv() {
+                Ok(Ok(event)) if is_ignored(&event) => continue,
+                Ok(Ok(event)) => {
+                    changed.extend(event.paths);
+                    break true;
+                }
+                Ok(Err(_)) => continue,
+                Err(_) => break false, // watcher dropped, stop watching
+            }
+        };
+        if !relevant {
+            return Ok(());
+        }
+        // Drain the rest of the burst so one rerun covers the whole save,
+        // mirroring devit_context::watch_index's debounce.
+        loop {
+            match rx.recv_timeout(DEBOUNCE) {
+                Ok(Ok(event)) if !is_ignored(&event) => {
+                    changed.extend(event.paths);
+                }
+                Ok(_) => continue,
+                Err(std::sync::mpsc::RecvTimeoutError::Timeout) => break,
+                Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => return Ok(()),
+            }
+        }
+
+        let changed_paths: Vec<String> = changed
+            .iter()
+            .filter_map(|p| pathdiff::diff_paths(p, &root_abs))
+            .map(|p| p.display().to_string())
+            .collect();
+        if changed_paths.is_empty() {
+            continue;
+        }
+
+        let _ = crate::journal_event(&Event::Info {
+            message: format!("test watch: rerunning impacted tests for {changed_paths:?}"),
+        });
+
+        let opts = crate::test_runner::ImpactedOpts {
+            changed_from: None,
+            changed_paths: Some(changed_paths),
+            max_jobs: None,
+            framework: Some(framework.clone()),
+            timeout_secs,
+            retries: None,
+            shards: None,
+            shard_index: None,
+            custom_command: test_cfg.impacted_command.clone(),
+            custom_env: test_cfg.env.clone(),
+        };
+        match crate::test_runner::run_impacted(&opts) {
+            Ok(rep) => {
+                eprintln!(
+                    "✅ {} passed, {} failed ({} ran)",
+                    rep.passed, rep.failed, rep.ran
+                );
+                let _ = crate::journal_event(&Event::CommandOut {
+                    line: format!(
+                        "test watch: {} passed, {} failed ({} ran, {}ms)",
+                        rep.passed, rep.failed, rep.ran, rep.duration_ms
+                    ),
+                });
+            }
+            Err(e) => {
+                eprintln!("❌ impacted tests failed: {e}");
+                let _ = crate::journal_event(&Event::Error {
+                    message: format!("test watch: {e}"),
+                });
+            }
+        }
+    }
+}
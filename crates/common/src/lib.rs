@@ -4,6 +4,8 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+pub mod messages;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub backend: BackendCfg,
@@ -16,6 +18,97 @@ pub struct Config {
     pub precommit: Option<PrecommitCfg>,
     #[serde(default)]
     pub commit: Option<CommitCfg>,
+    #[serde(default)]
+    pub secrets: SecretsCfg,
+    #[serde(default)]
+    pub i18n: I18nCfg,
+    #[serde(default)]
+    pub github: GitHubCfg,
+    #[serde(default)]
+    pub gitlab: GitLabCfg,
+    #[serde(default)]
+    pub hooks: HooksCfg,
+    #[serde(default)]
+    pub context: ContextCfg,
+    #[serde(default)]
+    pub test: TestCfg,
+}
+
+/// `devit context map`/`search`/`pack` retrieval tuning.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ContextCfg {
+    /// Glob→weight relevance rules, e.g. `[[context.scoring]]\nglob =
+    /// "src/**"\nweight = 50`. Empty (the default) keeps the built-in
+    /// heuristics.
+    #[serde(default)]
+    pub scoring: Vec<ScoringRuleCfg>,
+}
+
+/// Bespoke test entry points (`make`, `just`, `nx`, ...) for projects where
+/// framework auto-detection (`devit test`/`devit test impacted`) doesn't
+/// apply. When set, `command` replaces the auto-detected `devit test all`
+/// runner and `impacted_command` replaces `devit test impacted`'s
+/// framework dispatch — both are run via `bash -lc`, with `env` merged into
+/// the child's environment.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TestCfg {
+    #[serde(default)]
+    pub command: Option<String>,
+    #[serde(default)]
+    pub impacted_command: Option<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoringRuleCfg {
+    pub glob: String,
+    pub weight: i64,
+}
+
+/// User lifecycle scripts run around `devit apply`/`devit run` (see
+/// `crates/cli/src/hooks.rs`). Each list runs in order, stopping at the
+/// first failure; a failing `pre_apply` hook vetoes the apply.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct HooksCfg {
+    #[serde(default)]
+    pub pre_apply: Vec<String>,
+    #[serde(default)]
+    pub post_commit: Vec<String>,
+    #[serde(default)]
+    pub post_test: Vec<String>,
+}
+
+/// GitHub PR integration settings (see `devit pr create`). The token itself
+/// is never stored here; it comes from `GITHUB_TOKEN` at call time.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct GitHubCfg {
+    /// Labels applied to newly created pull requests.
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// REST API base, override for GitHub Enterprise (default: api.github.com).
+    #[serde(default)]
+    pub api_base: Option<String>,
+}
+
+/// GitLab MR integration settings (see `devit mr create`). The token itself
+/// is never stored here; it comes from `GITLAB_TOKEN` at call time.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct GitLabCfg {
+    /// Labels applied to newly created merge requests.
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// REST API base, override for self-hosted GitLab (default: gitlab.com/api/v4).
+    #[serde(default)]
+    pub api_base: Option<String>,
+}
+
+/// Selects the language used for user-facing text (see [`messages`]).
+/// `DEVIT_LANG` always takes priority over this when set.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct I18nCfg {
+    #[serde(default)]
+    pub lang: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,6 +134,79 @@ pub struct SandboxCfg {
     pub cpu_limit: u32,
     pub mem_limit_mb: u32,
     pub net: String,
+    /// Directories mutating commands are confined to, relative to the
+    /// workspace root unless absolute. `.git/` is always off-limits.
+    #[serde(default = "default_write_roots")]
+    pub write_roots: Vec<String>,
+    /// Wall-clock budget for a single `shell_exec` call. Falls back to
+    /// `DEVIT_TIMEOUT_SECS`, then a built-in default, when unset.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// Extra binaries permitted on top of `policy.profile`'s built-in tier
+    /// (ignored by the `read-only` profile, irrelevant to `danger`).
+    #[serde(default = "default_allow_bins")]
+    pub allow_bins: Vec<String>,
+    /// Binaries rejected even if present in `allow_bins`; checked first.
+    #[serde(default)]
+    pub deny_bins: Vec<String>,
+    /// Binaries treated as network-sensitive and blocked when `net = "off"`.
+    #[serde(default = "default_net_bins")]
+    pub net_bins: Vec<String>,
+    /// Domains reachable when `net = "allowlist"`. A leading `.` matches any
+    /// subdomain (e.g. `.crates.io` also allows `static.crates.io`).
+    #[serde(default)]
+    pub net_allowlist: Vec<String>,
+}
+
+fn default_allow_bins() -> Vec<String> {
+    [
+        "true", "false", "printf", "echo", "cat", "ls", "stat", "head", "tail", "wc", "cut",
+        "sort", "uniq", "tr", "sed", "awk", "grep", "rg", "find", "xargs", "dirname", "basename",
+        "pwd",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+fn default_net_bins() -> Vec<String> {
+    ["curl", "wget", "pip", "npm", "apt", "git", "ssh", "scp"]
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+fn default_write_roots() -> Vec<String> {
+    vec![".".into()]
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretsCfg {
+    /// Environment variable names inherited by sandboxed children; anything
+    /// not in this list (e.g. API keys, tokens) is dropped rather than
+    /// merely unset case-by-case.
+    #[serde(default = "default_env_allow")]
+    pub env_allow: Vec<String>,
+}
+
+impl Default for SecretsCfg {
+    fn default() -> Self {
+        Self {
+            env_allow: default_env_allow(),
+        }
+    }
+}
+
+fn default_env_allow() -> Vec<String> {
+    vec![
+        "PATH".into(),
+        "HOME".into(),
+        "LANG".into(),
+        "LC_ALL".into(),
+        "TERM".into(),
+        "TMPDIR".into(),
+        "TZ".into(),
+    ]
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,6 +221,15 @@ pub struct GitCfg {
 pub struct ProvenanceCfg {
     #[serde(default)]
     pub footer: bool,
+    /// `Co-authored-by: <identity>` trailer appended alongside
+    /// `DevIt-Attest` (e.g. `"DevIt <devit@users.noreply.github.com>"`).
+    /// `None` disables it.
+    #[serde(default)]
+    pub co_author: Option<String>,
+    /// Append a `DevIt-Model: <backend.model>` trailer alongside
+    /// `DevIt-Attest`.
+    #[serde(default)]
+    pub attribute_model: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -67,6 +242,40 @@ pub struct QualityCfg {
     pub allow_lint_warnings: bool,
     #[serde(default)]
     pub fail_on_missing_reports: bool,
+    /// Minimum line coverage %, checked against the latest
+    /// `.devit/reports/coverage/` report. `None` skips the check.
+    #[serde(default)]
+    pub min_line_coverage: Option<f64>,
+    /// Minimum branch coverage %, same semantics as `min_line_coverage`.
+    #[serde(default)]
+    pub min_branch_coverage: Option<f64>,
+    /// Max secrets-scan findings (`devit scan secrets`) tolerated before the
+    /// gate fails.
+    #[serde(default)]
+    pub max_secrets: u32,
+    /// Max license-policy violations (`devit report licenses`) tolerated
+    /// before the gate fails.
+    #[serde(default)]
+    pub max_license_violations: u32,
+    /// Max cyclomatic complexity per function (`devit report complexity`).
+    /// `None` skips the check.
+    #[serde(default)]
+    pub max_function_complexity: Option<u32>,
+    /// Max function length in lines, same semantics as
+    /// `max_function_complexity`.
+    #[serde(default)]
+    pub max_function_length: Option<usize>,
+}
+
+/// `[licenses]` allow/deny policy checked by `devit report licenses`.
+/// Denied licenses always fail; when `allow` is non-empty, anything not
+/// listed there fails too.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct LicensesCfg {
+    #[serde(default)]
+    pub allow: Vec<String>,
+    #[serde(default)]
+    pub deny: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -79,12 +288,45 @@ pub struct CommitCfg {
     pub default_type: Option<String>,
     #[serde(default)]
     pub template_body: Option<String>,
+    /// Ask the backend for a short body paragraph summarizing *why* the
+    /// change was made, alongside the existing heuristic/template body.
+    #[serde(default)]
+    pub llm_body: bool,
+    /// Ticket prefixes (e.g. `PROJ`) to scan for in the current branch name
+    /// -- a match on `<prefix>-<digits>` (e.g. `feature/PROJ-123-foo`)
+    /// appends a `Refs: PROJ-123` trailer. Empty disables the inference.
+    #[serde(default)]
+    pub issue_prefixes: Vec<String>,
+    /// Commit types accepted by `devit commit-msg lint`.
+    #[serde(default = "default_commit_types")]
+    pub types: Vec<String>,
+    /// Scopes accepted by `devit commit-msg lint`. Empty allows any scope.
+    #[serde(default)]
+    pub allowed_scopes: Vec<String>,
+    /// Subject-line style: `"conventional"` (default), `"gitmoji"`, or
+    /// `"custom"` (rendered from `subject_template`).
+    #[serde(default)]
+    pub style: Option<String>,
+    /// Template for `style = "custom"`; placeholders `{type}`, `{scope}`,
+    /// `{bang}`, `{subject}`.
+    #[serde(default)]
+    pub subject_template: Option<String>,
 }
 
 fn default_max_subject() -> usize {
     72
 }
 
+fn default_commit_types() -> Vec<String> {
+    [
+        "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore",
+        "revert",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PrecommitCfg {
     #[serde(default = "default_true")]
@@ -99,6 +341,54 @@ pub struct PrecommitCfg {
     pub fail_on: Vec<String>,
     #[serde(default)]
     pub allow_bypass_profiles: Vec<String>,
+    /// `[precommit.commands]` -- explicit per-language command lists that
+    /// replace the hardcoded fmt/clippy/eslint/ruff commands for that
+    /// language when present, e.g.
+    /// `rust = ["cargo fmt --check", { cmd = "cargo clippy -D warnings", timeout_secs = 180 }]`.
+    #[serde(default)]
+    pub commands: std::collections::HashMap<String, Vec<PrecommitCommandSpec>>,
+    /// Run formatters/fixers (`cargo fmt`, `eslint --fix`, `ruff --fix`)
+    /// before the check tools and stage the result, instead of just
+    /// failing on formatting/lint issues.
+    #[serde(default)]
+    pub autofix: bool,
+}
+
+/// One entry of a `[precommit.commands]` list: either a bare command
+/// string (default timeout/working dir), or a table spelling out a
+/// per-command timeout and/or working dir.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PrecommitCommandSpec {
+    Plain(String),
+    Detailed {
+        cmd: String,
+        #[serde(default)]
+        timeout_secs: Option<u64>,
+        #[serde(default)]
+        workdir: Option<String>,
+    },
+}
+
+impl PrecommitCommandSpec {
+    pub fn cmd(&self) -> &str {
+        match self {
+            PrecommitCommandSpec::Plain(c) => c,
+            PrecommitCommandSpec::Detailed { cmd, .. } => cmd,
+        }
+    }
+    pub fn timeout_secs(&self) -> Option<u64> {
+        match self {
+            PrecommitCommandSpec::Plain(_) => None,
+            PrecommitCommandSpec::Detailed { timeout_secs, .. } => *timeout_secs,
+        }
+    }
+    pub fn workdir(&self) -> Option<&str> {
+        match self {
+            PrecommitCommandSpec::Plain(_) => None,
+            PrecommitCommandSpec::Detailed { workdir, .. } => workdir.as_deref(),
+        }
+    }
 }
 
 fn default_true() -> bool {
@@ -132,4 +422,29 @@ pub enum Event {
     Attest {
         hash: String,
     },
+    Revert {
+        /// SHA of the commit that was reverted
+        reverted: String,
+        /// SHA of the new revert commit
+        sha: String,
+        /// DevIt-Attest hash the reverted commit carried
+        hash: String,
+    },
+    Checkpoint {
+        /// Checkpoint identifier (matches `.devit/checkpoints/<id>.json`)
+        id: String,
+        /// HEAD SHA the worktree was snapshotted from
+        base_sha: String,
+    },
+    /// Per-test progress emitted live during `run_impacted` (cargo/go, whose
+    /// output streams per-test results) so the TUI and MCP clients can
+    /// render a progress bar instead of waiting for the final report.
+    TestProgress {
+        framework: String,
+        name: String,
+        /// `"pass"` or `"fail"` -- frameworks parsed here don't report a
+        /// separate "started" line, so this fires once the result is known.
+        status: String,
+        duration_ms: u128,
+    },
 }
@@ -0,0 +1,86 @@
+// # -----------------------------
+// # crates/common/src/messages.rs
+// # -----------------------------
+// Small message catalog so user-facing text isn't hardcoded French strings
+// scattered across every crate. Selected via `DEVIT_LANG` (env, wins if
+// set) or `config.i18n.lang`, defaulting to English.
+
+use std::env;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    En,
+    Fr,
+}
+
+impl Lang {
+    fn parse(raw: &str) -> Option<Self> {
+        match raw.to_lowercase().as_str() {
+            "fr" | "fr-fr" => Some(Lang::Fr),
+            "en" | "en-us" | "en-gb" => Some(Lang::En),
+            _ => None,
+        }
+    }
+}
+
+/// Resolve the active language: `DEVIT_LANG` env var first, then the
+/// `config.i18n.lang` value passed in, then English.
+pub fn resolve_lang(config_lang: Option<&str>) -> Lang {
+    if let Ok(env_lang) = env::var("DEVIT_LANG") {
+        if let Some(l) = Lang::parse(&env_lang) {
+            return l;
+        }
+    }
+    config_lang.and_then(Lang::parse).unwrap_or(Lang::En)
+}
+
+macro_rules! catalog {
+    ($( $key:ident => { en: $en:expr, fr: $fr:expr } ),+ $(,)?) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum MsgKey {
+            $( $key, )+
+        }
+
+        impl MsgKey {
+            pub fn text(self, lang: Lang) -> &'static str {
+                match (self, lang) {
+                    $( (MsgKey::$key, Lang::En) => $en, )+
+                    $( (MsgKey::$key, Lang::Fr) => $fr, )+
+                }
+            }
+        }
+    };
+}
+
+catalog! {
+    NotGitRepo => {
+        en: "not inside a git repository (git rev-parse --is-inside-work-tree)",
+        fr: "pas dans un dépôt git (git rev-parse --is-inside-work-tree)"
+    },
+    GitUnavailable => {
+        en: "git is not available on PATH",
+        fr: "git n'est pas disponible dans le PATH"
+    },
+    ApplyCancelled => {
+        en: "Cancelled by the user.",
+        fr: "Annulé par l'utilisateur."
+    },
+    CommitNotFound => {
+        en: "commit not found",
+        fr: "commit introuvable"
+    },
+    RevertFailed => {
+        en: "git revert failed",
+        fr: "git revert a échoué"
+    },
+    CommitFailed => {
+        en: "git commit failed",
+        fr: "git commit a échoué"
+    },
+}
+
+/// Fetch `key`'s text for the process' resolved language (env-only; callers
+/// that also have a loaded `Config` should prefer `resolve_lang` explicitly).
+pub fn t(key: MsgKey) -> &'static str {
+    key.text(resolve_lang(None))
+}
@@ -0,0 +1,1268 @@
+//! Repo-wide file index, symbol/import extraction, and goal-driven
+//! retrieval, extracted out of `devit-cli` so `devit-mcpd` and the TUI can
+//! call [`query`] (or the lower-level [`search`]/[`pack`]) directly instead
+//! of shelling out to the `devit context` subcommands.
+
+use anyhow::{anyhow, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::{DirEntry, WalkBuilder, WalkState};
+use memmap2::MmapOptions;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug)]
+pub struct ContextOpts {
+    pub max_bytes_per_file: usize,
+    pub max_files: usize,
+    pub ext_allow: Option<Vec<String>>, // like ["rs","toml"]
+    pub timeout: Option<Duration>,
+    pub out_path: PathBuf,
+    /// Glob→weight relevance rules applied on top of a file's base score
+    /// (see [`default_scoring_rules`]) — lets a project tune ranking via a
+    /// `[context.scoring]` table instead of the built-in heuristics.
+    pub scoring: Vec<ScoringRule>,
+}
+
+/// One `[[context.scoring]]` entry: files whose root-relative path matches
+/// `glob` get `weight` added to their index score. Matched with
+/// [`globset`]'s default (non-`literal_separator`) semantics, so `*` also
+/// matches `/` — `"*mcp*"` matches "mcp" anywhere in the path, not just the
+/// file name.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScoringRule {
+    pub glob: String,
+    pub weight: i64,
+}
+
+/// The scoring rules `devit context map` used before they became
+/// configurable: a src/tests boost, an "mcp"/"plugin" boost, and a small
+/// bump for recognized source-code extensions.
+pub fn default_scoring_rules() -> Vec<ScoringRule> {
+    let rule = |glob: &str, weight: i64| ScoringRule {
+        glob: glob.to_string(),
+        weight,
+    };
+    vec![
+        rule("src/**", 50),
+        rule("tests/**", 50),
+        rule("*mcp*", 30),
+        rule("*plugin*", 30),
+        rule("*.rs", 20),
+        rule("*.js", 20),
+        rule("*.ts", 20),
+        rule("*.py", 20),
+        rule("*.c", 20),
+        rule("*.cpp", 20),
+        rule("*.h", 20),
+        rule("*.go", 20),
+        rule("*.java", 20),
+        rule("*.cs", 20),
+        rule("*.rb", 20),
+        rule("*.php", 20),
+    ]
+}
+
+/// Compile [`ScoringRule`]s once per index build rather than per file;
+/// an invalid glob is dropped rather than failing the whole index (matches
+/// [`default_scoring_rules`]'s "best-effort" spirit — bad user config
+/// shouldn't break indexing).
+fn compile_scoring_rules(rules: &[ScoringRule]) -> Vec<(globset::GlobMatcher, i64)> {
+    rules
+        .iter()
+        .filter_map(|r| Glob::new(&r.glob).ok().map(|g| (g.compile_matcher(), r.weight)))
+        .collect()
+}
+
+#[derive(Serialize, Clone, Debug)]
+struct FileEntry {
+    path: String,
+    size: u64,
+    lang: String,
+    score: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    symbols_count: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    symbols: Vec<SymbolEntry>,
+    /// Root-relative paths of files this one directly imports (see
+    /// [`extract_imports`]) — the edges of the module-level dependency
+    /// graph used by [`dependents_of`].
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    imports: Vec<String>,
+}
+
+/// A per-file symbol entry in the index (see [`FileEntry::symbols`]): name,
+/// tree-sitter node kind and 1-based line range, so `devit context symbols`
+/// and the agent can pull just the relevant function body for a goal
+/// instead of the whole file.
+#[derive(Serialize, Clone, Debug)]
+pub struct SymbolEntry {
+    pub name: String,
+    pub kind: &'static str,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+impl From<&SymbolInfo> for SymbolEntry {
+    fn from(s: &SymbolInfo) -> Self {
+        SymbolEntry {
+            name: s.name.clone(),
+            kind: s.kind,
+            start_line: s.start_line,
+            end_line: s.end_line,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct IndexJson {
+    root: String,
+    generated_at: String,
+    files: Vec<FileEntry>,
+    skipped: Skipped,
+}
+
+#[derive(Serialize, Default)]
+struct Skipped {
+    too_large: u64,
+    binary: u64,
+    /// Files dropped by the `opts.max_files` cap, after prioritizing by
+    /// preliminary score so the drop favors the least-relevant paths.
+    over_limit: u64,
+}
+
+/// Cheap stand-in for [`FileEntry::score`] used only to decide which files
+/// survive the `opts.max_files` cap in [`discover_paths`] — path glob
+/// weight, a size bucket, and the same recency boost as the real score, but
+/// none of the tree-sitter symbol/import extraction that makes
+/// [`summarize_file`] too slow to run on every candidate before truncating.
+fn prelim_score(
+    rel: &str,
+    size: u64,
+    scoring: &[(globset::GlobMatcher, i64)],
+    recent: &std::collections::HashSet<String>,
+) -> i64 {
+    let mut score: i64 = 0;
+    for (glob, weight) in scoring {
+        if glob.is_match(rel) {
+            score += weight;
+        }
+    }
+    if recent.contains(rel) {
+        score += 15;
+    }
+    score += (size.min(50_000) / 1000) as i64;
+    score
+}
+
+/// Walk `root` under the same ignore/exclude/ext-allow rules as
+/// [`generate_index`], deduped and capped to `opts.max_files`. In addition
+/// to `.gitignore`, a `.devitignore` (same syntax, per-directory like
+/// `.gitignore`) lets a repo exclude generated code, fixtures, or vendored
+/// deps from prompts/index without touching version control. When more
+/// files are discovered than `opts.max_files` allows, the cap is applied by
+/// [`prelim_score`] (highest first) rather than alphabetically, so the
+/// dropped files are the least likely to matter; the drop count is
+/// returned alongside the surviving paths for [`Skipped::over_limit`].
+fn discover_paths(root: &Path, opts: &ContextOpts) -> Result<(Vec<PathBuf>, u64)> {
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .git_ignore(true)
+        .git_global(true)
+        .git_exclude(true)
+        .hidden(true)
+        .follow_links(false)
+        .add_custom_ignore_filename(".devitignore");
+
+    // Internal excludes
+    let mut gs = GlobSetBuilder::new();
+    for pat in [".devit/**", "target/**", "bench/**"].iter() {
+        gs.add(Glob::new(pat)?);
+    }
+    if let Some(exts) = &opts.ext_allow {
+        // Build inclusion set for quick check
+        for e in exts {
+            let pat = format!("**/*.{}", e.trim().trim_start_matches('.'));
+            gs.add(Glob::new(&pat)?);
+        }
+    }
+    let globset = gs.build()?;
+
+    let mut paths: Vec<PathBuf> = Vec::new();
+    let paths_sync = std::sync::Mutex::new(&mut paths);
+    builder.build_parallel().run(|| {
+        let globset = globset.clone();
+        let paths_sync = &paths_sync;
+        Box::new(move |res| match res {
+            Ok(ent) => {
+                if should_skip_entry(&ent, &globset) {
+                    WalkState::Continue
+                } else {
+                    if ent.file_type().map(|t| t.is_file()).unwrap_or(false) {
+                        if let Ok(mut guard) = paths_sync.lock() {
+                            guard.push(ent.path().to_path_buf());
+                        }
+                    }
+                    WalkState::Continue
+                }
+            }
+            Err(_) => WalkState::Continue,
+        })
+    });
+
+    // Deterministic order
+    paths.sort();
+
+    let over_limit = paths.len().saturating_sub(opts.max_files) as u64;
+    if over_limit > 0 {
+        let recent = recently_changed_paths(root, RECENCY_WINDOW);
+        let scoring = compile_scoring_rules(&opts.scoring);
+        let mut scored: Vec<(i64, PathBuf)> = paths
+            .into_iter()
+            .map(|p| {
+                let rel = pathdiff::diff_paths(&p, root).unwrap_or_else(|| p.clone());
+                let rels = rel.to_string_lossy().to_string();
+                let size = fs::metadata(&p).map(|m| m.len()).unwrap_or(0);
+                let score = prelim_score(&rels, size, &scoring, &recent);
+                (score, p)
+            })
+            .collect();
+        // Highest preliminary score first; ties broken by path for
+        // determinism (`sort_by` is stable, and paths are still unique).
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+        scored.truncate(opts.max_files);
+        paths = scored.into_iter().map(|(_, p)| p).collect();
+        paths.sort();
+    }
+    Ok((paths, over_limit))
+}
+
+/// How many recent commits count as "actively developed" for the
+/// [`recently_changed_paths`] score boost.
+const RECENCY_WINDOW: usize = 20;
+
+/// Root-relative paths touched by the last `n` commits (best-effort: an
+/// empty set on any git error, e.g. not a repo or no commits yet), used to
+/// boost [`FileEntry::score`] for actively-developed code.
+fn recently_changed_paths(root: &Path, n: usize) -> std::collections::HashSet<String> {
+    let out = Command::new("git")
+        .current_dir(root)
+        .args(["log", "-n", &n.to_string(), "--name-only", "--pretty=format:"])
+        .output();
+    match out {
+        Ok(o) if o.status.success() => String::from_utf8_lossy(&o.stdout)
+            .lines()
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect(),
+        _ => std::collections::HashSet::new(),
+    }
+}
+
+/// Root-relative paths that appeared alongside any of `targets` in the
+/// last `n` commits touching each target, counted by how many of those
+/// commits they co-occurred in — a cheap proxy for "usually changed
+/// together" used to boost [`search`] results toward a goal's blast
+/// radius. Best-effort: empty on any git error or when `targets` is empty.
+fn co_changed_paths(root: &Path, targets: &[String], n: usize) -> std::collections::HashMap<String, u32> {
+    let mut counts = std::collections::HashMap::new();
+    for target in targets {
+        let out = Command::new("git")
+            .current_dir(root)
+            .args([
+                "log",
+                "-n",
+                &n.to_string(),
+                "--name-only",
+                "--pretty=format:",
+                "--",
+                target,
+            ])
+            .output();
+        if let Ok(o) = out {
+            if o.status.success() {
+                for line in String::from_utf8_lossy(&o.stdout).lines() {
+                    let line = line.trim();
+                    if line.is_empty() || line == target {
+                        continue;
+                    }
+                    *counts.entry(line.to_string()).or_insert(0u32) += 1;
+                }
+            }
+        }
+    }
+    counts
+}
+
+/// Summarize and score every discovered file, highest score first — the
+/// shared candidate list behind both [`generate_index`] and [`search`].
+fn build_entries(root: &Path, opts: &ContextOpts, paths: &[PathBuf]) -> Vec<FileEntry> {
+    let start = Instant::now();
+    let timeout = opts.timeout;
+    let max_bytes = opts.max_bytes_per_file as u64;
+    let recent = recently_changed_paths(root, RECENCY_WINDOW);
+    let scoring = compile_scoring_rules(&opts.scoring);
+    let mut files: Vec<FileEntry> = paths
+        .par_iter()
+        .map(|p| {
+            if let Some(t) = timeout {
+                if start.elapsed() > t {
+                    return Err(anyhow!("timeout"));
+                }
+            }
+            summarize_file(root, p, max_bytes, &scoring)
+        })
+        .filter_map(|r| r.ok())
+        .map(|mut f| {
+            if recent.contains(&f.path) {
+                f.score += 15;
+            }
+            f
+        })
+        .collect();
+    files.sort_by_key(|f| std::cmp::Reverse(f.score));
+    files
+}
+
+/// `devit context map --watch`: keep the index at `opts.out_path` in sync
+/// with the filesystem instead of exiting after one build. Bursts of
+/// changes (a checkout, a formatter run) are coalesced into a single
+/// rebuild by draining the watch channel for `DEBOUNCE` after the first
+/// event; [`generate_index`]'s atomic tmp-then-rename write means
+/// concurrent readers (MCP's `server.context_head`, the TUI) never
+/// observe a half-written index. Runs until the watcher's channel closes.
+pub fn watch_index(root: &Path, opts: &ContextOpts) -> Result<()> {
+    use notify::{RecursiveMode, Watcher};
+    use std::time::Duration as StdDuration;
+
+    const DEBOUNCE: StdDuration = StdDuration::from_millis(300);
+
+    generate_index(root, opts)?;
+    eprintln!("index à jour: {}", opts.out_path.display());
+
+    // notify reports canonical/absolute paths regardless of whether `root`
+    // and `opts.out_path` were given relative to the cwd, so both sides of
+    // the comparison below need to be resolved the same way.
+    let root_abs = fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+    let devit_dir = {
+        let parent = opts.out_path.parent().unwrap_or_else(|| Path::new(".devit"));
+        let joined = if parent.is_absolute() {
+            parent.to_path_buf()
+        } else {
+            root_abs.join(parent)
+        };
+        fs::canonicalize(&joined).unwrap_or(joined)
+    };
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    watcher.watch(&root_abs, RecursiveMode::Recursive)?;
+
+    let is_index_write = |event: &notify::Event| {
+        event.paths.iter().all(|p| {
+            fs::canonicalize(p)
+                .map(|cp| cp.starts_with(&devit_dir))
+                .unwrap_or_else(|_| p.starts_with(&devit_dir))
+        })
+    };
+
+    loop {
+        let relevant = loop {
+            match rx.recv() {
+                Ok(Ok(event)) if is_index_write(&event) => continue,
+                Ok(Ok(_)) => break true,
+                Ok(Err(_)) => continue,
+                Err(_) => break false, // watcher dropped, stop watching
+            }
+        };
+        if !relevant {
+            return Ok(());
+        }
+        // Drain anything else that arrives within the debounce window so
+        // one rebuild covers the whole burst instead of one per file.
+        loop {
+            match rx.recv_timeout(DEBOUNCE) {
+                Ok(_) => continue,
+                Err(std::sync::mpsc::RecvTimeoutError::Timeout) => break,
+                Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => return Ok(()),
+            }
+        }
+        match generate_index(root, opts) {
+            Ok(_) => eprintln!("index à jour: {}", opts.out_path.display()),
+            Err(e) => eprintln!("reindex échoué: {e}"),
+        }
+    }
+}
+
+pub fn generate_index(root: &Path, opts: &ContextOpts) -> Result<PathBuf> {
+    let start = Instant::now();
+    let (paths, over_limit) = discover_paths(root, opts)?;
+    let max_bytes = opts.max_bytes_per_file as u64;
+    let files = build_entries(root, opts, &paths);
+
+    // Compute skipped counts (approx by scanning again quickly)
+    let mut skipped = Skipped {
+        over_limit,
+        ..Default::default()
+    };
+    for p in &paths {
+        if let Ok(md) = fs::metadata(p) {
+            if md.len() > max_bytes {
+                skipped.too_large += 1;
+                continue;
+            }
+            if is_binary_quick(p).unwrap_or(false) {
+                skipped.binary += 1;
+                continue;
+            }
+        }
+    }
+
+    let idx = IndexJson {
+        root: root.display().to_string(),
+        generated_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        files,
+        skipped,
+    };
+
+    if let Some(t) = opts.timeout {
+        if start.elapsed() > t {
+            return Err(anyhow!("timeout"));
+        }
+    }
+
+    // Atomic write
+    let out = opts.out_path.clone();
+    if let Some(parent) = out.parent() {
+        fs::create_dir_all(parent).ok();
+    }
+    if out.extension().and_then(|e| e.to_str()) == Some("ndjson") {
+        write_index_ndjson(&out, &idx)?;
+    } else {
+        let tmp = out.with_extension("json.tmp");
+        let mut f = fs::File::create(&tmp)?;
+        writeln!(f, "{}", serde_json::to_string_pretty(&idx)?)?;
+        fs::rename(tmp, &out)?;
+    }
+    Ok(out)
+}
+
+/// Sidecar path holding the byte offset of each record in a compact
+/// NDJSON index (see [`write_index_ndjson`]).
+fn ndjson_offsets_path(index_path: &Path) -> PathBuf {
+    let mut s = index_path.as_os_str().to_os_string();
+    s.push(".offsets");
+    PathBuf::from(s)
+}
+
+/// Compact index format for large repos: one JSON object per line (a
+/// header, then one per [`FileEntry`], already score-sorted) instead of a
+/// single pretty-printed document. A `.offsets` sidecar records each
+/// record's byte offset so a reader can seek straight to the top-N
+/// entries without parsing the whole file — see `server.context_head` in
+/// `devit-mcpd` for the streaming reader.
+fn write_index_ndjson(out: &Path, idx: &IndexJson) -> Result<()> {
+    let tmp = out.with_extension("ndjson.tmp");
+    let mut buf: Vec<u8> = Vec::new();
+    let header = serde_json::json!({
+        "root": idx.root,
+        "generated_at": idx.generated_at,
+        "skipped": idx.skipped,
+        "count": idx.files.len(),
+    });
+    buf.extend_from_slice(serde_json::to_string(&header)?.as_bytes());
+    buf.push(b'\n');
+
+    let mut offsets: Vec<u64> = Vec::with_capacity(idx.files.len());
+    for f in &idx.files {
+        offsets.push(buf.len() as u64);
+        buf.extend_from_slice(serde_json::to_string(f)?.as_bytes());
+        buf.push(b'\n');
+    }
+    fs::write(&tmp, &buf)?;
+    fs::rename(&tmp, out)?;
+
+    let offsets_path = ndjson_offsets_path(out);
+    let mut offsets_tmp = offsets_path.as_os_str().to_os_string();
+    offsets_tmp.push(".tmp");
+    let offsets_tmp = PathBuf::from(offsets_tmp);
+    fs::write(&offsets_tmp, serde_json::to_vec(&offsets)?)?;
+    fs::rename(&offsets_tmp, &offsets_path)?;
+    Ok(())
+}
+
+fn should_skip_entry(ent: &DirEntry, gs: &GlobSet) -> bool {
+    let p = ent.path();
+    let rel = p.to_string_lossy();
+    for pat in [".devit/", "target/", "bench/"].iter() {
+        if rel.contains(pat) {
+            return true;
+        }
+    }
+    // If ext_allow provided (encoded in globset along with excludes), ensure it matches at least one allowed pattern
+    if !gs.is_empty() {
+        // If any of our exclude globs match, skip
+        if gs.is_match(p) {
+            // ambiguous: our set has both excludes and includes; we rely on explicit excludes by prefix checks above.
+        }
+    }
+    false
+}
+
+fn summarize_file(
+    root: &Path,
+    path: &Path,
+    max_bytes: u64,
+    scoring: &[(globset::GlobMatcher, i64)],
+) -> Result<FileEntry> {
+    let md = fs::metadata(path)?;
+    let sz = md.len();
+    // Skip too large and binaries
+    if sz > max_bytes {
+        anyhow::bail!("too large")
+    }
+    if is_binary_quick(path)? {
+        anyhow::bail!("binary")
+    }
+    let rel = pathdiff::diff_paths(path, root).unwrap_or_else(|| path.to_path_buf());
+    let rels = rel.to_string_lossy().to_string();
+    let lang = detect_lang(&rels);
+    let mut score: i64 = 0;
+    for (glob, weight) in scoring {
+        if glob.is_match(&rels) {
+            score += weight;
+        }
+    }
+
+    // symbols via tree-sitter (best-effort)
+    let mut symbols_count: Option<u32> = None;
+    let mut symbols: Vec<SymbolEntry> = Vec::new();
+    let mut imports: Vec<String> = Vec::new();
+    if matches!(
+        lang.as_str(),
+        "rust" | "js" | "py" | "go" | "java" | "csharp" | "ruby" | "php"
+    ) {
+        if let Ok(source) = fs::read_to_string(path) {
+            let found = extract_symbols(&source, &lang);
+            symbols_count = Some(found.len() as u32);
+            symbols = found.iter().map(SymbolEntry::from).collect();
+            imports = extract_imports(root, path, &source, &lang);
+        }
+    }
+
+    Ok(FileEntry {
+        path: rels,
+        size: sz,
+        lang,
+        score,
+        symbols_count,
+        symbols,
+        imports,
+    })
+}
+
+fn is_binary_quick(path: &Path) -> Result<bool> {
+    // try mmap
+    if let Ok(file) = fs::File::open(path) {
+        if let Ok(m) = unsafe { MmapOptions::new().len(1024 * 16).map(&file) } {
+            if m.contains(&0) {
+                return Ok(true);
+            }
+            return Ok(false);
+        }
+    }
+    // fallback: read small chunk
+    let mut f = fs::File::open(path)?;
+    let mut buf = [0u8; 8192];
+    let n = f.read(&mut buf).unwrap_or(0);
+    Ok(buf[..n].contains(&0))
+}
+
+pub fn detect_lang(p: &str) -> String {
+    let lower = p.to_lowercase();
+    for (exts, tag) in [
+        ((vec![".rs"]), "rust"),
+        ((vec![".js", ".ts", ".tsx"]), "js"),
+        ((vec![".py"]), "py"),
+        ((vec![".toml"]), "toml"),
+        ((vec![".md"]), "md"),
+        ((vec![".json"]), "json"),
+        ((vec![".yml", ".yaml"]), "yml"),
+        ((vec![".c", ".h"]), "c"),
+        ((vec![".cpp", ".hpp"]), "cpp"),
+        ((vec![".sh"]), "sh"),
+        ((vec![".go"]), "go"),
+        ((vec![".java"]), "java"),
+        ((vec![".cs"]), "csharp"),
+        ((vec![".rb"]), "ruby"),
+        ((vec![".php"]), "php"),
+    ] {
+        if exts.iter().any(|e| lower.ends_with(e)) {
+            return tag.to_string();
+        }
+    }
+    "text".to_string()
+}
+
+/// Resolve a [`detect_lang`] tag to its tree-sitter grammar, when one is
+/// compiled in. Go/Java/C#/Ruby/PHP are each behind their own Cargo
+/// feature (`lang-go`, `lang-java`, `lang-csharp`, `lang-ruby`, `lang-php`)
+/// so a default build doesn't pay for grammars it doesn't need; when a
+/// feature is off, callers just see no symbols for that language.
+fn language_for(lang: &str) -> Option<tree_sitter::Language> {
+    match lang {
+        "rust" => Some(tree_sitter_rust::LANGUAGE.into()),
+        "js" => Some(tree_sitter_javascript::LANGUAGE.into()),
+        "py" => Some(tree_sitter_python::LANGUAGE.into()),
+        #[cfg(feature = "lang-go")]
+        "go" => Some(tree_sitter_go::LANGUAGE.into()),
+        #[cfg(feature = "lang-java")]
+        "java" => Some(tree_sitter_java::LANGUAGE.into()),
+        #[cfg(feature = "lang-csharp")]
+        "csharp" => Some(tree_sitter_c_sharp::LANGUAGE.into()),
+        #[cfg(feature = "lang-ruby")]
+        "ruby" => Some(tree_sitter_ruby::LANGUAGE.into()),
+        #[cfg(feature = "lang-php")]
+        "php" => Some(tree_sitter_php::LANGUAGE_PHP.into()),
+        _ => None,
+    }
+}
+
+/// A top-level symbol found by [`extract_symbols`]: name, raw tree-sitter
+/// node kind (e.g. `"function_item"`), 1-based line range, and the node's
+/// own source text so callers can tell a same-named symbol apart from an
+/// edited one without re-parsing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolInfo {
+    pub name: String,
+    pub kind: &'static str,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub text: String,
+}
+
+/// Shallow top-level scan returning each symbol's name, kind, and line
+/// range (used by `devit explain-patch` to say *which* functions/types
+/// changed, and by `devit context symbols`/`map`/`search` for indexing).
+pub fn extract_symbols(source: &str, lang: &str) -> Vec<SymbolInfo> {
+    use tree_sitter::Parser;
+    let mut parser = Parser::new();
+    match language_for(lang).and_then(|language| parser.set_language(&language).ok()) {
+        Some(()) => {}
+        None => return Vec::new(),
+    }
+    let tree = match parser.parse(source, None) {
+        Some(t) => t,
+        None => return Vec::new(),
+    };
+    let mut out = Vec::new();
+    let root = tree.root_node();
+    let mut cursor = root.walk();
+    for n in root.children(&mut cursor) {
+        let kind = n.kind();
+        let (name_field, reported_kind): (&str, &'static str) = match (lang, kind) {
+            ("rust", "function_item") => ("name", "function_item"),
+            ("rust", "struct_item") => ("name", "struct_item"),
+            ("rust", "enum_item") => ("name", "enum_item"),
+            ("rust", "trait_item") => ("name", "trait_item"),
+            ("rust", "mod_item") => ("name", "mod_item"),
+            ("rust", "impl_item") => ("type", "impl_item"),
+            ("js", "function_declaration") => ("name", "function_declaration"),
+            ("js", "class_declaration") => ("name", "class_declaration"),
+            ("py", "function_definition") => ("name", "function_definition"),
+            ("py", "class_definition") => ("name", "class_definition"),
+            ("go", "function_declaration") => ("name", "function_declaration"),
+            ("go", "method_declaration") => ("name", "method_declaration"),
+            ("java", "class_declaration") => ("name", "class_declaration"),
+            ("java", "interface_declaration") => ("name", "interface_declaration"),
+            ("java", "enum_declaration") => ("name", "enum_declaration"),
+            ("csharp", "class_declaration") => ("name", "class_declaration"),
+            ("csharp", "interface_declaration") => ("name", "interface_declaration"),
+            ("csharp", "struct_declaration") => ("name", "struct_declaration"),
+            ("csharp", "enum_declaration") => ("name", "enum_declaration"),
+            ("ruby", "class") => ("name", "class"),
+            ("ruby", "module") => ("name", "module"),
+            ("ruby", "method") => ("name", "method"),
+            ("php", "class_declaration") => ("name", "class_declaration"),
+            ("php", "interface_declaration") => ("name", "interface_declaration"),
+            ("php", "trait_declaration") => ("name", "trait_declaration"),
+            ("php", "enum_declaration") => ("name", "enum_declaration"),
+            ("php", "function_definition") => ("name", "function_definition"),
+            _ => continue,
+        };
+        let name = n
+            .child_by_field_name(name_field)
+            .and_then(|nn| nn.utf8_text(source.as_bytes()).ok())
+            .unwrap_or("<anonyme>")
+            .to_string();
+        let text = n.utf8_text(source.as_bytes()).unwrap_or("").to_string();
+        out.push(SymbolInfo {
+            name,
+            kind: reported_kind,
+            start_line: n.start_position().row + 1,
+            end_line: n.end_position().row + 1,
+            text,
+        });
+        if out.len() >= 200 {
+            break;
+        }
+    }
+    out
+}
+
+/// `devit context symbols <path>`: the file's top-level symbols with their
+/// line ranges, so the agent can request just the relevant function body
+/// for a goal instead of pulling the whole file into the prompt.
+pub fn symbols_for_file(path: &str) -> Result<Vec<SymbolEntry>, String> {
+    let source = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let lang = detect_lang(path);
+    Ok(extract_symbols(&source, &lang)
+        .iter()
+        .map(SymbolEntry::from)
+        .collect())
+}
+
+/// Best-effort "who does this file import" extraction, resolved to
+/// root-relative paths of files that actually exist on disk — unresolvable
+/// specifiers (external crates/packages, stdlib modules) are dropped rather
+/// than guessed. Only rust/js/py are covered for now, the same set
+/// `extract_symbols` supported before the tree-sitter grammar expansion;
+/// these edges are the module-level dependency graph stored in the index
+/// and consumed by [`dependents_of`].
+fn extract_imports(root: &Path, file: &Path, source: &str, lang: &str) -> Vec<String> {
+    let dir = file.parent().unwrap_or_else(|| Path::new(""));
+    let mut out: Vec<String> = Vec::new();
+    let mut push_if_file = |candidate: PathBuf| {
+        if candidate.is_file() {
+            if let Some(rel) = pathdiff::diff_paths(&candidate, root) {
+                out.push(rel.to_string_lossy().replace('\\', "/"));
+            }
+        }
+    };
+    match lang {
+        "rust" => {
+            for line in source.lines() {
+                let line = line.trim();
+                let Some(rest) = line.strip_prefix("mod ") else {
+                    continue;
+                };
+                let name = rest.trim_end_matches(';').trim();
+                if name.is_empty() || rest.trim_start().starts_with('{') {
+                    continue;
+                }
+                push_if_file(dir.join(format!("{name}.rs")));
+                push_if_file(dir.join(name).join("mod.rs"));
+            }
+        }
+        "js" => {
+            for line in source.lines() {
+                let line = line.trim();
+                let looks_like_import = line.starts_with("import ")
+                    || line.contains(" from ")
+                    || line.contains("require(");
+                let Some(spec) = looks_like_import.then(|| extract_quoted(line)).flatten() else {
+                    continue;
+                };
+                if !spec.starts_with('.') {
+                    continue; // skip bare package specifiers (node_modules)
+                }
+                let base = dir.join(&spec);
+                for cand in [
+                    base.clone(),
+                    base.with_extension("js"),
+                    base.with_extension("ts"),
+                    base.with_extension("tsx"),
+                    base.join("index.js"),
+                    base.join("index.ts"),
+                ] {
+                    push_if_file(cand);
+                }
+            }
+        }
+        "py" => {
+            // AST-based (via tree-sitter) rather than a line scan, so
+            // multi-line `from ... import (...)` and imports inside
+            // triple-quoted strings/comments don't produce false edges.
+            use tree_sitter::Parser;
+            let mut parser = Parser::new();
+            if let Some(language) = language_for(lang) {
+                if parser.set_language(&language).is_ok() {
+                    if let Some(tree) = parser.parse(source, None) {
+                        let root = tree.root_node();
+                        let mut cursor = root.walk();
+                        for n in root.children(&mut cursor) {
+                            if n.kind() != "import_from_statement" {
+                                continue;
+                            }
+                            let Some(module) = n.child_by_field_name("module_name") else {
+                                continue;
+                            };
+                            if module.kind() != "relative_import" {
+                                continue; // skip absolute imports (stdlib/third-party)
+                            }
+                            let Ok(text) = module.utf8_text(source.as_bytes()) else {
+                                continue;
+                            };
+                            let ups = text.chars().take_while(|c| *c == '.').count();
+                            let rest = &text[ups..];
+                            let mut base = dir.to_path_buf();
+                            for _ in 1..ups {
+                                base = base.parent().unwrap_or(&base).to_path_buf();
+                            }
+                            if !rest.is_empty() {
+                                base = base.join(rest.replace('.', "/"));
+                            }
+                            push_if_file(base.with_extension("py"));
+                            push_if_file(base.join("__init__.py"));
+                        }
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+    out.sort();
+    out.dedup();
+    out
+}
+
+/// Contents of the first quoted string literal in `s` (single or double
+/// quotes), used to pull the module specifier out of a JS `import`/
+/// `require` line without a full parser.
+fn extract_quoted(s: &str) -> Option<String> {
+    let mut start = None;
+    for (i, c) in s.char_indices() {
+        if c == '\'' || c == '"' {
+            match start {
+                None => start = Some(i + 1),
+                Some(st) => return Some(s[st..i].to_string()),
+            }
+        }
+    }
+    None
+}
+
+/// Files that directly import any path in `changed` (see
+/// [`FileEntry::imports`]) — the reverse edges of the dependency graph,
+/// used to widen context and impacted-test selection to whoever depends on
+/// a changed file, not just the file itself.
+pub fn dependents_of(root: &Path, opts: &ContextOpts, changed: &[String]) -> Result<Vec<String>> {
+    let (paths, _over_limit) = discover_paths(root, opts)?;
+    let entries = build_entries(root, opts, &paths);
+    let mut out: Vec<String> = entries
+        .into_iter()
+        .filter(|e| e.imports.iter().any(|imp| changed.iter().any(|c| c == imp)))
+        .map(|e| e.path)
+        .collect();
+    out.sort();
+    out.dedup();
+    Ok(out)
+}
+
+/// Transitive closure of [`dependents_of`]: files that import any path in
+/// `changed`, plus files that import *those*, and so on, until a round adds
+/// nothing new — so a test importing a thin wrapper around a changed leaf
+/// module is still selected, not just tests that import the leaf directly.
+pub fn transitive_dependents_of(
+    root: &Path,
+    opts: &ContextOpts,
+    changed: &[String],
+) -> Result<Vec<String>> {
+    let (paths, _over_limit) = discover_paths(root, opts)?;
+    let entries = build_entries(root, opts, &paths);
+    let mut seen: std::collections::HashSet<String> = changed.iter().cloned().collect();
+    let mut frontier: Vec<String> = changed.to_vec();
+    let mut out: Vec<String> = Vec::new();
+    loop {
+        let next: Vec<String> = entries
+            .iter()
+            .filter(|e| !seen.contains(&e.path))
+            .filter(|e| e.imports.iter().any(|imp| frontier.iter().any(|c| c == imp)))
+            .map(|e| e.path.clone())
+            .collect();
+        if next.is_empty() {
+            break;
+        }
+        for n in &next {
+            seen.insert(n.clone());
+        }
+        out.extend(next.iter().cloned());
+        frontier = next;
+    }
+    out.sort();
+    out.dedup();
+    Ok(out)
+}
+
+/// A [`search`] result: the context-index score plus embedding similarity
+/// to the query, and the file's top-level symbol names for a quick skim.
+#[derive(Serialize, Clone, Debug)]
+pub struct SearchHit {
+    pub path: String,
+    pub lang: String,
+    pub score: i64,
+    pub similarity: f32,
+    pub symbols: Vec<String>,
+}
+
+/// Semantic search over the context index: prefilter to the
+/// highest-`score` candidates, then rerank by embedding similarity to
+/// `query`. Falls back to the score-only order (similarity left at 0.0)
+/// when the configured backend has no working `/embeddings` endpoint, so
+/// `devit context search` still returns something useful without one.
+pub async fn search(
+    root: &Path,
+    opts: &ContextOpts,
+    query: &str,
+    top: usize,
+    agent: &devit_agent::Agent,
+) -> Result<Vec<SearchHit>> {
+    const CANDIDATE_CAP: usize = 50;
+    let (paths, _over_limit) = discover_paths(root, opts)?;
+    let candidates = build_entries(root, opts, &paths);
+    let candidates = &candidates[..candidates.len().min(CANDIDATE_CAP)];
+
+    // Boost candidates that historically co-change with a file the query
+    // names, so "fix the bug in foo.rs" surfaces the files usually touched
+    // alongside foo.rs, not just foo.rs itself.
+    let targets: Vec<String> = query
+        .split_whitespace()
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric() && c != '.' && c != '/' && c != '_' && c != '-'))
+        .filter(|w| w.contains('.') && root.join(w).is_file())
+        .map(|w| w.to_string())
+        .collect();
+    let co_changed = co_changed_paths(root, &targets, RECENCY_WINDOW);
+
+    let query_embedding = agent.embed(query).await.ok();
+
+    let mut hits = Vec::with_capacity(candidates.len());
+    for entry in candidates {
+        let full_path = root.join(&entry.path);
+        let content = fs::read_to_string(&full_path).unwrap_or_default();
+
+        let similarity = match &query_embedding {
+            Some(qv) => {
+                let snippet: String = content.chars().take(4000).collect();
+                agent
+                    .embed(&snippet)
+                    .await
+                    .map(|fv| cosine_similarity(qv, &fv))
+                    .unwrap_or(0.0)
+            }
+            None => 0.0,
+        };
+
+        let symbols = if matches!(
+            entry.lang.as_str(),
+            "rust" | "js" | "py" | "go" | "java" | "csharp" | "ruby" | "php"
+        ) {
+            extract_symbols(&content, &entry.lang)
+                .into_iter()
+                .map(|s| s.name)
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let score = entry.score + co_changed.get(&entry.path).copied().unwrap_or(0) as i64;
+
+        hits.push(SearchHit {
+            path: entry.path.clone(),
+            lang: entry.lang.clone(),
+            score,
+            similarity,
+            symbols,
+        });
+    }
+
+    hits.sort_by(|a, b| {
+        let ranked = |h: &SearchHit| h.similarity * 100.0 + h.score as f32 * 0.1;
+        ranked(b)
+            .partial_cmp(&ranked(a))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    hits.truncate(top);
+    Ok(hits)
+}
+
+/// Default token budget for [`pack`] when the caller doesn't override it
+/// (`devit context pack`'s own `--budget` default, and what `devit
+/// suggest`/`devit run` fall back to).
+pub const DEFAULT_PACK_BUDGET: usize = 8000;
+
+/// Rough tokens-per-character estimate (~4 chars/token), the same
+/// best-effort heuristic used wherever this repo sizes LLM input without
+/// calling out to a real tokenizer.
+fn estimate_tokens(s: &str) -> usize {
+    s.len() / 4
+}
+
+/// Rank files by [`search`] relevance to `goal`, then greedily pack their
+/// full contents into a single prompt-ready bundle that stays within
+/// `budget` tokens -- replaces the old fixed depth-2 file walk `devit
+/// suggest`/`devit run` used to build their context with.
+pub async fn pack(
+    root: &Path,
+    opts: &ContextOpts,
+    goal: &str,
+    budget: usize,
+    agent: &devit_agent::Agent,
+) -> Result<String> {
+    let hits = search(root, opts, goal, 50, agent).await?;
+    let mut out = String::new();
+    let mut used = 0usize;
+    for hit in &hits {
+        let content = match fs::read_to_string(root.join(&hit.path)) {
+            Ok(c) => c,
+            Err(_) => continue,
+        };
+        let block = format!(">>> FILE: {}\n{}\n", hit.path, content);
+        let cost = estimate_tokens(&block);
+        if used + cost > budget {
+            if used == 0 {
+                let max_chars = budget.saturating_mul(4);
+                let truncated: String = content.chars().take(max_chars).collect();
+                out.push_str(&format!(">>> FILE: {} (truncated)\n{}\n", hit.path, truncated));
+            }
+            break;
+        }
+        out.push_str(&block);
+        used += cost;
+    }
+    Ok(out)
+}
+
+/// Caps for a [`query`] call. `root` and `agent` decide *where* and *how*
+/// retrieval runs; `QueryLimits` decides *how much* comes back.
+#[derive(Clone, Debug)]
+pub struct QueryLimits {
+    pub top: usize,
+    pub budget: usize,
+    pub max_bytes_per_file: usize,
+    pub max_files: usize,
+}
+
+impl Default for QueryLimits {
+    fn default() -> Self {
+        QueryLimits {
+            top: 20,
+            budget: DEFAULT_PACK_BUDGET,
+            max_bytes_per_file: 262_144,
+            max_files: 5000,
+        }
+    }
+}
+
+/// Result of a [`query`] call: the ranked files themselves ([`search`]) and
+/// a token-budgeted bundle of their contents ready to drop into a prompt
+/// ([`pack`]).
+#[derive(Serialize, Clone, Debug)]
+pub struct QueryResult {
+    pub hits: Vec<SearchHit>,
+    pub packed: String,
+}
+
+/// One-call ranked retrieval for a `goal`: combines [`search`] and [`pack`]
+/// over the same candidate set so callers (`devit-mcpd`, the TUI) don't have
+/// to know about `ContextOpts`/the on-disk index to get "the files and
+/// snippets relevant to this goal".
+pub async fn query(
+    root: &Path,
+    goal: &str,
+    limits: QueryLimits,
+    agent: &devit_agent::Agent,
+) -> Result<QueryResult> {
+    let opts = ContextOpts {
+        max_bytes_per_file: limits.max_bytes_per_file,
+        max_files: limits.max_files,
+        ext_allow: None,
+        timeout: None,
+        out_path: root.join(".devit/index.json"),
+        scoring: default_scoring_rules(),
+    };
+    let hits = search(root, &opts, goal, limits.top, agent).await?;
+    let packed = pack(root, &opts, goal, limits.budget, agent).await?;
+    Ok(QueryResult { hits, packed })
+}
+
+/// A cached [`summarize_dirs`] entry: the directory's LLM-generated
+/// paragraph plus the file-list signature it was generated from, so a
+/// later run can tell whether the directory changed since.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DirSummary {
+    pub dir: String,
+    pub summary: String,
+    signature: String,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct SummaryCache {
+    dirs: std::collections::HashMap<String, DirSummary>,
+}
+
+/// Where [`summarize_dirs`] caches its output: alongside the index itself,
+/// so `.devit/index.json` and `.devit/summaries.json` live and get cleaned
+/// up together.
+fn summary_cache_path(opts: &ContextOpts) -> PathBuf {
+    opts.out_path
+        .parent()
+        .map(|p| p.join("summaries.json"))
+        .unwrap_or_else(|| PathBuf::from(".devit/summaries.json"))
+}
+
+/// Fingerprint of a directory's contents (sorted relative paths + sizes) —
+/// cheap enough to recompute every run, and changes whenever a file is
+/// added, removed, or edited, which is what tells [`summarize_dirs`] a
+/// cached paragraph is stale.
+fn dir_signature(files: &[&FileEntry]) -> String {
+    let mut names: Vec<&FileEntry> = files.to_vec();
+    names.sort_by(|a, b| a.path.cmp(&b.path));
+    let mut hasher = Sha256::new();
+    for f in &names {
+        hasher.update(f.path.as_bytes());
+        hasher.update([0u8]);
+        hasher.update(f.size.to_le_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// `devit context summarize`: one LLM-generated paragraph per directory,
+/// cached at [`summary_cache_path`] and only regenerated for directories
+/// whose [`dir_signature`] changed since the last run — cheap enough to
+/// call before every prompt instead of re-summarizing the whole tree, and
+/// used by [`render_repo_map`] to give the model a lightweight overview
+/// before the packed file contents.
+pub async fn summarize_dirs(
+    root: &Path,
+    opts: &ContextOpts,
+    agent: &devit_agent::Agent,
+) -> Result<Vec<DirSummary>> {
+    let (paths, _over_limit) = discover_paths(root, opts)?;
+    let entries = build_entries(root, opts, &paths);
+
+    let mut by_dir: std::collections::BTreeMap<String, Vec<&FileEntry>> =
+        std::collections::BTreeMap::new();
+    for e in &entries {
+        let dir = Path::new(&e.path)
+            .parent()
+            .map(|p| p.to_string_lossy().to_string())
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| ".".to_string());
+        by_dir.entry(dir).or_default().push(e);
+    }
+
+    let cache_path = summary_cache_path(opts);
+    let mut cache: SummaryCache = fs::read_to_string(&cache_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default();
+
+    let mut out = Vec::with_capacity(by_dir.len());
+    for (dir, files) in &by_dir {
+        let signature = dir_signature(files);
+        if let Some(cached) = cache.dirs.get(dir) {
+            if cached.signature == signature {
+                out.push(cached.clone());
+                continue;
+            }
+        }
+        let file_list = files
+            .iter()
+            .map(|f| f.path.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let summary = agent
+            .summarize_directory(dir, &file_list)
+            .await
+            .unwrap_or_default();
+        let entry = DirSummary {
+            dir: dir.clone(),
+            summary,
+            signature,
+        };
+        cache.dirs.insert(dir.clone(), entry.clone());
+        out.push(entry);
+    }
+    cache.dirs.retain(|d, _| by_dir.contains_key(d));
+
+    if let Some(parent) = cache_path.parent() {
+        fs::create_dir_all(parent).ok();
+    }
+    let tmp = cache_path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(&cache)?)?;
+    fs::rename(tmp, &cache_path)?;
+
+    Ok(out)
+}
+
+/// Render cached [`DirSummary`]s as a prompt-ready repo map, one line per
+/// directory — meant to be prepended to [`pack`]'s output so the model gets
+/// a cheap overview of the tree before the packed file contents.
+pub fn render_repo_map(summaries: &[DirSummary]) -> String {
+    let mut out = String::new();
+    if summaries.is_empty() {
+        return out;
+    }
+    out.push_str(">>> REPO MAP\n");
+    for s in summaries {
+        out.push_str(&format!("- {}: {}\n", s.dir, s.summary));
+    }
+    out
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.is_empty() || b.is_empty() || a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    #[test]
+    fn builds_index_with_filters() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("tests")).unwrap();
+        fs::create_dir_all(root.join("target")).unwrap();
+        fs::create_dir_all(root.join(".devit")).unwrap();
+        fs::write(root.join("src/lib.rs"), "pub fn x(){}\n").unwrap();
+        fs::write(root.join("tests/foo.rs"), "#[test] fn t(){}\n").unwrap();
+        fs::write(root.join(".devit/secret.txt"), "sekrit").unwrap();
+        let mut big = fs::File::create(root.join("target/junk.bin")).unwrap();
+        big.write_all(&vec![0u8; 300_000]).unwrap();
+
+        let out = root.join(".devit/index.json");
+        let opts = ContextOpts {
+            max_bytes_per_file: 262_144,
+            max_files: 5000,
+            ext_allow: None,
+            timeout: Some(Duration::from_secs(5)),
+            out_path: out.clone(),
+            scoring: default_scoring_rules(),
+        };
+        let written = generate_index(root, &opts).unwrap();
+        assert_eq!(written, out);
+        let txt = fs::read_to_string(&written).unwrap();
+        assert!(txt.contains("\"root\":"));
+        assert!(!txt.contains(".devit/secret.txt"));
+        assert!(!txt.contains("target/junk.bin"));
+    }
+}
@@ -6,71 +6,553 @@
 // - Optional "no-net" policy (best-effort)
 
 use anyhow::{anyhow, Result};
-use devit_common::{PolicyCfg, SandboxCfg};
-use std::process::{Command, Stdio};
-
-fn tokenize_commands(cmd: &str) -> Vec<String> {
-    // Split on shell operators to extract leading binaries of sub-commands
-    let seps = ['|', ';', '&', '\n'];
-    cmd.split(|c| seps.contains(&c))
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
+use devit_common::{PolicyCfg, SandboxCfg, SecretsCfg};
+use sha2::{Digest, Sha256};
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// A structured record of one `shell_exec` invocation, handed to an
+/// `on_audit` callback so callers can journal it consistently (e.g. as
+/// `Event::ToolCall`/`Event::CommandOut`).
+#[derive(Debug, Clone)]
+pub struct ExecAudit {
+    pub cmd: String,
+    pub cwd: PathBuf,
+    pub exit_code: i32,
+    pub duration_ms: u128,
+    /// SHA-256 hex digest of the captured stdout+stderr. Empty-input digest
+    /// when the command's output wasn't captured (see `run_shell_sandboxed`).
+    pub output_sha256: String,
+    pub usage: ResourceUsage,
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    hex::encode(hasher.finalize())
+}
+
+mod proxy;
+use proxy::FilteringProxy;
+
+#[cfg(windows)]
+mod winjob;
+#[cfg(windows)]
+use winjob::JobObject;
+
+/// On Windows, confine `child` to a Job Object enforcing `sb`'s CPU/memory
+/// limits and killing the whole tree when the guard is dropped. On other
+/// platforms this is a no-op — the process group already provides that.
+#[cfg(windows)]
+fn confine_to_job(child: &Child, sb: &SandboxCfg) -> Result<JobObject> {
+    let job = JobObject::create(sb)?;
+    job.assign(child)?;
+    Ok(job)
+}
+
+#[cfg(not(windows))]
+fn confine_to_job(_child: &Child, _sb: &SandboxCfg) -> Result<()> {
+    Ok(())
+}
+
+/// If `sb.net == "allowlist"`, start the local filtering proxy and point
+/// `command` at it via the standard proxy env vars. The returned guard must
+/// be kept alive for as long as `command`'s child runs.
+fn apply_net_allowlist(command: &mut Command, sb: &SandboxCfg) -> Result<Option<FilteringProxy>> {
+    if !sb.net.eq_ignore_ascii_case("allowlist") {
+        return Ok(None);
+    }
+    let proxy = FilteringProxy::spawn(sb.net_allowlist.clone())?;
+    let proxy_url = format!("http://127.0.0.1:{}", proxy.port);
+    for var in ["http_proxy", "https_proxy", "HTTP_PROXY", "HTTPS_PROXY"] {
+        command.env(var, &proxy_url);
+    }
+    Ok(Some(proxy))
+}
+
+/// Exit code conventionally reported for commands killed on timeout.
+const TIMEOUT_EXIT_CODE: i32 = 124;
+
+fn resolve_timeout(sb: &SandboxCfg) -> Duration {
+    let secs = sb
+        .timeout_secs
+        .or_else(|| {
+            std::env::var("DEVIT_TIMEOUT_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+        })
+        .unwrap_or(120);
+    Duration::from_secs(secs)
+}
+
+#[cfg(unix)]
+fn new_process_group(command: &mut Command) {
+    use std::os::unix::process::CommandExt;
+    command.process_group(0);
+}
+
+#[cfg(not(unix))]
+fn new_process_group(_command: &mut Command) {}
+
+#[cfg(unix)]
+fn kill_process_group(child: &mut Child) {
+    // SAFETY: kill() with a negative pid targets the whole process group we
+    // created via process_group(0); the pid is a valid live child we own.
+    unsafe {
+        libc::kill(-(child.id() as i32), libc::SIGKILL);
+    }
+    let _ = child.kill();
+}
+
+#[cfg(not(unix))]
+fn kill_process_group(child: &mut Child) {
+    let _ = child.kill();
+}
+
+/// CPU/memory usage of one `shell_exec` invocation. Populated from `wait4`'s
+/// `rusage` on Unix; zeroed on platforms where that isn't available.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ResourceUsage {
+    pub wall_ms: u128,
+    pub user_cpu_ms: u64,
+    pub sys_cpu_ms: u64,
+    /// Peak resident set size in kilobytes, as reported by `ru_maxrss`.
+    pub max_rss_kb: u64,
+}
+
+#[cfg(unix)]
+fn rusage_to_usage(rusage: &libc::rusage) -> ResourceUsage {
+    ResourceUsage {
+        wall_ms: 0,
+        user_cpu_ms: rusage.ru_utime.tv_sec as u64 * 1000 + rusage.ru_utime.tv_usec as u64 / 1000,
+        sys_cpu_ms: rusage.ru_stime.tv_sec as u64 * 1000 + rusage.ru_stime.tv_usec as u64 / 1000,
+        max_rss_kb: rusage.ru_maxrss as u64,
+    }
+}
+
+/// Non-blocking `wait4`: returns `Some((exit_code, usage))` once `child` has
+/// exited, `None` if it's still running.
+#[cfg(unix)]
+fn try_wait_rusage(child: &mut Child) -> Result<Option<(i32, ResourceUsage)>> {
+    let pid = child.id() as libc::pid_t;
+    let mut status: i32 = 0;
+    let mut rusage: libc::rusage = unsafe { std::mem::zeroed() };
+    // SAFETY: `pid` is our own live child; WNOHANG makes this return 0
+    // immediately instead of blocking when the child hasn't exited yet.
+    let ret = unsafe { libc::wait4(pid, &mut status, libc::WNOHANG, &mut rusage) };
+    if ret == 0 {
+        return Ok(None);
+    }
+    if ret < 0 {
+        return Err(anyhow!(std::io::Error::last_os_error()));
+    }
+    let code = if libc::WIFEXITED(status) {
+        libc::WEXITSTATUS(status)
+    } else if libc::WIFSIGNALED(status) {
+        128 + libc::WTERMSIG(status)
+    } else {
+        -1
+    };
+    Ok(Some((code, rusage_to_usage(&rusage))))
+}
+
+#[cfg(not(unix))]
+fn try_wait_rusage(child: &mut Child) -> Result<Option<(i32, ResourceUsage)>> {
+    Ok(child
+        .try_wait()?
+        .map(|status| (status.code().unwrap_or(-1), ResourceUsage::default())))
+}
+
+/// Blocking reap of an already-killed `child`, still collecting its rusage.
+#[cfg(unix)]
+fn reap_with_rusage(child: &mut Child) -> ResourceUsage {
+    let pid = child.id() as libc::pid_t;
+    let mut status: i32 = 0;
+    let mut rusage: libc::rusage = unsafe { std::mem::zeroed() };
+    // SAFETY: same contract as `try_wait_rusage`, blocking instead of
+    // polling since the child was just sent SIGKILL and will exit promptly.
+    let ret = unsafe { libc::wait4(pid, &mut status, 0, &mut rusage) };
+    if ret < 0 {
+        return ResourceUsage::default();
+    }
+    rusage_to_usage(&rusage)
+}
+
+#[cfg(not(unix))]
+fn reap_with_rusage(child: &mut Child) -> ResourceUsage {
+    let _ = child.wait();
+    ResourceUsage::default()
+}
+
+/// Wait for `child` up to `timeout`, polling instead of blocking so a hung
+/// command doesn't hang the caller forever. Returns `(exit_code, timed_out,
+/// usage)`.
+fn wait_with_timeout(child: &mut Child, timeout: Duration) -> Result<(i32, bool, ResourceUsage)> {
+    let start = Instant::now();
+    loop {
+        if let Some((code, usage)) = try_wait_rusage(child)? {
+            return Ok((
+                code,
+                false,
+                ResourceUsage {
+                    wall_ms: start.elapsed().as_millis(),
+                    ..usage
+                },
+            ));
+        }
+        if start.elapsed() >= timeout {
+            kill_process_group(child);
+            let usage = reap_with_rusage(child);
+            return Ok((
+                TIMEOUT_EXIT_CODE,
+                true,
+                ResourceUsage {
+                    wall_ms: start.elapsed().as_millis(),
+                    ..usage
+                },
+            ));
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+/// Reset `command`'s environment to a clean slate containing only the
+/// variables named in `secrets.env_allow`, so API keys and other secrets
+/// held by the parent process never reach the child.
+fn apply_minimized_env(command: &mut Command, secrets: &SecretsCfg) {
+    command.env_clear();
+    for name in &secrets.env_allow {
+        if let Ok(value) = std::env::var(name) {
+            command.env(name, value);
+        }
+    }
+}
+
+/// Constructs that let a sub-command escape naive tokenization: command
+/// substitution and process substitution. Rejected outright rather than
+/// parsed, since their expansion can hide an arbitrary binary.
+const UNSAFE_SUBSTITUTIONS: &[&str] = &["$(", "`", "<(", ">("];
+
+/// Split `cmd` into shell-operator-delimited sub-commands, honoring quoting
+/// so that an operator inside a quoted string (e.g. `echo "a;b"`) is not
+/// mistaken for a command separator, and honoring backslash escapes so this
+/// agrees with real `bash` on which characters are quotes/operators versus
+/// literal text -- a `\"` must not close a double-quoted region, and a
+/// `\;` must not act as a separator, the same as outside this function
+/// `bash -lc` would treat them. Escaped text is kept in the segment as-is
+/// (backslash included) so [`tokenize_commands`]'s `shell_words::split` can
+/// resolve the escape itself.
+fn split_on_operators(cmd: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut cur = String::new();
+    let mut chars = cmd.chars().peekable();
+    let mut in_single = false;
+    let mut in_double = false;
+    while let Some(c) = chars.next() {
+        if in_single {
+            cur.push(c);
+            if c == '\'' {
+                in_single = false;
+            }
+            continue;
+        }
+        if in_double {
+            // Inside double quotes, bash only lets a backslash escape a
+            // following `"`, `\`, or `$`; any other backslash is literal.
+            if c == '\\' && matches!(chars.peek(), Some('"') | Some('\\') | Some('$')) {
+                cur.push(c);
+                if let Some(next) = chars.next() {
+                    cur.push(next);
+                }
+            } else if c == '"' {
+                in_double = false;
+                cur.push(c);
+            } else {
+                cur.push(c);
+            }
+            continue;
+        }
+        match c {
+            '\\' => {
+                // Outside quotes, backslash escapes the very next character
+                // literally -- including a quote or an operator -- so it
+                // can't be mistaken for either.
+                cur.push(c);
+                if let Some(next) = chars.next() {
+                    cur.push(next);
+                }
+            }
+            '\'' => {
+                in_single = true;
+                cur.push(c);
+            }
+            '"' => {
+                in_double = true;
+                cur.push(c);
+            }
+            '|' | ';' | '&' | '\n' => {
+                // Swallow a doubled operator (&&, ||) as a single separator.
+                if (c == '&' || c == '|') && chars.peek() == Some(&c) {
+                    chars.next();
+                }
+                parts.push(cur.trim().to_string());
+                cur.clear();
+            }
+            _ => cur.push(c),
+        }
+    }
+    parts.push(cur.trim().to_string());
+    parts.into_iter().filter(|s| !s.is_empty()).collect()
+}
+
+/// Parse `cmd` into its constituent sub-commands, each already split into
+/// shell-words argv, rejecting anything a naive lexer could misjudge.
+fn tokenize_commands(cmd: &str) -> Result<Vec<Vec<String>>> {
+    for needle in UNSAFE_SUBSTITUTIONS {
+        if cmd.contains(needle) {
+            return Err(anyhow!(format!(
+                "sandbox: construction shell non autorisée: {needle}"
+            )));
+        }
+    }
+    split_on_operators(cmd)
+        .into_iter()
+        .map(|segment| {
+            shell_words::split(&segment)
+                .map_err(|e| anyhow!(format!("sandbox: commande non analysable: {e}")))
+        })
         .collect()
 }
 
-fn first_word(s: &str) -> &str {
-    s.split_whitespace().next().unwrap_or("")
+fn first_word(argv: &[String]) -> &str {
+    argv.first().map(String::as_str).unwrap_or("")
+}
+
+/// File-argument positions to check for a mutating command, given its argv
+/// tail (everything after the binary itself). Best-effort: flags are
+/// skipped, and `sed`'s in-place edits only inspect args once `-i` is seen.
+fn path_confinement_targets<'a>(bin: &str, args: &'a [String]) -> Vec<&'a str> {
+    let non_flags = || args.iter().map(String::as_str).filter(|a| !a.starts_with('-'));
+    match bin {
+        "rm" | "mv" | "cp" | "tee" | "del" | "move" | "copy" => non_flags().collect(),
+        "sed" if args.iter().any(|a| a == "-i" || a.starts_with("-i")) => {
+            // First non-flag arg is the sed script/expression, not a file.
+            non_flags().skip(1).collect()
+        }
+        _ => Vec::new(),
+    }
 }
 
-fn allowed_binaries() -> &'static [&'static str] {
-    // Conservative default allow-list for read/inspect operations
+/// Resolve `target` against the current directory without requiring it to
+/// exist yet (e.g. a `mv`/`cp` destination), by canonicalizing the deepest
+/// existing ancestor and rejoining the rest.
+fn resolve_target_path(target: &str) -> PathBuf {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let joined = if Path::new(target).is_absolute() {
+        PathBuf::from(target)
+    } else {
+        cwd.join(target)
+    };
+    if let Ok(canon) = joined.canonicalize() {
+        return canon;
+    }
+    let mut existing = joined.clone();
+    let mut tail = Vec::new();
+    while !existing.exists() {
+        match existing.file_name() {
+            Some(name) => {
+                tail.push(name.to_owned());
+                existing.pop();
+            }
+            None => break,
+        }
+    }
+    let mut resolved = existing.canonicalize().unwrap_or(existing);
+    for part in tail.into_iter().rev() {
+        resolved.push(part);
+    }
+    resolved
+}
+
+fn check_path_confinement(commands: &[Vec<String>], sb: &SandboxCfg) -> Result<()> {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let roots: Vec<PathBuf> = sb
+        .write_roots
+        .iter()
+        .map(|r| {
+            let p = Path::new(r);
+            let joined = if p.is_absolute() {
+                p.to_path_buf()
+            } else {
+                cwd.join(p)
+            };
+            joined.canonicalize().unwrap_or(joined)
+        })
+        .collect();
+    for argv in commands {
+        let bin = first_word(argv);
+        for target in path_confinement_targets(bin, &argv[1..]) {
+            let resolved = resolve_target_path(target);
+            if resolved.components().any(|c| c.as_os_str() == ".git") {
+                return Err(anyhow!(format!(
+                    "sandbox: chemin interdit sous .git: {target}"
+                )));
+            }
+            if !roots.iter().any(|root| resolved.starts_with(root)) {
+                return Err(anyhow!(format!(
+                    "sandbox: chemin hors de l'espace de travail: {target}"
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Read/inspect-only tools available regardless of profile.
+fn inspection_bins() -> &'static [&'static str] {
     &[
         "true", "false", "printf", "echo", "cat", "ls", "stat", "head", "tail", "wc", "cut",
         "sort", "uniq", "tr", "sed", "awk", "grep", "rg", "find", "xargs", "dirname", "basename",
         "pwd",
+        // Windows `cmd /C` builtins with no external executable of their
+        // own; harmless to list on Unix since they'll never be invoked.
+        "dir", "type", "findstr", "where", "cd",
     ]
 }
 
-fn net_sensitive_binaries() -> &'static [&'static str] {
-    &["curl", "wget", "pip", "npm", "apt", "git", "ssh", "scp"]
+/// Additional build/VCS tools granted on top of `inspection_bins()` for the
+/// default (`std`) profile.
+fn build_bins() -> &'static [&'static str] {
+    &[
+        "cargo", "rustc", "make", "npm", "pnpm", "yarn", "node", "python3", "pytest", "go", "cc",
+        "gcc", "clang", "git",
+    ]
+}
+
+/// Binaries allowed for `profile`, shared by the CLI and MCP call paths.
+/// `None` means unrestricted (the `danger` profile).
+fn profile_allow_bins(sb: &SandboxCfg, profile: &str) -> Option<Vec<String>> {
+    match profile {
+        "danger" | "danger-full-access" => None,
+        "read-only" => Some(inspection_bins().iter().map(|s| s.to_string()).collect()),
+        _ => {
+            let mut allow: Vec<String> = inspection_bins()
+                .iter()
+                .chain(build_bins())
+                .map(|s| s.to_string())
+                .collect();
+            allow.extend(sb.allow_bins.iter().cloned());
+            allow.sort();
+            allow.dedup();
+            Some(allow)
+        }
+    }
 }
 
 fn enforce_policy(cmd: &str, policy: &PolicyCfg, sb: &SandboxCfg) -> Result<()> {
-    let parts = tokenize_commands(cmd);
-    let allow = allowed_binaries();
-    let netblk = net_sensitive_binaries();
-    for p in parts {
-        let bin = first_word(&p);
-        if !allow.contains(&bin) {
-            return Err(anyhow!(format!("sandbox: binaire non autorisé: {bin}")));
-        }
-        if sb.net.eq_ignore_ascii_case("off") && netblk.contains(&bin) {
+    let commands = tokenize_commands(cmd)?;
+    let profile = policy
+        .profile
+        .as_ref()
+        .map(|s| s.to_ascii_lowercase())
+        .unwrap_or_else(|| "std".into());
+    let allow = profile_allow_bins(sb, &profile);
+    for argv in &commands {
+        let bin = first_word(argv);
+        if sb.deny_bins.iter().any(|d| d == bin) {
+            return Err(anyhow!(format!("sandbox: binaire interdit: {bin}")));
+        }
+        if let Some(allow) = &allow {
+            if !allow.iter().any(|a| a == bin) {
+                return Err(anyhow!(format!("sandbox: binaire non autorisé: {bin}")));
+            }
+        }
+        if sb.net.eq_ignore_ascii_case("off") && sb.net_bins.iter().any(|n| n == bin) {
             return Err(anyhow!(format!(
                 "sandbox: réseau interdit, commande bloquée: {bin}"
             )));
         }
     }
-    // Approval profile may further restrict execution later at CLI layer
-    let eff = policy
+    check_path_confinement(&commands, sb)?;
+    if profile == "read-only" {
+        // Best-effort: block common mutating commands and output redirection
+        let mutating = [
+            "rm", "mv", "cp", "chmod", "chown", "tee", "dd", "del", "move", "copy",
+        ];
+        let writes = commands.iter().any(|argv| {
+            mutating.contains(&first_word(argv))
+                || argv.iter().any(|tok| tok == ">" || tok == ">>")
+        });
+        if writes {
+            return Err(anyhow!("sandbox: profil read-only: écriture interdite"));
+        }
+    }
+    Ok(())
+}
+
+/// Result of evaluating a command against policy without executing it, for
+/// `devit tool call shell_exec --explain`.
+#[derive(Debug, Clone)]
+pub struct PolicyExplanation {
+    pub allowed: bool,
+    pub profile: String,
+    /// Binary of each sub-command, split the same way `enforce_policy` does.
+    pub commands: Vec<String>,
+    /// Same message `enforce_policy` would fail with; `None` when allowed.
+    pub blocked_by: Option<String>,
+}
+
+/// Evaluate `cmd` against `policy`/`sb` without running anything, reporting
+/// which binary/rule would block it and which profile is in effect.
+pub fn explain_policy(cmd: &str, policy: &PolicyCfg, sb: &SandboxCfg) -> PolicyExplanation {
+    let profile = policy
         .profile
         .as_ref()
         .map(|s| s.to_ascii_lowercase())
-        .unwrap_or_default();
-    if eff == "read-only" {
-        // Best-effort: block common mutating commands
-        let mutating = ["rm", "mv", "cp", "chmod", "chown", "tee", "dd", ">", ">>"];
-        if tokenize_commands(cmd)
-            .iter()
-            .any(|c| mutating.contains(&first_word(c)))
-        {
-            return Err(anyhow!("sandbox: profil read-only: écriture interdite"));
+        .unwrap_or_else(|| "std".into());
+    let commands = match tokenize_commands(cmd) {
+        Ok(c) => c,
+        Err(e) => {
+            return PolicyExplanation {
+                allowed: false,
+                profile,
+                commands: Vec::new(),
+                blocked_by: Some(e.to_string()),
+            };
         }
+    };
+    let bins = commands
+        .iter()
+        .map(|argv| first_word(argv).to_string())
+        .collect();
+    match enforce_policy(cmd, policy, sb) {
+        Ok(()) => PolicyExplanation {
+            allowed: true,
+            profile,
+            commands: bins,
+            blocked_by: None,
+        },
+        Err(e) => PolicyExplanation {
+            allowed: false,
+            profile,
+            commands: bins,
+            blocked_by: Some(e.to_string()),
+        },
     }
-    Ok(())
 }
 
-pub fn run_shell_sandboxed(cmd: &str, policy: &PolicyCfg, sb: &SandboxCfg) -> Result<i32> {
+pub fn run_shell_sandboxed(
+    cmd: &str,
+    policy: &PolicyCfg,
+    sb: &SandboxCfg,
+    secrets: &SecretsCfg,
+    on_audit: Option<&dyn Fn(&ExecAudit)>,
+) -> Result<i32> {
     enforce_policy(cmd, policy, sb)?;
+    let started = Instant::now();
     // Execute via /bin/bash -lc in a minimized env
     let mut command = if cfg!(target_os = "windows") {
         let mut c = Command::new("cmd");
@@ -81,24 +563,35 @@ pub fn run_shell_sandboxed(cmd: &str, policy: &PolicyCfg, sb: &SandboxCfg) -> Re
         c.args(["-lc", cmd]);
         c
     };
-    // Best-effort disable proxies when net=off
-    if sb.net.eq_ignore_ascii_case("off") {
-        command.env_remove("http_proxy");
-        command.env_remove("https_proxy");
-        command.env_remove("HTTP_PROXY");
-        command.env_remove("HTTPS_PROXY");
-        command.env_remove("ALL_PROXY");
+    apply_minimized_env(&mut command, secrets);
+    let _proxy = apply_net_allowlist(&mut command, sb)?;
+    new_process_group(&mut command);
+    let mut child = command.spawn()?;
+    #[allow(clippy::let_unit_value)]
+    let _job = confine_to_job(&child, sb)?;
+    let (code, _timed_out, usage) = wait_with_timeout(&mut child, resolve_timeout(sb))?;
+    if let Some(cb) = on_audit {
+        cb(&ExecAudit {
+            cmd: cmd.to_string(),
+            cwd: std::env::current_dir().unwrap_or_default(),
+            exit_code: code,
+            duration_ms: started.elapsed().as_millis(),
+            output_sha256: sha256_hex(&[]),
+            usage,
+        });
     }
-    let status = command.status()?;
-    Ok(status.code().unwrap_or(-1))
+    Ok(code)
 }
 
 pub fn run_shell_sandboxed_capture(
     cmd: &str,
     policy: &PolicyCfg,
     sb: &SandboxCfg,
+    secrets: &SecretsCfg,
+    on_audit: Option<&dyn Fn(&ExecAudit)>,
 ) -> Result<(i32, String)> {
     enforce_policy(cmd, policy, sb)?;
+    let started = Instant::now();
     let mut command = if cfg!(target_os = "windows") {
         let mut c = Command::new("cmd");
         c.args(["/C", cmd]);
@@ -108,17 +601,273 @@ pub fn run_shell_sandboxed_capture(
         c.args(["-lc", cmd]);
         c
     };
-    if sb.net.eq_ignore_ascii_case("off") {
-        command.env_remove("http_proxy");
-        command.env_remove("https_proxy");
-        command.env_remove("HTTP_PROXY");
-        command.env_remove("HTTPS_PROXY");
-        command.env_remove("ALL_PROXY");
-    }
+    apply_minimized_env(&mut command, secrets);
+    let _proxy = apply_net_allowlist(&mut command, sb)?;
+    new_process_group(&mut command);
     command.stdout(Stdio::piped()).stderr(Stdio::piped());
-    let out = command.output()?;
-    let code = out.status.code().unwrap_or(-1);
-    let txt = String::from_utf8_lossy(&out.stdout).to_string()
-        + String::from_utf8_lossy(&out.stderr).as_ref();
+    let mut child = command.spawn()?;
+    #[allow(clippy::let_unit_value)]
+    let _job = confine_to_job(&child, sb)?;
+
+    // Drain stdout/stderr on background threads so a full pipe buffer can't
+    // block us while we poll for the timeout below.
+    let mut stdout = child.stdout.take().expect("piped stdout");
+    let mut stderr = child.stderr.take().expect("piped stderr");
+    let (tx_out, rx_out) = mpsc::channel();
+    let (tx_err, rx_err) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = stdout.read_to_end(&mut buf);
+        let _ = tx_out.send(buf);
+    });
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = stderr.read_to_end(&mut buf);
+        let _ = tx_err.send(buf);
+    });
+
+    let (code, timed_out, usage) = wait_with_timeout(&mut child, resolve_timeout(sb))?;
+    let wait_for_pipe = if timed_out {
+        Duration::from_millis(200)
+    } else {
+        Duration::from_secs(5)
+    };
+    let out_buf = rx_out.recv_timeout(wait_for_pipe).unwrap_or_default();
+    let err_buf = rx_err.recv_timeout(wait_for_pipe).unwrap_or_default();
+    let txt =
+        String::from_utf8_lossy(&out_buf).to_string() + String::from_utf8_lossy(&err_buf).as_ref();
+    if let Some(cb) = on_audit {
+        cb(&ExecAudit {
+            cmd: cmd.to_string(),
+            cwd: std::env::current_dir().unwrap_or_default(),
+            exit_code: code,
+            duration_ms: started.elapsed().as_millis(),
+            output_sha256: sha256_hex(txt.as_bytes()),
+            usage,
+        });
+    }
     Ok((code, txt))
 }
+
+/// One line of output from a streamed `shell_exec`, tagged by which stream
+/// it came from.
+#[derive(Debug, Clone)]
+pub enum OutputLine {
+    Stdout(String),
+    Stderr(String),
+}
+
+/// Like `run_shell_sandboxed_capture`, but delivers output incrementally:
+/// `on_line` is invoked for each line as soon as it's read instead of after
+/// the command finishes, so a caller (CLI/TUI) can display long-running
+/// output live.
+pub fn run_shell_sandboxed_stream(
+    cmd: &str,
+    policy: &PolicyCfg,
+    sb: &SandboxCfg,
+    secrets: &SecretsCfg,
+    on_line: &mut dyn FnMut(OutputLine),
+    on_audit: Option<&dyn Fn(&ExecAudit)>,
+) -> Result<i32> {
+    enforce_policy(cmd, policy, sb)?;
+    let started = Instant::now();
+    let mut command = if cfg!(target_os = "windows") {
+        let mut c = Command::new("cmd");
+        c.args(["/C", cmd]);
+        c
+    } else {
+        let mut c = Command::new("bash");
+        c.args(["-lc", cmd]);
+        c
+    };
+    apply_minimized_env(&mut command, secrets);
+    let _proxy = apply_net_allowlist(&mut command, sb)?;
+    new_process_group(&mut command);
+    command.stdout(Stdio::piped()).stderr(Stdio::piped());
+    let mut child = command.spawn()?;
+    #[allow(clippy::let_unit_value)]
+    let _job = confine_to_job(&child, sb)?;
+
+    let stdout = child.stdout.take().expect("piped stdout");
+    let stderr = child.stderr.take().expect("piped stderr");
+    let (tx, rx) = mpsc::channel();
+    let tx_out = tx.clone();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(std::result::Result::ok) {
+            if tx_out.send(OutputLine::Stdout(line)).is_err() {
+                break;
+            }
+        }
+    });
+    std::thread::spawn(move || {
+        for line in BufReader::new(stderr).lines().map_while(std::result::Result::ok) {
+            if tx.send(OutputLine::Stderr(line)).is_err() {
+                break;
+            }
+        }
+    });
+
+    let timeout = resolve_timeout(sb);
+    let mut hasher = Sha256::new();
+    let record = |line: OutputLine, hasher: &mut Sha256, on_line: &mut dyn FnMut(OutputLine)| {
+        let text = match &line {
+            OutputLine::Stdout(l) | OutputLine::Stderr(l) => l.as_str(),
+        };
+        hasher.update(text.as_bytes());
+        hasher.update(b"\n");
+        on_line(line);
+    };
+    let (code, timed_out, usage) = loop {
+        while let Ok(line) = rx.try_recv() {
+            record(line, &mut hasher, on_line);
+        }
+        if let Some((code, usage)) = try_wait_rusage(&mut child)? {
+            break (code, false, usage);
+        }
+        if started.elapsed() >= timeout {
+            kill_process_group(&mut child);
+            let usage = reap_with_rusage(&mut child);
+            break (TIMEOUT_EXIT_CODE, true, usage);
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    };
+    let drain_for = if timed_out {
+        Duration::from_millis(200)
+    } else {
+        Duration::from_secs(5)
+    };
+    let drain_deadline = Instant::now() + drain_for;
+    while let Ok(line) = rx.recv_timeout(drain_deadline.saturating_duration_since(Instant::now())) {
+        record(line, &mut hasher, on_line);
+    }
+
+    if let Some(cb) = on_audit {
+        cb(&ExecAudit {
+            cmd: cmd.to_string(),
+            cwd: std::env::current_dir().unwrap_or_default(),
+            exit_code: code,
+            duration_ms: started.elapsed().as_millis(),
+            output_sha256: hex::encode(hasher.finalize()),
+            usage: ResourceUsage {
+                wall_ms: started.elapsed().as_millis(),
+                ..usage
+            },
+        });
+    }
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_on_operators_respects_quotes() {
+        assert_eq!(
+            split_on_operators(r#"echo "a;b" && echo c"#),
+            vec![r#"echo "a;b""#, "echo c"]
+        );
+    }
+
+    #[test]
+    fn split_on_operators_does_not_close_quote_on_escaped_quote() {
+        // bash: `echo \"; rm -rf /` is TWO commands -- the `\"` is a literal
+        // quote inside `echo`'s argument, not the start of a quoted region,
+        // so the `;` right after it is a real separator.
+        assert_eq!(
+            split_on_operators(r#"echo \"; rm -rf /"#),
+            vec![r#"echo \""#, "rm -rf /"]
+        );
+    }
+
+    #[test]
+    fn split_on_operators_keeps_escaped_operator_literal() {
+        // bash: `echo foo\;bar` is ONE command -- the `\;` is a literal
+        // semicolon in the argument, not a separator.
+        assert_eq!(
+            split_on_operators(r#"echo foo\;bar"#),
+            vec![r#"echo foo\;bar"#]
+        );
+    }
+
+    #[test]
+    fn tokenize_commands_sees_rm_after_escaped_quote() {
+        let commands = tokenize_commands(r#"echo \"; rm -rf /"#).unwrap();
+        assert_eq!(commands.len(), 2);
+        assert_eq!(first_word(&commands[1]), "rm");
+    }
+
+    #[test]
+    fn tokenize_commands_rejects_command_substitution() {
+        assert!(tokenize_commands("echo $(whoami)").is_err());
+    }
+
+    /// The bug this guards against: `tokenize_commands` must see the same
+    /// number of sub-commands that `bash -lc` actually runs, or
+    /// `enforce_policy` ends up checking an allow-list against a binary
+    /// bash never runs, missing the one it does.
+    #[test]
+    fn tokenize_commands_agrees_with_bash_on_escaped_quote() {
+        let cmd = r#"echo \"; echo second"#;
+        let commands = tokenize_commands(cmd).unwrap();
+        let out = std::process::Command::new("bash")
+            .args(["-c", cmd])
+            .output()
+            .expect("bash must be available to run shell_exec at all");
+        let bash_lines = String::from_utf8_lossy(&out.stdout).lines().count();
+        assert_eq!(
+            commands.len(),
+            bash_lines,
+            "tokenize_commands split {} sub-commands but bash actually ran {}",
+            commands.len(),
+            bash_lines
+        );
+    }
+
+    fn sandbox_cfg(write_roots: Vec<String>) -> SandboxCfg {
+        SandboxCfg {
+            cpu_limit: 0,
+            mem_limit_mb: 0,
+            net: "off".into(),
+            write_roots,
+            timeout_secs: None,
+            allow_bins: vec![],
+            deny_bins: vec![],
+            net_bins: vec![],
+            net_allowlist: vec![],
+        }
+    }
+
+    #[test]
+    fn check_path_confinement_allows_write_inside_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("file.txt");
+        std::fs::write(&target, "x").unwrap();
+        let sb = sandbox_cfg(vec![dir.path().to_string_lossy().into_owned()]);
+        let commands = vec![vec!["rm".to_string(), target.to_string_lossy().into_owned()]];
+        assert!(check_path_confinement(&commands, &sb).is_ok());
+    }
+
+    #[test]
+    fn check_path_confinement_rejects_write_outside_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let target = outside.path().join("file.txt");
+        std::fs::write(&target, "x").unwrap();
+        let sb = sandbox_cfg(vec![dir.path().to_string_lossy().into_owned()]);
+        let commands = vec![vec!["rm".to_string(), target.to_string_lossy().into_owned()]];
+        assert!(check_path_confinement(&commands, &sb).is_err());
+    }
+
+    #[test]
+    fn check_path_confinement_rejects_dot_git() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join(".git").join("config");
+        std::fs::create_dir_all(target.parent().unwrap()).unwrap();
+        std::fs::write(&target, "x").unwrap();
+        let sb = sandbox_cfg(vec![dir.path().to_string_lossy().into_owned()]);
+        let commands = vec![vec!["rm".to_string(), target.to_string_lossy().into_owned()]];
+        assert!(check_path_confinement(&commands, &sb).is_err());
+    }
+}
+
@@ -0,0 +1,180 @@
+// # -----------------------------
+// # crates/sandbox/src/proxy.rs
+// # -----------------------------
+// Minimal local filtering proxy for `net = "allowlist"`: only CONNECT (TLS)
+// and absolute-form HTTP requests to an allow-listed domain are tunnelled;
+// everything else gets a 403 and the connection is closed.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A running local proxy bound to `127.0.0.1:<port>`. Dropping it stops the
+/// accept loop and closes the listener.
+pub struct FilteringProxy {
+    pub port: u16,
+    stop: Arc<AtomicBool>,
+    handle: Option<std::thread::JoinHandle<()>>,
+}
+
+impl FilteringProxy {
+    /// Start listening and accepting connections in the background, only
+    /// permitting CONNECT/request targets whose host matches `allowlist`
+    /// (exact match, or a `.`-prefixed entry matching any subdomain).
+    pub fn spawn(allowlist: Vec<String>) -> std::io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        listener.set_nonblocking(true)?;
+        let port = listener.local_addr()?.port();
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_thread = stop.clone();
+        let handle = std::thread::spawn(move || {
+            while !stop_thread.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        let allow = allowlist.clone();
+                        std::thread::spawn(move || {
+                            let _ = handle_conn(stream, &allow);
+                        });
+                    }
+                    Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                        std::thread::sleep(Duration::from_millis(20));
+                    }
+                    Err(_) => break,
+                }
+            }
+        });
+        Ok(Self {
+            port,
+            stop,
+            handle: Some(handle),
+        })
+    }
+}
+
+impl Drop for FilteringProxy {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(h) = self.handle.take() {
+            let _ = h.join();
+        }
+    }
+}
+
+fn host_allowed(host: &str, allowlist: &[String]) -> bool {
+    allowlist.iter().any(|entry| {
+        if let Some(suffix) = entry.strip_prefix('.') {
+            host == suffix || host.ends_with(&format!(".{suffix}"))
+        } else {
+            host.eq_ignore_ascii_case(entry)
+        }
+    })
+}
+
+fn handle_conn(mut client: TcpStream, allowlist: &[String]) -> std::io::Result<()> {
+    client.set_nonblocking(false)?;
+    let mut reader = BufReader::new(client.try_clone()?);
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line)? == 0 {
+        return Ok(());
+    }
+    let mut headers = Vec::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line == "\r\n" || line.is_empty() {
+            break;
+        }
+        headers.push(line);
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let target = parts.next().unwrap_or("");
+
+    let host_port = if method.eq_ignore_ascii_case("CONNECT") {
+        target.to_string()
+    } else {
+        // Absolute-form request: GET http://host[:port]/path HTTP/1.1
+        target
+            .split("://")
+            .nth(1)
+            .and_then(|rest| rest.split('/').next())
+            .unwrap_or("")
+            .to_string()
+    };
+    let host = host_port.split(':').next().unwrap_or("").to_string();
+
+    if host.is_empty() || !host_allowed(&host, allowlist) {
+        let _ = client.write_all(b"HTTP/1.1 403 Forbidden\r\n\r\n");
+        return Ok(());
+    }
+
+    let default_port = if method.eq_ignore_ascii_case("CONNECT") {
+        443
+    } else {
+        80
+    };
+    let addr = if host_port.contains(':') {
+        host_port.clone()
+    } else {
+        format!("{host_port}:{default_port}")
+    };
+    let upstream = match TcpStream::connect(&addr) {
+        Ok(s) => s,
+        Err(_) => {
+            let _ = client.write_all(b"HTTP/1.1 502 Bad Gateway\r\n\r\n");
+            return Ok(());
+        }
+    };
+
+    if method.eq_ignore_ascii_case("CONNECT") {
+        client.write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")?;
+    } else {
+        let mut upstream = upstream.try_clone()?;
+        upstream.write_all(request_line.as_bytes())?;
+        for h in &headers {
+            upstream.write_all(h.as_bytes())?;
+        }
+        upstream.write_all(b"\r\n")?;
+    }
+
+    splice(client, upstream)
+}
+
+/// Relay bytes in both directions until either side closes.
+fn splice(client: TcpStream, upstream: TcpStream) -> std::io::Result<()> {
+    let mut client_to_up = upstream.try_clone()?;
+    let mut client_reader = client.try_clone()?;
+    let up_handle = std::thread::spawn(move || {
+        let _ = std::io::copy(&mut client_reader, &mut client_to_up);
+    });
+    let mut up_reader = upstream;
+    let mut client_writer = client;
+    let _ = std::io::copy(&mut up_reader, &mut client_writer);
+    let _ = up_handle.join();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn host_allowed_exact_match() {
+        let allow = vec!["crates.io".to_string()];
+        assert!(host_allowed("crates.io", &allow));
+        assert!(host_allowed("CRATES.IO", &allow));
+        assert!(!host_allowed("static.crates.io", &allow));
+        assert!(!host_allowed("notcrates.io", &allow));
+    }
+
+    #[test]
+    fn host_allowed_subdomain_wildcard() {
+        let allow = vec![".crates.io".to_string()];
+        assert!(host_allowed("crates.io", &allow));
+        assert!(host_allowed("static.crates.io", &allow));
+        assert!(!host_allowed("evilcrates.io", &allow));
+        assert!(!host_allowed("crates.io.evil.com", &allow));
+    }
+}
@@ -0,0 +1,112 @@
+// # -----------------------------
+// # crates/sandbox/src/winjob.rs
+// # -----------------------------
+// Windows-only: confine a sandboxed child to a Job Object so
+// `sandbox.cpu_limit` / `sandbox.mem_limit_mb` are actually enforced and the
+// whole process tree dies with the job (mirrors the Unix process-group kill
+// in `kill_process_group`).
+
+use anyhow::{anyhow, Result};
+use devit_common::SandboxCfg;
+use std::os::windows::io::AsRawHandle;
+use std::process::Child;
+use windows_sys::Win32::Foundation::CloseHandle;
+use windows_sys::Win32::Foundation::HANDLE;
+use windows_sys::Win32::System::JobObjects::{
+    AssignProcessToJobObject, CreateJobObjectW, JobObjectCpuRateControlInformation,
+    JobObjectExtendedLimitInformation, SetInformationJobObject,
+    JOBOBJECT_CPU_RATE_CONTROL_INFORMATION, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+    JOB_OBJECT_CPU_RATE_CONTROL_ENABLE, JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP,
+    JOB_OBJECT_LIMIT_JOB_MEMORY, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    JOB_OBJECT_LIMIT_PROCESS_MEMORY,
+};
+
+/// A Job Object that terminates every process assigned to it as soon as it
+/// is dropped (`JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE`).
+pub struct JobObject {
+    handle: HANDLE,
+}
+
+impl JobObject {
+    pub fn create(sb: &SandboxCfg) -> Result<Self> {
+        // SAFETY: CreateJobObjectW with a null security descriptor and no
+        // name is the documented way to create an anonymous, unshared job.
+        let handle = unsafe { CreateJobObjectW(std::ptr::null(), std::ptr::null()) };
+        if handle == 0 {
+            return Err(anyhow!("sandbox: CreateJobObjectW failed"));
+        }
+        let job = Self { handle };
+
+        let mut ext_info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = unsafe { std::mem::zeroed() };
+        ext_info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+        if sb.mem_limit_mb > 0 {
+            ext_info.BasicLimitInformation.LimitFlags |= JOB_OBJECT_LIMIT_PROCESS_MEMORY;
+            ext_info.ProcessMemoryLimit = (sb.mem_limit_mb as usize) * 1024 * 1024;
+            ext_info.BasicLimitInformation.LimitFlags |= JOB_OBJECT_LIMIT_JOB_MEMORY;
+            ext_info.JobMemoryLimit = ext_info.ProcessMemoryLimit;
+        }
+        // SAFETY: `ext_info` is a valid, correctly-sized limit struct for
+        // JobObjectExtendedLimitInformation, matching the API contract.
+        let ok = unsafe {
+            SetInformationJobObject(
+                job.handle,
+                JobObjectExtendedLimitInformation,
+                &ext_info as *const _ as *const _,
+                std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+            )
+        };
+        if ok == 0 {
+            return Err(anyhow!("sandbox: SetInformationJobObject (memory limits) failed"));
+        }
+
+        if sb.cpu_limit > 0 {
+            let cores = std::thread::available_parallelism()
+                .map(|n| n.get() as u32)
+                .unwrap_or(1);
+            let rate = ((sb.cpu_limit.min(cores) as u64 * 10_000) / cores as u64).clamp(1, 10_000);
+            let mut cpu_info: JOBOBJECT_CPU_RATE_CONTROL_INFORMATION =
+                unsafe { std::mem::zeroed() };
+            cpu_info.ControlFlags =
+                JOB_OBJECT_CPU_RATE_CONTROL_ENABLE | JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP;
+            cpu_info.Anonymous.CpuRate = rate as u32;
+            // SAFETY: same contract as above, for JobObjectCpuRateControlInformation.
+            let ok = unsafe {
+                SetInformationJobObject(
+                    job.handle,
+                    JobObjectCpuRateControlInformation,
+                    &cpu_info as *const _ as *const _,
+                    std::mem::size_of::<JOBOBJECT_CPU_RATE_CONTROL_INFORMATION>() as u32,
+                )
+            };
+            if ok == 0 {
+                return Err(anyhow!("sandbox: SetInformationJobObject (CPU limit) failed"));
+            }
+        }
+
+        Ok(job)
+    }
+
+    /// Move `child` into this job so it inherits its memory/CPU limits and
+    /// dies with it.
+    pub fn assign(&self, child: &Child) -> Result<()> {
+        let process_handle = child.as_raw_handle() as HANDLE;
+        // SAFETY: `process_handle` is the live handle owned by `child`, and
+        // `self.handle` is a valid job object created by `create`.
+        let ok = unsafe { AssignProcessToJobObject(self.handle, process_handle) };
+        if ok == 0 {
+            return Err(anyhow!("sandbox: AssignProcessToJobObject failed"));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for JobObject {
+    fn drop(&mut self) {
+        // SAFETY: `self.handle` is a valid handle we own; closing it with
+        // KILL_ON_JOB_CLOSE set terminates the whole confined process tree.
+        unsafe {
+            CloseHandle(self.handle);
+        }
+    }
+}
+
@@ -44,7 +44,33 @@ pub fn run_tests() -> Result<i32> {
     }
 }
 
-pub fn run_tests_with_output() -> Result<(i32, String)> {
+/// Like [`run_tests_with_output`], but honors `[test] command` from
+/// `devit.toml` when set, running it via `bash -lc` with `[test].env`
+/// merged in instead of auto-detecting the stack.
+pub fn run_tests_with_output(test_cfg: &devit_common::TestCfg) -> Result<(i32, String)> {
+    if let Some(command) = test_cfg.command.as_deref() {
+        return run_shell_with_output(command, &test_cfg.env);
+    }
+    run_tests_with_output_auto()
+}
+
+fn run_shell_with_output(
+    command: &str,
+    env: &std::collections::HashMap<String, String>,
+) -> Result<(i32, String)> {
+    let out = Command::new("bash")
+        .arg("-lc")
+        .arg(command)
+        .envs(env)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()?;
+    let txt = String::from_utf8_lossy(&out.stdout).to_string()
+        + String::from_utf8_lossy(&out.stderr).as_ref();
+    Ok((out.status.code().unwrap_or(-1), txt))
+}
+
+fn run_tests_with_output_auto() -> Result<(i32, String)> {
     match detect_stack() {
         Stack::Cargo => {
             let out = Command::new("cargo")
@@ -1,5 +1,7 @@
 use anyhow::{anyhow, Result};
+use devit_common::messages::{t, MsgKey};
 use std::io::Write;
+use std::path::Path;
 use std::process::{Command, Stdio};
 
 pub fn is_git_available() -> bool {
@@ -14,6 +16,61 @@ pub fn in_repo() -> bool {
         .unwrap_or(false)
 }
 
+/// Chemin du répertoire `.git` du dépôt courant (gère worktrees/submodules
+/// où `.git` est un fichier pointeur plutôt qu'un dossier).
+pub fn git_dir() -> Result<String> {
+    let out = Command::new("git")
+        .args(["rev-parse", "--git-dir"])
+        .output()?;
+    if !out.status.success() {
+        return Err(anyhow!(
+            "impossible de déterminer le répertoire .git:\n{}",
+            String::from_utf8_lossy(&out.stderr)
+        ));
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+/// Nom de la branche courante (`HEAD` détaché exclu).
+pub fn current_branch() -> Result<String> {
+    let out = Command::new("git")
+        .args(["rev-parse", "--abbrev-ref", "HEAD"])
+        .output()?;
+    if !out.status.success() {
+        return Err(anyhow!(
+            "impossible de déterminer la branche courante:\n{}",
+            String::from_utf8_lossy(&out.stderr)
+        ));
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+/// URL configurée pour le remote `name` (ex: `origin`).
+pub fn remote_url(name: &str) -> Result<String> {
+    let out = Command::new("git")
+        .args(["remote", "get-url", name])
+        .output()?;
+    if !out.status.success() {
+        return Err(anyhow!("remote '{name}' introuvable"));
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+/// Pousse `branch` vers `remote`, en la publiant si besoin (`-u`).
+pub fn push(remote: &str, branch: &str) -> Result<()> {
+    let out = Command::new("git")
+        .args(["push", "-u", remote, branch])
+        .output()?;
+    if !out.status.success() {
+        return Err(anyhow!(
+            "git push a échoué:\n{}{}",
+            String::from_utf8_lossy(&out.stdout),
+            String::from_utf8_lossy(&out.stderr)
+        ));
+    }
+    Ok(())
+}
+
 pub fn status_porcelain() -> Result<String> {
     let out = Command::new("git")
         .args(["status", "--porcelain"])
@@ -118,11 +175,23 @@ pub fn apply_worktree(patch: &str) -> Result<bool> {
     )))
 }
 
+/// Commit l'index courant. Ne laisse rien fuiter sur stdout/stderr du
+/// processus parent (utile pour les sorties `--json` qui exigent un seul
+/// document JSON sur stdout) ; en cas d'échec, le détail Git est renvoyé
+/// dans l'erreur.
 pub fn commit(message: &str) -> Result<bool> {
-    let status = Command::new("git")
+    let out = Command::new("git")
         .args(["commit", "-m", message])
-        .status()?;
-    Ok(status.success())
+        .output()?;
+    if !out.status.success() {
+        return Err(anyhow!(format!(
+            "{}:\n{}{}",
+            t(MsgKey::CommitFailed),
+            String::from_utf8_lossy(&out.stdout),
+            String::from_utf8_lossy(&out.stderr)
+        )));
+    }
+    Ok(true)
 }
 
 pub fn head_short() -> Option<String> {
@@ -139,6 +208,96 @@ pub fn head_short() -> Option<String> {
         })
 }
 
+/// Stash tracked and untracked uncommitted changes under `message`.
+/// Returns `false` (no-op) when the worktree was already clean.
+pub fn stash_push(message: &str) -> Result<bool> {
+    let out = Command::new("git")
+        .args(["stash", "push", "-u", "-m", message])
+        .output()?;
+    if !out.status.success() {
+        return Err(anyhow!(
+            "git stash push a échoué:\n{}{}",
+            String::from_utf8_lossy(&out.stdout),
+            String::from_utf8_lossy(&out.stderr)
+        ));
+    }
+    Ok(!String::from_utf8_lossy(&out.stdout).contains("No local changes to save"))
+}
+
+/// Reapply and drop the most recent stash entry. On conflict, the worktree
+/// is reset back to a clean `HEAD` (discarding the partial merge) and the
+/// stash entry is left in place — never dropped — so the caller recovers by
+/// running `git stash pop` themselves instead of losing anything.
+pub fn stash_pop() -> Result<bool> {
+    let out = Command::new("git").args(["stash", "pop"]).output()?;
+    if out.status.success() {
+        return Ok(true);
+    }
+    let _ = Command::new("git")
+        .args(["reset", "--hard", "HEAD"])
+        .status();
+    let _ = Command::new("git").args(["clean", "-fd"]).status();
+    Ok(false)
+}
+
+/// Start a `git bisect` session and mark the initial bad/good boundary.
+/// Returns git's own status text after the `good` step (the same text a
+/// human would see running these commands directly), which already says
+/// "is the first bad commit" if the range only had one candidate.
+pub fn bisect_start(bad_rev: &str, good_rev: &str) -> Result<String> {
+    run_bisect(&["start"])?;
+    run_bisect(&["bad", bad_rev])?;
+    run_bisect(&["good", good_rev])
+}
+
+/// Mark the commit `git bisect` just checked out as good or bad, advancing
+/// to the next candidate (or finishing the search).
+pub fn bisect_mark(good: bool) -> Result<String> {
+    run_bisect(&[if good { "good" } else { "bad" }])
+}
+
+/// Leave bisect mode and return the worktree to the branch it started on.
+pub fn bisect_reset() {
+    let _ = Command::new("git").args(["bisect", "reset"]).status();
+}
+
+/// Pull the culprit SHA out of git's own "<sha> is the first bad commit"
+/// line, so callers don't need to reparse output from every bisect step.
+pub fn parse_bisect_culprit(output: &str) -> Option<String> {
+    output.lines().find_map(|l| {
+        l.strip_suffix(" is the first bad commit")
+            .map(|sha| sha.trim().to_string())
+    })
+}
+
+fn run_bisect(args: &[&str]) -> Result<String> {
+    let mut full = vec!["bisect"];
+    full.extend_from_slice(args);
+    let out = Command::new("git").args(&full).output()?;
+    let txt = String::from_utf8_lossy(&out.stdout).to_string()
+        + String::from_utf8_lossy(&out.stderr).as_ref();
+    if !out.status.success() {
+        return Err(anyhow!("git bisect {}: échec\n{txt}", args.join(" ")));
+    }
+    Ok(txt)
+}
+
+/// Unified diff introduced by a single commit (`git show`, commit message
+/// stripped), ready to feed into a unified-diff parser.
+pub fn commit_diff(sha: &str) -> Result<String> {
+    let out = Command::new("git")
+        .args(["show", "--pretty=format:", "--no-color", sha])
+        .output()?;
+    if !out.status.success() {
+        return Err(anyhow!(format!(
+            "{}: {sha}\n{}",
+            t(MsgKey::CommitNotFound),
+            String::from_utf8_lossy(&out.stderr)
+        )));
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).to_string())
+}
+
 pub fn is_worktree_clean() -> bool {
     let wt = Command::new("git")
         .args(["diff", "--quiet"]) // worktree
@@ -159,3 +318,77 @@ pub fn add_note(message: &str) -> Result<bool> {
         .status()?;
     Ok(status.success())
 }
+
+/// Corps complet du message de commit (sujet + footers) pour `sha`.
+pub fn commit_message(sha: &str) -> Result<String> {
+    let out = Command::new("git")
+        .args(["log", "-1", "--format=%B", sha])
+        .output()?;
+    if !out.status.success() {
+        return Err(anyhow!(format!(
+            "{}: {sha}\n{}",
+            t(MsgKey::CommitNotFound),
+            String::from_utf8_lossy(&out.stderr)
+        )));
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).to_string())
+}
+
+/// Whether a `git rebase` is currently stopped on a conflict --
+/// `.git/rebase-merge` covers interactive/merge rebases, `.git/rebase-apply`
+/// the apply-based (`--whitespace`, am-style) ones.
+pub fn rebase_in_progress() -> bool {
+    let dir = git_dir().unwrap_or_else(|_| ".git".to_string());
+    let base = Path::new(&dir);
+    base.join("rebase-merge").exists() || base.join("rebase-apply").exists()
+}
+
+/// Stage every resolved conflict so `git rebase --continue` has a clean
+/// index to pick up.
+pub fn add_all() -> Result<bool> {
+    let out = Command::new("git").args(["add", "-A"]).output()?;
+    if !out.status.success() {
+        return Err(anyhow!(
+            "git add -A a échoué:\n{}{}",
+            String::from_utf8_lossy(&out.stdout),
+            String::from_utf8_lossy(&out.stderr)
+        ));
+    }
+    Ok(true)
+}
+
+/// Continue an in-progress rebase non-interactively. `GIT_EDITOR=true` so a
+/// step that only needs the replayed commit message re-confirmed doesn't
+/// pop an editor and hang the assistant.
+pub fn rebase_continue() -> Result<String> {
+    let out = Command::new("git")
+        .env("GIT_EDITOR", "true")
+        .args(["rebase", "--continue"])
+        .output()?;
+    let txt = String::from_utf8_lossy(&out.stdout).to_string()
+        + String::from_utf8_lossy(&out.stderr).as_ref();
+    if !out.status.success() {
+        return Err(anyhow!("git rebase --continue a échoué:\n{txt}"));
+    }
+    Ok(txt)
+}
+
+/// Revert `sha` en créant un nouveau commit (équivalent `git revert --no-edit`).
+pub fn revert_commit(sha: &str) -> Result<bool> {
+    let out = Command::new("git")
+        .args(["revert", "--no-edit", sha])
+        .output()?;
+    if !out.status.success() {
+        // Annule un éventuel revert partiel pour ne pas laisser le repo en conflit.
+        let _ = Command::new("git")
+            .args(["revert", "--abort"])
+            .status();
+        return Err(anyhow!(format!(
+            "{}:\n{}{}",
+            t(MsgKey::RevertFailed),
+            String::from_utf8_lossy(&out.stdout),
+            String::from_utf8_lossy(&out.stderr)
+        )));
+    }
+    Ok(true)
+}
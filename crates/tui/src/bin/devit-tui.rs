@@ -1258,18 +1258,7 @@ impl DiffState {
     }
 }
 
-#[derive(Debug, Clone)]
-struct DiffFile {
-    display_name: String,
-    header: Vec<String>,
-    hunks: Vec<DiffHunk>,
-}
-
-#[derive(Debug, Clone)]
-struct DiffHunk {
-    header: String,
-    lines: Vec<String>,
-}
+use devit_tui::{parse_unified_diff, DiffFile, DiffHunk};
 
 fn run_app<B: Backend>(
     terminal: &mut Terminal<B>,
@@ -1302,24 +1291,16 @@ fn run_app<B: Backend>(
                         let mut updated = false;
                         match key.code {
                             KeyCode::Char('j') | KeyCode::Char('J') | KeyCode::Down => {
-                                if diff.next_hunk() {
-                                    updated = true;
-                                }
+                                updated = diff.next_hunk();
                             }
                             KeyCode::Char('k') | KeyCode::Char('K') | KeyCode::Up => {
-                                if diff.prev_hunk() {
-                                    updated = true;
-                                }
+                                updated = diff.prev_hunk();
                             }
                             KeyCode::Char('h') => {
-                                if diff.prev_file() {
-                                    updated = true;
-                                }
+                                updated = diff.prev_file();
                             }
                             KeyCode::Char('H') => {
-                                if diff.next_file() {
-                                    updated = true;
-                                }
+                                updated = diff.next_file();
                             }
                             KeyCode::Char('a') | KeyCode::Char('A') => {
                                 if matches!(app.recipes.mode, RecipeMode::DryRunReady { .. }) {
@@ -1646,133 +1627,6 @@ fn load_diff(path: &PathBuf, source: DiffSource, max_size: usize) -> Result<Diff
     Ok(DiffState::new(files))
 }
 
-fn parse_unified_diff(content: &str) -> Result<Vec<DiffFile>, String> {
-    #[derive(Default)]
-    struct PartialFile {
-        header: Vec<String>,
-        hunks: Vec<DiffHunk>,
-        old_path: Option<String>,
-        new_path: Option<String>,
-        diff_header: Option<String>,
-    }
-
-    impl PartialFile {
-        fn with_diff_header(line: &str) -> Self {
-            PartialFile {
-                diff_header: Some(line.to_string()),
-                header: vec![line.to_string()],
-                ..Default::default()
-            }
-        }
-
-        fn finalize(self) -> DiffFile {
-            let display = self
-                .new_path
-                .as_ref()
-                .or(self.old_path.as_ref())
-                .cloned()
-                .or_else(|| {
-                    self.diff_header
-                        .as_ref()
-                        .and_then(|h| extract_from_diff_header(h))
-                })
-                .unwrap_or_else(|| "(unknown)".to_string());
-            DiffFile {
-                display_name: clean_diff_path(&display),
-                header: self.header,
-                hunks: self.hunks,
-            }
-        }
-    }
-
-    let mut files: Vec<DiffFile> = Vec::new();
-    let mut current_file: Option<PartialFile> = None;
-    let mut current_hunk: Option<DiffHunk> = None;
-
-    let flush_hunk = |file: &mut Option<PartialFile>, hunk: &mut Option<DiffHunk>| {
-        if let Some(h) = hunk.take() {
-            if file.is_none() {
-                *file = Some(PartialFile::default());
-            }
-            if let Some(f) = file.as_mut() {
-                f.hunks.push(h);
-            }
-        }
-    };
-
-    let flush_file =
-        |files: &mut Vec<DiffFile>, file: &mut Option<PartialFile>, hunk: &mut Option<DiffHunk>| {
-            flush_hunk(file, hunk);
-            if let Some(pf) = file.take() {
-                files.push(pf.finalize());
-            }
-        };
-
-    for line in content.lines() {
-        if line.starts_with("diff --git") {
-            flush_file(&mut files, &mut current_file, &mut current_hunk);
-            current_file = Some(PartialFile::with_diff_header(line));
-            continue;
-        }
-
-        if line.starts_with("@@") {
-            if current_file.is_none() {
-                current_file = Some(PartialFile::default());
-            }
-            flush_hunk(&mut current_file, &mut current_hunk);
-            current_hunk = Some(DiffHunk {
-                header: line.to_string(),
-                lines: Vec::new(),
-            });
-            continue;
-        }
-
-        if let Some(hunk) = current_hunk.as_mut() {
-            hunk.lines.push(line.to_string());
-            continue;
-        }
-
-        if current_file.is_none() {
-            current_file = Some(PartialFile::default());
-        }
-
-        if let Some(file) = current_file.as_mut() {
-            if line.starts_with("--- ") {
-                file.old_path = extract_path_after_prefix(line);
-            }
-            if line.starts_with("+++ ") {
-                file.new_path = extract_path_after_prefix(line);
-            }
-            file.header.push(line.to_string());
-        }
-    }
-
-    flush_file(&mut files, &mut current_file, &mut current_hunk);
-
-    Ok(files)
-}
-
-fn extract_path_after_prefix(line: &str) -> Option<String> {
-    line.split_whitespace().nth(1).map(clean_diff_path)
-}
-
-fn clean_diff_path(raw: &str) -> String {
-    let trimmed = raw.trim_matches('"');
-    let without_prefix = trimmed
-        .strip_prefix("a/")
-        .or_else(|| trimmed.strip_prefix("b/"))
-        .unwrap_or(trimmed);
-    without_prefix.to_string()
-}
-
-fn extract_from_diff_header(line: &str) -> Option<String> {
-    let mut parts = line.split_whitespace();
-    // Expect format: diff --git a/path b/path
-    let first = parts.find(|part| part.starts_with('a'))?;
-    let second = parts.next();
-    second.or(Some(first)).map(clean_diff_path)
-}
-
 fn centered_rect(
     percent_x: u16,
     percent_y: u16,
@@ -1,4 +1,148 @@
 //# -----------------------------
 //# crates/tui/src/lib.rs
 //# -----------------------------
-// Minimal placeholder TUI; later: streaming events
+// Unified-diff parsing, shared between the TUI's diff viewer and anything
+// else that needs to walk a patch file-by-file/hunk-by-hunk (e.g.
+// `devit apply --interactive`).
+
+/// One file section of a unified diff.
+#[derive(Debug, Clone)]
+pub struct DiffFile {
+    pub display_name: String,
+    pub header: Vec<String>,
+    pub hunks: Vec<DiffHunk>,
+}
+
+/// One `@@ ... @@` hunk and its body lines.
+#[derive(Debug, Clone)]
+pub struct DiffHunk {
+    pub header: String,
+    pub lines: Vec<String>,
+}
+
+pub fn parse_unified_diff(content: &str) -> Result<Vec<DiffFile>, String> {
+    #[derive(Default)]
+    struct PartialFile {
+        header: Vec<String>,
+        hunks: Vec<DiffHunk>,
+        old_path: Option<String>,
+        new_path: Option<String>,
+        diff_header: Option<String>,
+    }
+
+    impl PartialFile {
+        fn with_diff_header(line: &str) -> Self {
+            PartialFile {
+                diff_header: Some(line.to_string()),
+                header: vec![line.to_string()],
+                ..Default::default()
+            }
+        }
+
+        fn finalize(self) -> DiffFile {
+            let display = self
+                .new_path
+                .as_ref()
+                .or(self.old_path.as_ref())
+                .cloned()
+                .or_else(|| {
+                    self.diff_header
+                        .as_ref()
+                        .and_then(|h| extract_from_diff_header(h))
+                })
+                .unwrap_or_else(|| "(unknown)".to_string());
+            DiffFile {
+                display_name: clean_diff_path(&display),
+                header: self.header,
+                hunks: self.hunks,
+            }
+        }
+    }
+
+    let mut files: Vec<DiffFile> = Vec::new();
+    let mut current_file: Option<PartialFile> = None;
+    let mut current_hunk: Option<DiffHunk> = None;
+
+    let flush_hunk = |file: &mut Option<PartialFile>, hunk: &mut Option<DiffHunk>| {
+        if let Some(h) = hunk.take() {
+            if file.is_none() {
+                *file = Some(PartialFile::default());
+            }
+            if let Some(f) = file.as_mut() {
+                f.hunks.push(h);
+            }
+        }
+    };
+
+    let flush_file =
+        |files: &mut Vec<DiffFile>, file: &mut Option<PartialFile>, hunk: &mut Option<DiffHunk>| {
+            flush_hunk(file, hunk);
+            if let Some(pf) = file.take() {
+                files.push(pf.finalize());
+            }
+        };
+
+    for line in content.lines() {
+        if line.starts_with("diff --git") {
+            flush_file(&mut files, &mut current_file, &mut current_hunk);
+            current_file = Some(PartialFile::with_diff_header(line));
+            continue;
+        }
+
+        if line.starts_with("@@") {
+            if current_file.is_none() {
+                current_file = Some(PartialFile::default());
+            }
+            flush_hunk(&mut current_file, &mut current_hunk);
+            current_hunk = Some(DiffHunk {
+                header: line.to_string(),
+                lines: Vec::new(),
+            });
+            continue;
+        }
+
+        if let Some(hunk) = current_hunk.as_mut() {
+            hunk.lines.push(line.to_string());
+            continue;
+        }
+
+        if current_file.is_none() {
+            current_file = Some(PartialFile::default());
+        }
+
+        if let Some(file) = current_file.as_mut() {
+            if line.starts_with("--- ") {
+                file.old_path = extract_path_after_prefix(line);
+            }
+            if line.starts_with("+++ ") {
+                file.new_path = extract_path_after_prefix(line);
+            }
+            file.header.push(line.to_string());
+        }
+    }
+
+    flush_file(&mut files, &mut current_file, &mut current_hunk);
+
+    Ok(files)
+}
+
+fn extract_path_after_prefix(line: &str) -> Option<String> {
+    line.split_whitespace().nth(1).map(clean_diff_path)
+}
+
+fn clean_diff_path(raw: &str) -> String {
+    let trimmed = raw.trim_matches('"');
+    let without_prefix = trimmed
+        .strip_prefix("a/")
+        .or_else(|| trimmed.strip_prefix("b/"))
+        .unwrap_or(trimmed);
+    without_prefix.to_string()
+}
+
+fn extract_from_diff_header(line: &str) -> Option<String> {
+    let mut parts = line.split_whitespace();
+    // Expect format: diff --git a/path b/path
+    let first = parts.find(|part| part.starts_with('a'))?;
+    let second = parts.next();
+    second.or(Some(first)).map(clean_diff_path)
+}